@@ -7,8 +7,151 @@
 /// - `iter_mut` ... implements [`ParametrizedIterMut`]
 /// - `into_iter` ... implements [`ParametrizedIntoIter`]
 /// - `map` ... implements [`ParametrizedMap`]
+/// - `try_map` ... implements [`ParametrizedTryMap`]
+/// - `map_ref` ... implements [`ParametrizedMapRef`]
+/// - `zip` ... implements [`ParametrizedZip`]
+/// - `unzip` ... implements [`ParametrizedUnzip`]
+/// - `retain` ... implements [`ParametrizedRetain`]
+/// - `filter_map` ... implements [`ParametrizedFilterMap`]
+/// - `update` ... implements [`ParametrizedUpdate`]
+/// - `path` ... implements [`ParametrizedPath`]
+/// - `diff` ... implements [`ParametrizedDiff`]
+/// - `bimap` ... implements [`ParametrizedBiMap`] for the pair of parameters
+///   given by `bimap = [<PARAM0>, <PARAM1>]`, which implies `map` for both
+/// - `union` ... implements [`ParametrizedUnion`] for the pair of parameters
+///   given by `union = [<PARAM0>, <PARAM1>]`
+/// - `union_mut` ... implements [`ParametrizedUnionMut`] likewise
 ///
-/// You can specify `PARAM` index by using `<arg> = [<PARAM>, ..]` syntax.
+/// You can specify `PARAM` index by using `<arg> = [<PARAM>, ..]` syntax. A type
+/// parameter can also be given by its name, as in `map = [Operand, Reg]`.
+/// Indices count type parameters only, both in the attribute and in the
+/// implemented traits, so that they agree with the traits of fields such as
+/// `Inner<'a, T>`. Without any index, the first type parameter is selected.
+/// Earlier versions counted lifetime and const parameters too, so a numeric
+/// index which selected another type parameter then is rejected. Pass
+/// `legacy_index` to keep counting all generic parameters, both in the
+/// attribute and in the implemented traits, as before; such types cannot be
+/// nested in other annotated types where the two counts differ.
+///
+/// ```compile_fail
+/// # use parametrized::*;
+/// // `1` selected `K` in earlier versions, but selects `V` now.
+/// #[parametrized(default = 1)]
+/// struct Entry<'a, K, V>(&'a K, Vec<V>);
+/// ```
+/// Every type parameter gets an associated const holding its `PARAM` index,
+/// named after the parameter in upper snake case (`PARAM_OPERAND` for
+/// `Operand`). Pass `no_param_consts` to leave them out, such as where the
+/// type defines consts of the same names.
+///
+/// ```
+/// # use parametrized::*;
+/// #[parametrized(default = Operand, map = Operand)]
+/// struct Instruction<'a, Reg, Operand> {
+///     name: &'a str,
+///     dest: Reg,
+///     operands: Vec<Operand>,
+/// }
+/// let inst = Instruction { name: "add", dest: 0u8, operands: vec![1usize, 2] };
+/// const PARAM: usize = Instruction::<u8, usize>::PARAM_OPERAND;
+/// assert_eq!(Parametrized::<PARAM>::param_len(&inst), 2);
+/// ```
+///
+/// Fields and variants accept a `#[param(..)]` helper attribute:
+///
+/// - `#[param(skip)]` ... the field is not traversed. [`ParametrizedMap`] moves
+///   it as is, so its type must not mention the parameter; use `with` to map
+///   such a field.
+/// - `#[param(opaque)]` ... the field is treated as containing no parameter.
+///   [`ParametrizedMap`] moves it as is, so its type must not mention the
+///   parameter either.
+/// - `#[param(with = <path>)]` ... the field is traversed by the functions
+///   `<path>::len(&F) -> usize`, `<path>::iter(&F)`, `<path>::iter_mut(&mut F)`,
+///   `<path>::into_iter(F)` returning iterators,
+///   `<path>::map(F, impl FnMut(T) -> M)`,
+///   `<path>::try_map(F, impl FnMut(T) -> Result<M, E>) -> Result<_, E>`,
+///   `<path>::map_ref(&'a F, impl FnMut(&'a T) -> M)`,
+///   `<path>::try_zip_with(F, G, impl FnMut(T, U) -> Result<M, E>) -> Result<_, E>`
+///   with `E: From<ShapeMismatch>`, `<path>::unzip(F) -> (G, H)`,
+///   `<path>::retain(&mut F, impl FnMut(&T) -> bool)`,
+///   `<path>::filter_map(F, impl FnMut(T) -> Option<M>)`,
+///   `<path>::update(&mut F, impl FnMut(T) -> T)`,
+///   `<path>::paths(&F)` returning an iterable of [`ParamPath`],
+///   `<path>::get_path_prefix(&'a F, &'p [PathSegment]) -> Option<(&'a T, &'p [PathSegment])>`,
+///   `<path>::pair(&'a F, &'a F) -> Vec<ParamDiff<&'a T>>`,
+///   `<path>::bimap(F, impl FnMut(T) -> M, impl FnMut(U) -> N)`,
+///   `<path>::iter_either(&F)` and `<path>::iter_mut_either(&mut F)` returning
+///   iterators of [`Either`],
+///   as needed by the implemented traits.
+///   Such a field contributes `0` to `MIN_LEN` and `None` to `MAX_LEN`. Not
+///   allowed on variants.
+///
+/// `#[cfg(..)]` on fields and on variants is honoured. The iterator of such a
+/// field or variant is empty when it is compiled out, and its type is named
+/// either way, so the types of such fields must exist regardless of the
+/// predicate. The traits are implemented once for every combination of the
+/// predicates on unnamed fields, whose indices shift with them, so at most
+/// four distinct predicates are allowed there; name the fields to use more.
+///
+/// ```compile_fail
+/// # use parametrized::*;
+/// #[parametrized(default)]
+/// struct Flags<T>(
+///     #[cfg(feature = "a")] T,
+///     #[cfg(feature = "b")] T,
+///     #[cfg(feature = "c")] T,
+///     #[cfg(feature = "d")] T,
+///     #[cfg(feature = "e")] T,
+/// );
+/// ```
+///
+/// Fields referring back to the annotated type (such as `Box<Self>` or
+/// `Vec<Expr<T>>`, but not `other::Expr<T>`) are detected by their paths, and
+/// the iterators of such recursive types are boxed. They contribute `0` to
+/// `MIN_LEN` and `None` to `MAX_LEN`. Other types held in a `Box` (such as
+/// `Box<Stmt<T>>`) are taken to refer back too, as mutually recursive types
+/// usually do. Where mutually recursive types refer to each other only
+/// through other containers, name the other types with
+/// `recursive = [<Type>, ..]` on one of them, by the paths the fields use.
+/// The boxed iterators nest once per level of recursion, so very deep values
+/// (such as a chain of 100,000 `Box<Self>`) can overflow the stack when they
+/// are iterated, as when they are dropped.
+///
+/// Pass `boxed` to collect the values of [`Parametrized`],
+/// [`ParametrizedIterMut`] and [`ParametrizedIntoIter`] into a `Vec` and
+/// iterate it, rather than naming the structural types of the iterators, which
+/// keeps large enums quick to compile at the cost of an allocation and a pass
+/// over the values per iterator. The iterators yield the same values, and are
+/// [`DoubleEndedIterator`], [`ExactSizeIterator`],
+/// [`FusedIterator`](core::iter::FusedIterator) regardless of the fields, with
+/// exact size hints, and [`Clone`] where their items are. The `boxed` feature of this
+/// crate does so for every annotated type.
+///
+/// [`Parametrized::param_for_each()`], [`Parametrized::param_try_for_each()`],
+/// [`ParametrizedIterMut::param_for_each_mut()`] and
+/// [`ParametrizedIntoIter::param_fold()`] are implemented as nested loops over
+/// the fields, without building the iterators, so they stay fast for deeply
+/// nested fields and where the values are collected by `boxed`.
+///
+/// Pass `named_iter` to name the iterator types after the item, rather than
+/// leaving them to anonymous types. The iterators of [`Parametrized`],
+/// [`ParametrizedIterMut`] and [`ParametrizedIntoIter`] for `Inst<R>` are then
+/// `InstParamIter<'a, R>`, `InstParamIterMut<'a, R>` and `InstParamIntoIter<R>`,
+/// with the visibility of the item. For an enum they are enums with a variant
+/// wrapping the iterator of each variant of the item, which enums with several
+/// variants get anyway, only hidden. When the item has several type parameters,
+/// the name of the parameter replaces `Param`, as in `InstRegIter<'a, Reg, Imm>`.
+/// Where the values are collected by `boxed`, the named types are structs
+/// wrapping the iterators of the `Vec`.
+///
+/// The iterators of structs and enums are [`DoubleEndedIterator`],
+/// [`ExactSizeIterator`], [`FusedIterator`](core::iter::FusedIterator) and
+/// [`Clone`] whenever the iterators of all of their fields are, so generic code
+/// can require them with bounds such as
+/// `Parametrized<0, Iter<'a>: DoubleEndedIterator>`. Values in collections of
+/// variable length nested in other collections (such as `Vec<Vec<T>>`) are
+/// counted only one by one, so such fields are not [`ExactSizeIterator`], while
+/// those of fixed length (such as `Vec<[T; 2]>` or `Vec<(T, T)>`) are.
 pub use parametrized_macro::parametrized;
 use std::hash::Hash;
 
@@ -24,6 +167,10 @@ use std::hash::Hash;
 /// assert_eq!(<S<usize>>::MAX_LEN, None);
 /// assert_eq!(s.param_len(), 3);
 /// assert_eq!(s.param_iter().collect::<Vec<_>>(), vec![&1usize, &2, &3]);
+/// assert_eq!(s.param_get(2), Some(&3usize));
+/// let mut sum = 0;
+/// s.param_for_each(|i| sum += i);
+/// assert_eq!(sum, 6);
 /// ```
 pub trait Parametrized<const PARAM: usize> {
     type Item: ?Sized;
@@ -38,6 +185,46 @@ pub trait Parametrized<const PARAM: usize> {
     fn param_iter<'a>(&'a self) -> Self::Iter<'a>
     where
         Self::Item: 'a;
+
+    /// Returns the `n`-th value of [`Parametrized::param_iter()`]. Implementations
+    /// skip whole fields and sub-containers by their lengths, and index directly
+    /// into slices, rather than walking every preceding value.
+    fn param_get(&self, n: usize) -> Option<&Self::Item> {
+        self.param_iter().nth(n)
+    }
+
+    /// Calls `f` on every value, in the order of [`Parametrized::param_iter()`].
+    /// Implementations loop over the fields and containers directly instead of
+    /// driving the nested iterators.
+    fn param_for_each<'a>(&'a self, f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        self.param_iter().for_each(f)
+    }
+
+    /// Like [`param_for_each()`](Self::param_for_each), but stops at the first
+    /// [`Break`](core::ops::ControlFlow::Break) returned by `f`.
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        self.param_iter().try_for_each(f)
+    }
+
+    /// Borrows every value, as in `Option::as_ref()`. See [`ParametrizedMapRef`].
+    fn param_as_ref<'a>(
+        &'a self,
+    ) -> <Self as ParametrizedMapRef<PARAM, &'a <Self as Parametrized<PARAM>>::Item>>::Mapped
+    where
+        Self: ParametrizedMapRef<PARAM, &'a <Self as Parametrized<PARAM>>::Item>,
+        Self::Item: 'a,
+    {
+        ParametrizedMapRef::<PARAM, &'a Self::Item>::param_map_ref(self, |item| item)
+    }
 }
 
 /// Provide [`ParametrizedIterMut::param_iter_mut()`] method to return mutable
@@ -66,6 +253,22 @@ pub trait ParametrizedIterMut<const PARAM: usize>: Parametrized<PARAM> {
     fn param_iter_mut<'a>(&'a mut self) -> Self::IterMut<'a>
     where
         Self::Item: 'a;
+
+    /// Returns the `n`-th value of [`ParametrizedIterMut::param_iter_mut()`],
+    /// skipping preceding values like [`Parametrized::param_get()`].
+    fn param_get_mut(&mut self, n: usize) -> Option<&mut Self::Item> {
+        self.param_iter_mut().nth(n)
+    }
+
+    /// Calls `f` on every value, in the order of
+    /// [`ParametrizedIterMut::param_iter_mut()`], looping directly like
+    /// [`Parametrized::param_for_each()`].
+    fn param_for_each_mut<'a>(&'a mut self, f: impl FnMut(&'a mut Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        self.param_iter_mut().for_each(f)
+    }
 }
 
 /// Provide [`ParametrizedIntoIter::param_into_iter()`] method to return iterator that
@@ -89,6 +292,91 @@ pub trait ParametrizedIntoIter<const PARAM: usize>: Parametrized<PARAM> + Sized
     fn param_into_iter(self) -> Self::IntoIter
     where
         Self::Item: Sized;
+
+    /// Folds every value into an accumulator, in the order of
+    /// [`ParametrizedIntoIter::param_into_iter()`], looping directly like
+    /// [`Parametrized::param_for_each()`].
+    fn param_fold<B>(self, init: B, f: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        self.param_into_iter().fold(init, f)
+    }
+
+    /// Pairs every value with the value at the same position in `other`. See
+    /// [`ParametrizedZip`].
+    #[allow(clippy::type_complexity)]
+    fn param_zip<O>(
+        self,
+        other: O,
+    ) -> Result<
+        <Self as ParametrizedMap<
+            PARAM,
+            (<Self as Parametrized<PARAM>>::Item, <O as Parametrized<PARAM>>::Item),
+        >>::Mapped,
+        ShapeMismatch,
+    >
+    where
+        O: ParametrizedIntoIter<PARAM>,
+        <Self as Parametrized<PARAM>>::Item: Sized,
+        <O as Parametrized<PARAM>>::Item: Sized,
+        Self: ParametrizedZip<
+            PARAM,
+            O,
+            (<Self as Parametrized<PARAM>>::Item, <O as Parametrized<PARAM>>::Item),
+        >,
+    {
+        self.param_zip_with(other, |a, b| (a, b))
+    }
+
+    /// Separates the values from their shape, which is `self` with the values
+    /// replaced by `()`. [`ParametrizedMap::param_fill()`] puts them back.
+    ///
+    /// The shapes of containers ordered or hashed by the values, such as sets
+    /// and the keys of maps, collapse into one `()`, so that filling them fails
+    /// unless they held at most one value; use
+    /// [`param_split_dedup()`](Self::param_split_dedup) for them. The shapes of
+    /// `HashMap`s do not keep the order of their values either.
+    #[allow(clippy::type_complexity)]
+    fn param_split(
+        self,
+    ) -> (
+        <Self as ParametrizedMap<PARAM, ()>>::Mapped,
+        Vec<<Self as Parametrized<PARAM>>::Item>,
+    )
+    where
+        <Self as Parametrized<PARAM>>::Item: Sized,
+        Self: ParametrizedMap<PARAM, ()>,
+    {
+        let mut items = Vec::new();
+        let shape = ParametrizedMap::<PARAM, ()>::param_map(self, |item| items.push(item));
+        (shape, items)
+    }
+
+    /// Like [`param_split()`](Self::param_split), but keeps each distinct value
+    /// once, leaving its index in the returned values in the shape. This works
+    /// for sets and the keys of maps too, whose values are distinct, and
+    /// `shape.param_map(|i| items[i].clone())` puts them back.
+    #[allow(clippy::type_complexity)]
+    fn param_split_dedup(
+        self,
+    ) -> (
+        <Self as ParametrizedMap<PARAM, usize>>::Mapped,
+        Vec<<Self as Parametrized<PARAM>>::Item>,
+    )
+    where
+        <Self as Parametrized<PARAM>>::Item: Sized + Hash + Eq,
+        Self: ParametrizedMap<PARAM, usize>,
+    {
+        let mut indices = std::collections::HashMap::new();
+        let shape = ParametrizedMap::<PARAM, usize>::param_map(self, |item| {
+            let len = indices.len();
+            *indices.entry(item).or_insert(len)
+        });
+        let mut items = indices.into_iter().collect::<Vec<_>>();
+        items.sort_unstable_by_key(|(_, index)| *index);
+        (shape, items.into_iter().map(|(item, _)| item).collect())
+    }
 }
 
 /// Provide [`ParametrizedMap::param_map()`] method to map values specified by
@@ -97,6 +385,7 @@ pub trait ParametrizedIntoIter<const PARAM: usize>: Parametrized<PARAM> + Sized
 /// ```
 /// # use parametrized::*;
 /// #[parametrized(map)]
+/// #[derive(Debug, PartialEq)]
 /// struct S<T>(Vec<T>);
 ///
 /// let s = S(vec![1usize, 2, 3]).param_map(|s| s.to_string());
@@ -105,142 +394,1547 @@ pub trait ParametrizedIntoIter<const PARAM: usize>: Parametrized<PARAM> + Sized
 ///     &"2".to_string(),
 ///     &"3".to_string(),
 /// ]);
+///
+/// let (next, s) = S(vec!['a', 'b']).param_map_accum(10, |n, c| (n + 1, (n, c)));
+/// assert_eq!(next, 12);
+/// assert_eq!(s.param_into_iter().collect::<Vec<_>>(), vec![(10, 'a'), (11, 'b')]);
+/// let s = S(vec!['a', 'b']).param_map_indexed(|i, c| format!("{c}{i}"));
+/// assert_eq!(s.0, vec!["a0".to_string(), "b1".to_string()]);
+///
+/// let (shape, items) = S(vec!["a", "b", "a"]).param_split();
+/// assert_eq!(items, vec!["a", "b", "a"]);
+/// let s = shape.param_fill([1, 2, 3]).unwrap();
+/// assert_eq!(s, S(vec![1, 2, 3]));
+/// let (shape, items) = S(vec!["a", "b", "a"]).param_split_dedup();
+/// assert_eq!((shape, items), (S(vec![0, 1, 0]), vec!["a", "b"]));
+/// let shape = S(vec![(), ()]);
+/// assert_eq!(shape.param_fill([1]), Err(FillError::TooFew { expected: 2, found: 1 }));
 /// ```
 pub trait ParametrizedMap<const PARAM: usize, K>: ParametrizedIntoIter<PARAM> + Sized {
     type Mapped: ParametrizedIntoIter<PARAM, Item = K>;
     fn param_map(self, f: impl FnMut(Self::Item) -> K) -> Self::Mapped
     where
         Self::Item: Sized;
-}
 
-/// Like [`std::iter::Flatten`], but no where clauses in type definitions.
-#[doc(hidden)]
-pub struct Flatten<I, Iter> {
-    slot: I,
-    iter: Option<Iter>,
-}
-
-impl<I, OuterItem> Flatten<I, OuterItem> {
-    pub fn new(slot: I) -> Self {
-        Flatten { slot, iter: None }
+    /// Maps values while threading the state `init` through them in the order of
+    /// [`param_iter()`](Parametrized::param_iter), returning the final state
+    /// together with the mapped value.
+    fn param_map_accum<A>(
+        self,
+        init: A,
+        mut f: impl FnMut(A, Self::Item) -> (A, K),
+    ) -> (A, Self::Mapped)
+    where
+        Self::Item: Sized,
+    {
+        // The state is moved out only while `f` runs.
+        let mut state = Some(init);
+        let mapped = self.param_map(|item| {
+            let (next, out) = f(state.take().unwrap(), item);
+            state = Some(next);
+            out
+        });
+        (state.unwrap(), mapped)
     }
-}
 
-impl<I, Iter, T> Iterator for Flatten<I, Iter>
-where
-    I: Iterator,
-    <I as Iterator>::Item: IntoIterator<IntoIter = Iter, Item = T>,
-    Iter: Iterator<Item = T>,
-{
-    type Item = T;
+    /// Maps values with their indices in the order of
+    /// [`param_iter()`](Parametrized::param_iter).
+    fn param_map_indexed(self, mut f: impl FnMut(usize, Self::Item) -> K) -> Self::Mapped
+    where
+        Self::Item: Sized,
+    {
+        let mut index = 0;
+        self.param_map(|item| {
+            index += 1;
+            f(index - 1, item)
+        })
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if let Some(iter) = &mut self.iter {
-            if let Some(item) = iter.next() {
-                return Some(item);
+    /// Replaces the values with `items` in [`Parametrized::param_iter()`]
+    /// order, failing unless there are exactly as many items as values. The
+    /// shape returned by [`ParametrizedIntoIter::param_split()`] is refilled with
+    /// its values.
+    fn param_fill(self, items: impl IntoIterator<Item = K>) -> Result<Self::Mapped, FillError>
+    where
+        Self::Item: Sized,
+    {
+        let expected = self.param_len();
+        let mut items = items.into_iter();
+        let taken = items.by_ref().take(expected).collect::<Vec<_>>();
+        if taken.len() < expected {
+            return Err(FillError::TooFew {
+                expected,
+                found: taken.len(),
+            });
+        }
+        match items.count() {
+            0 => {
+                let mut taken = taken.into_iter();
+                Ok(self.param_map(|_| taken.next().expect("`param_len()` counts the values")))
             }
+            rest => Err(FillError::TooMany {
+                expected,
+                found: expected + rest,
+            }),
         }
-        self.iter = Some(self.slot.next()?.into_iter());
-        self.next()
     }
 }
 
-impl<'a, const PARAM: usize, T> Parametrized<PARAM> for &'a T
-where
-    T: Parametrized<PARAM>,
-{
-    type Item = <T as Parametrized<PARAM>>::Item;
-    const MIN_LEN: usize = <T as Parametrized<PARAM>>::MIN_LEN;
-    const MAX_LEN: Option<usize> = <T as Parametrized<PARAM>>::MAX_LEN;
-    fn param_len(&self) -> usize {
-        <T as Parametrized<PARAM>>::param_len(self)
+/// Provide [`ParametrizedTryMap::try_param_map()`] method to map values specified
+/// by `PARAM`-th type parameter of given type with a fallible function. For
+/// user-defined types, this trait is implemented by [`parametrized`] macro with
+/// `try_map` argument, which implies `map`.
+///
+/// ```
+/// # use parametrized::*;
+/// #[parametrized(try_map)]
+/// #[derive(Debug, PartialEq)]
+/// struct S<T>(Vec<T>, Option<T>);
+///
+/// let s = S(vec!["1", "2"], Some("3"));
+/// let t = s.try_param_map(|v| v.parse::<usize>()).unwrap();
+/// assert_eq!(t.param_iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+/// let s = S(vec!["1", "x"], Some("y"));
+/// assert_eq!(s.try_param_map_opt(|v| v.parse::<usize>().ok()), None);
+/// let s = S(vec!["1", "x"], Some("y"));
+/// let errs = s.try_param_map_all(|v| v.parse::<usize>().map_err(|_| v)).unwrap_err();
+/// assert_eq!(errs, vec![(1, "x"), (2, "y")]);
+/// let s = S(vec![Ok(1), Err("x")], None);
+/// assert_eq!(s.param_transpose().err(), Some("x"));
+/// ```
+pub trait ParametrizedTryMap<const PARAM: usize, K>: ParametrizedMap<PARAM, K> {
+    /// Maps every value with `f`, returning the first error `f` returns.
+    fn try_param_map<E>(
+        self,
+        f: impl FnMut(Self::Item) -> Result<K, E>,
+    ) -> Result<Self::Mapped, E>
+    where
+        Self::Item: Sized;
+
+    /// Maps every value with `f`, or returns `None` at the first `None`.
+    fn try_param_map_opt(self, mut f: impl FnMut(Self::Item) -> Option<K>) -> Option<Self::Mapped>
+    where
+        Self::Item: Sized,
+    {
+        self.try_param_map(|item| f(item).ok_or(())).ok()
     }
-    type Iter<'b> = <T as Parametrized<PARAM>>::Iter<'b>
+
+    /// Maps every value with `f` without stopping at errors. If any, the errors
+    /// are returned with the positions of their values in
+    /// [`Parametrized::param_iter()`] order.
+    #[allow(clippy::type_complexity)]
+    fn try_param_map_all<E>(
+        self,
+        mut f: impl FnMut(Self::Item) -> Result<K, E>,
+    ) -> Result<<Self as ParametrizedMap<PARAM, K>>::Mapped, Vec<(usize, E)>>
     where
-        (Self, Self::Item): 'b;
+        Self::Item: Sized,
+        Self: ParametrizedMap<PARAM, Option<K>>,
+        <Self as ParametrizedMap<PARAM, Option<K>>>::Mapped: ParametrizedTryMap<
+            PARAM,
+            K,
+            Item = Option<K>,
+            Mapped = <Self as ParametrizedMap<PARAM, K>>::Mapped,
+        >,
+    {
+        let mut errors = Vec::new();
+        let mut index = 0;
+        let mapped = ParametrizedMap::<PARAM, Option<K>>::param_map(self, |item| {
+            index += 1;
+            f(item).map_err(|e| errors.push((index - 1, e))).ok()
+        });
+        match mapped.try_param_map_opt(|item| item) {
+            Some(mapped) if errors.is_empty() => Ok(mapped),
+            _ => Err(errors),
+        }
+    }
 
-    fn param_iter<'b>(&'b self) -> Self::Iter<'b>
+    /// Turns a structure of `Result`s into a `Result` of the structure, with the
+    /// first error.
+    fn param_transpose<E>(self) -> Result<Self::Mapped, E>
     where
-        Self::Item: 'b,
+        Self: Parametrized<PARAM, Item = Result<K, E>>,
     {
-        <T as Parametrized<PARAM>>::param_iter(self)
+        self.try_param_map(|item| item)
     }
 }
 
-impl<'a, const PARAM: usize, T> Parametrized<PARAM> for &'a mut T
-where
-    T: Parametrized<PARAM>,
+/// Provide [`ParametrizedMapRef::param_map_ref()`] method to map values specified
+/// by `PARAM`-th type parameter of given type without consuming it. The values
+/// which are not mapped are cloned. For user-defined types, this trait is
+/// implemented by [`parametrized`] macro with `map_ref` argument, which requires
+/// the other type parameters to be [`Clone`].
+///
+/// ```
+/// # use parametrized::*;
+/// #[parametrized(map_ref)]
+/// struct S<T, U>(Vec<T>, U);
+///
+/// let s = S(vec![1usize, 2, 3], "s".to_string());
+/// let t = s.param_map_ref(|v| v.to_string());
+/// assert_eq!(t.param_iter().collect::<Vec<_>>(), vec!["1", "2", "3"]);
+/// assert_eq!(t.1, "s");
+/// let r: S<&usize, String> = s.param_as_ref();
+/// assert_eq!(r.0, vec![&1, &2, &3]);
+/// ```
+pub trait ParametrizedMapRef<const PARAM: usize, K>: Parametrized<PARAM> {
+    type Mapped: Parametrized<PARAM, Item = K>;
+    fn param_map_ref<'a>(&'a self, f: impl FnMut(&'a Self::Item) -> K) -> Self::Mapped
+    where
+        Self::Item: 'a;
+}
+
+/// Provide [`ParametrizedZip::param_zip_with()`] method to combine the values
+/// specified by `PARAM`-th type parameter of two values of the same shape, such
+/// as `S<T>` and `S<U>` into `S<K>`. The other fields are taken from `self`. For
+/// user-defined types, this trait is implemented by [`parametrized`] macro with
+/// `zip` argument, which implies `map`.
+///
+/// Containers without a defined order, such as `HashSet`, do not implement this
+/// trait. `HashMap`s are zipped by their keys.
+///
+/// ```
+/// # use parametrized::*;
+/// #[parametrized(zip)]
+/// #[derive(Debug, PartialEq)]
+/// enum Inst<R> {
+///     Mov(R, R),
+///     Call(String, Vec<R>),
+/// }
+///
+/// let before = Inst::Call("f".to_string(), vec!["a", "b"]);
+/// let after = Inst::Call("g".to_string(), vec![1, 2]);
+/// let zipped = before.param_zip(after).unwrap();
+/// assert_eq!(zipped, Inst::Call("f".to_string(), vec![("a", 1), ("b", 2)]));
+/// assert_eq!(
+///     Inst::Mov(1, 2).param_zip_with(Inst::Call("f".to_string(), vec![3]), |a, b| a + b),
+///     Err(ShapeMismatch::Variant { expected: "Mov", found: "Call" }),
+/// );
+/// assert_eq!(
+///     Inst::Call("f".to_string(), vec![1, 2]).param_zip(Inst::Call("f".to_string(), vec![3])),
+///     Err(ShapeMismatch::Length { expected: 2, found: 1 }),
+/// );
+/// ```
+pub trait ParametrizedZip<const PARAM: usize, O: ParametrizedIntoIter<PARAM>, K>:
+    ParametrizedMap<PARAM, K>
 {
-    type Item = <T as Parametrized<PARAM>>::Item;
-    const MIN_LEN: usize = <T as Parametrized<PARAM>>::MIN_LEN;
-    const MAX_LEN: Option<usize> = <T as Parametrized<PARAM>>::MAX_LEN;
-    fn param_len(&self) -> usize {
-        <T as Parametrized<PARAM>>::param_len(self)
-    }
-    type Iter<'b> = <T as Parametrized<PARAM>>::Iter<'b>
+    /// Combines every pair of values with `f`, returning the first error `f`
+    /// returns or the first mismatch of the shapes.
+    fn try_param_zip_with<E: From<ShapeMismatch>>(
+        self,
+        other: O,
+        f: impl FnMut(Self::Item, O::Item) -> Result<K, E>,
+    ) -> Result<Self::Mapped, E>
     where
-        (Self, Self::Item): 'b;
+        Self::Item: Sized,
+        O::Item: Sized;
 
-    fn param_iter<'b>(&'b self) -> Self::Iter<'b>
+    /// Combines every pair of values with `f`.
+    fn param_zip_with(
+        self,
+        other: O,
+        mut f: impl FnMut(Self::Item, O::Item) -> K,
+    ) -> Result<Self::Mapped, ShapeMismatch>
     where
-        Self::Item: 'b,
+        Self::Item: Sized,
+        O::Item: Sized,
     {
-        <T as Parametrized<PARAM>>::param_iter(self)
+        self.try_param_zip_with(other, |a, b| Ok(f(a, b)))
     }
 }
 
-impl<'a, const PARAM: usize, T> ParametrizedIterMut<PARAM> for &'a mut T
+/// The error of [`ParametrizedZip`], telling where the shapes of two values
+/// differ first.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShapeMismatch {
+    /// The values are different variants.
+    Variant {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// The containers have different lengths.
+    Length { expected: usize, found: usize },
+    /// The maps have different keys.
+    Keys,
+    /// The expected map or set has an entry which the found one lacks.
+    MissingEntry,
+    /// The found map or set has an entry which the expected one lacks.
+    ExtraEntry,
+}
+
+impl core::fmt::Display for ShapeMismatch {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Variant { expected, found } => {
+                write!(f, "expected variant `{}`, found `{}`", expected, found)
+            }
+            Self::Length { expected, found } => {
+                write!(f, "expected length {}, found {}", expected, found)
+            }
+            Self::Keys => write!(f, "the maps have different keys"),
+            Self::MissingEntry => write!(f, "expected an entry, found none"),
+            Self::ExtraEntry => write!(f, "expected no entry, found one"),
+        }
+    }
+}
+
+impl std::error::Error for ShapeMismatch {}
+
+/// The error of [`ParametrizedMap::param_fill()`], telling how many items
+/// were given for how many values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FillError {
+    /// The items ran out.
+    TooFew { expected: usize, found: usize },
+    /// Some items were left over.
+    TooMany { expected: usize, found: usize },
+}
+
+impl core::fmt::Display for FillError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::TooFew { expected, found } => {
+                write!(f, "expected {} items, found only {}", expected, found)
+            }
+            Self::TooMany { expected, found } => {
+                write!(f, "expected {} items, found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for FillError {}
+
+/// A value of either of two types, yielded by [`ParametrizedUnion`]. It is an
+/// iterator when both sides are iterators of the same items.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Either<L, R> {
+    Left(L),
+    Right(R),
+}
+
+impl<T> Either<T, T> {
+    /// Returns the value, whichever side it is on.
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Left(value) | Self::Right(value) => value,
+        }
+    }
+}
+
+impl<L, R> Iterator for Either<L, R>
 where
-    T: ParametrizedIterMut<PARAM>,
+    L: Iterator,
+    R: Iterator<Item = L::Item>,
 {
-    type IterMut<'b> = <T as ParametrizedIterMut<PARAM>>::IterMut<'b>
-    where
-        (Self, Self::Item): 'b;
+    type Item = L::Item;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Left(iter) => iter.next(),
+            Self::Right(iter) => iter.next(),
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self {
+            Self::Left(iter) => iter.size_hint(),
+            Self::Right(iter) => iter.size_hint(),
+        }
+    }
+}
 
-    fn param_iter_mut<'b>(&'b mut self) -> Self::IterMut<'b>
-    where
-        Self::Item: 'b,
-    {
-        <T as ParametrizedIterMut<PARAM>>::param_iter_mut(self)
+impl<L, R> DoubleEndedIterator for Either<L, R>
+where
+    L: DoubleEndedIterator,
+    R: DoubleEndedIterator<Item = L::Item>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Left(iter) => iter.next_back(),
+            Self::Right(iter) => iter.next_back(),
+        }
     }
 }
 
-macro_rules! impl_for_tuple {
-    (@wrap_f $fn:ident[] [] [$($_:expr),*] {$($out:expr),*}) => {($($out,)*)};
-    (@wrap_f $fn:ident[] [$_:ident$(,$params1:ident)*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*}) => {
-        impl_for_tuple!(@wrap_f $fn[] [$($params1),*] [$($t),*] {$($out,)*$rhs})
-    };
-    (@wrap_f $fn:ident[] $param:ident [$($params1:ident),*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*}) => {
-        impl_for_tuple!(@wrap_f $fn[] [$($params1),*] [$($t),*] {$($out,)*($fn($rhs))})
-    };
-    (@wrap_f $fn:ident[$_:ident $(,$params0:ident)*] $param:ident [$($params1:ident),*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*} ) => {
-        impl_for_tuple!(@wrap_f $fn[$($params0),*] $param [$($params1),*] [$($t),*] {$($out,)*$rhs})
-    };
-    (@nth [] [$rhs:expr$(,$_:expr)*]) => { $rhs };
-    (@nth [$_:expr $(,$lhs:expr)*] [$__:expr $(,$rhs:expr)*]) => {
-        impl_for_tuple!(@nth [$($lhs),*] [$($rhs),*])
-    };
-    (@count) => {0usize};
-    (@count $_:ident $(,$t:ident)*) => {
-        impl_for_tuple!(@count $($t),*) + 1
-    };
-    (
-        [$($params0:ident),*] $param:ident [$($params1:ident),*]
-    ) => {
-        impl<$($params0,)* $param $(,$params1)*> Parametrized<{impl_for_tuple!(@count $($params0),*)}>
-        for ($($params0,)* $param, $($params1),*)
-        {
-            type Item = $param;
-            const MIN_LEN: usize = 1;
-            const MAX_LEN: Option<usize> = Some(1);
-            fn param_len(&self) -> usize {
-                1
+impl<L, R> ExactSizeIterator for Either<L, R>
+where
+    L: ExactSizeIterator,
+    R: ExactSizeIterator<Item = L::Item>,
+{
+}
+
+impl<L, R> core::iter::FusedIterator for Either<L, R>
+where
+    L: core::iter::FusedIterator,
+    R: core::iter::FusedIterator<Item = L::Item>,
+{
+}
+
+/// One step of a [`ParamPath`].
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathSegment {
+    /// A variant of an enum, by name.
+    Variant(&'static str),
+    /// A named field.
+    Field(&'static str),
+    /// An unnamed field of a tuple struct or a tuple.
+    TupleField(usize),
+    /// A position in a collection, counted in the order of
+    /// [`Parametrized::param_iter()`].
+    Index(usize),
+    /// An entry of a map or a set, by its position in the order of
+    /// [`Parametrized::param_iter()`], which is looked up without comparing
+    /// the keys.
+    Entry(usize),
+    /// An entry of a map or a set, by the `Debug` representation of its key.
+    /// [`ParametrizedDiff`] names entries so, since it pairs the entries of two
+    /// maps by their keys, but such paths are not looked up.
+    Key(String),
+}
+
+/// Where a value lives, given by [`ParametrizedPath`]. It displays as the field
+/// accesses leading to the value, such as `BinaryOp.dest[2]` for the third
+/// value in the field `dest` of the variant `BinaryOp`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ParamPath(Vec<PathSegment>);
+
+impl ParamPath {
+    /// The path of the value itself.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.0
+    }
+
+    pub fn push(&mut self, segment: PathSegment) {
+        self.0.push(segment)
+    }
+
+    /// Returns the path with `segments` in front.
+    pub fn prefixed(mut self, segments: &[PathSegment]) -> Self {
+        self.0.splice(0..0, segments.iter().cloned());
+        self
+    }
+}
+
+impl From<Vec<PathSegment>> for ParamPath {
+    fn from(segments: Vec<PathSegment>) -> Self {
+        Self(segments)
+    }
+}
+
+impl FromIterator<PathSegment> for ParamPath {
+    fn from_iter<I: IntoIterator<Item = PathSegment>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl core::fmt::Display for ParamPath {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            let dot = if i == 0 { "" } else { "." };
+            match segment {
+                PathSegment::Variant(name) | PathSegment::Field(name) => write!(f, "{dot}{name}")?,
+                PathSegment::TupleField(index) => write!(f, "{dot}{index}")?,
+                PathSegment::Index(index) | PathSegment::Entry(index) => {
+                    write!(f, "[{index}]")?
+                }
+                PathSegment::Key(key) => write!(f, "[{key}]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A difference between two values found by [`ParametrizedDiff`]. It debugs as
+/// the path followed by what differs there, such as `srcs[1]: 2 != 3`.
+#[derive(Clone, PartialEq, Eq)]
+pub enum ParamDiff<T> {
+    /// The values at `path` differ, or are paired at all by
+    /// [`ParametrizedDiff::param_pair()`].
+    Value { path: ParamPath, left: T, right: T },
+    /// The values have different shapes at `path`, so that nothing inside is
+    /// paired.
+    Shape {
+        path: ParamPath,
+        mismatch: ShapeMismatch,
+    },
+}
+
+impl<T> ParamDiff<T> {
+    /// Where the difference is.
+    pub fn path(&self) -> &ParamPath {
+        match self {
+            Self::Value { path, .. } | Self::Shape { path, .. } => path,
+        }
+    }
+
+    /// Returns the difference with `segments` in front of its path.
+    pub fn prefixed(self, segments: &[PathSegment]) -> Self {
+        match self {
+            Self::Value { path, left, right } => Self::Value {
+                path: path.prefixed(segments),
+                left,
+                right,
+            },
+            Self::Shape { path, mismatch } => Self::Shape {
+                path: path.prefixed(segments),
+                mismatch,
+            },
+        }
+    }
+}
+
+impl<T: core::fmt::Debug> core::fmt::Debug for ParamDiff<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.path().segments().is_empty() {
+            write!(f, "self: ")?;
+        } else {
+            write!(f, "{}: ", self.path())?;
+        }
+        match self {
+            Self::Value { left, right, .. } => write!(f, "{:?} != {:?}", left, right),
+            Self::Shape { mismatch, .. } => write!(f, "{}", mismatch),
+        }
+    }
+}
+
+/// Provide [`ParametrizedUnzip::param_unzip()`] method to split the pairs
+/// specified by `PARAM`-th type parameter, turning `S<(A, B)>` into
+/// `(S<A>, S<B>)`. The other fields are cloned into both halves. For
+/// user-defined types, this trait is implemented by [`parametrized`] macro with
+/// `unzip` argument.
+///
+/// ```
+/// # use parametrized::*;
+/// #[parametrized(map, unzip)]
+/// #[derive(Debug, PartialEq)]
+/// struct Inst<R> {
+///     name: String,
+///     operands: Vec<R>,
+/// }
+///
+/// let inst = Inst { name: "add".to_string(), operands: vec![0, 1] };
+/// let annotated = inst.param_map(|r| (r, r == 0));
+/// let (inst, live) = annotated.param_unzip();
+/// assert_eq!(inst, Inst { name: "add".to_string(), operands: vec![0, 1] });
+/// assert_eq!(live, Inst { name: "add".to_string(), operands: vec![true, false] });
+/// ```
+pub trait ParametrizedUnzip<const PARAM: usize, A, B>:
+    Parametrized<PARAM, Item = (A, B)> + Sized
+{
+    type Left: Parametrized<PARAM, Item = A>;
+    type Right: Parametrized<PARAM, Item = B>;
+    /// Splits every pair, keeping the first values in the left half and the
+    /// second values in the right half.
+    fn param_unzip(self) -> (Self::Left, Self::Right);
+}
+
+/// Provide [`ParametrizedBiMap::param_bimap()`] method to map values specified by
+/// `P0`-th and `P1`-th type parameters at once, rebuilding the value only once.
+/// For user-defined types, this trait is implemented by [`parametrized`] macro
+/// with `bimap = [P0, P1]` argument.
+///
+/// ```
+/// # use parametrized::*;
+/// # use std::cell::Cell;
+/// #[parametrized(bimap = [0, 1])]
+/// #[derive(Debug, PartialEq)]
+/// struct Inst<Reg, Imm> {
+///     dest: Reg,
+///     operands: Vec<Result<Reg, Imm>>,
+/// }
+///
+/// let inst = Inst { dest: "r0", operands: vec![Ok("r1"), Err(2u8), Ok("r0")] };
+/// // Both functions may share state, as they are called in a single pass.
+/// let count = Cell::new(0);
+/// let inst = inst.param_bimap(
+///     |r: &str| { count.set(count.get() + 1); r[1..].parse::<u8>().unwrap() },
+///     |i| { count.set(count.get() + 1); i as i64 },
+/// );
+/// assert_eq!(inst, Inst { dest: 0, operands: vec![Ok(1), Err(2), Ok(0)] });
+/// assert_eq!(count.get(), 4);
+/// ```
+pub trait ParametrizedBiMap<const P0: usize, const P1: usize, K0, K1>:
+    ParametrizedIntoIter<P0> + ParametrizedIntoIter<P1> + Sized
+{
+    type Mapped: ParametrizedIntoIter<P0, Item = K0> + ParametrizedIntoIter<P1, Item = K1>;
+    /// Maps the values of `P0`-th parameter with `f0`, and those of `P1`-th
+    /// parameter with `f1`.
+    fn param_bimap(
+        self,
+        f0: impl FnMut(<Self as Parametrized<P0>>::Item) -> K0,
+        f1: impl FnMut(<Self as Parametrized<P1>>::Item) -> K1,
+    ) -> Self::Mapped
+    where
+        <Self as Parametrized<P0>>::Item: Sized,
+        <Self as Parametrized<P1>>::Item: Sized;
+}
+
+/// Provide [`ParametrizedUnion::param_iter_either()`] method to iterate the
+/// values of `P0`-th and `P1`-th type parameters together, in the order they
+/// appear in the value, rather than those of one parameter after the other.
+/// When both parameters are the same type, [`param_iter_union()`] yields the
+/// values themselves. For user-defined types, this trait is implemented by
+/// [`parametrized`] macro with `union = [P0, P1]` argument, whose iterators are
+/// boxed.
+///
+/// [`param_iter_union()`]: ParametrizedUnion::param_iter_union
+///
+/// ```
+/// # use parametrized::*;
+/// #[parametrized(union = [Src, Dst], union_mut = [Src, Dst])]
+/// struct Inst<Src, Dst> {
+///     dest: Dst,
+///     operands: Vec<Src>,
+///     clobbers: Vec<(Dst, Src)>,
+/// }
+///
+/// let mut inst = Inst { dest: 0, operands: vec![1, 2], clobbers: vec![(3, 4)] };
+/// assert_eq!(inst.param_iter_union().collect::<Vec<_>>(), vec![&0, &1, &2, &3, &4]);
+/// inst.param_iter_mut_union().for_each(|r| *r *= 10);
+/// assert_eq!(inst.clobbers, vec![(30, 40)]);
+///
+/// let inst = Inst { dest: "r0", operands: vec![1], clobbers: vec![] };
+/// assert_eq!(
+///     inst.param_iter_either().collect::<Vec<_>>(),
+///     vec![Either::Right(&"r0"), Either::Left(&1)],
+/// );
+/// ```
+pub trait ParametrizedUnion<const P0: usize, const P1: usize>:
+    Parametrized<P0> + Parametrized<P1>
+{
+    type UnionIter<'a>: Iterator<
+        Item = Either<&'a <Self as Parametrized<P0>>::Item, &'a <Self as Parametrized<P1>>::Item>,
+    >
+    where
+        (Self, <Self as Parametrized<P0>>::Item, <Self as Parametrized<P1>>::Item): 'a;
+    fn param_iter_either<'a>(&'a self) -> Self::UnionIter<'a>
+    where
+        <Self as Parametrized<P0>>::Item: 'a,
+        <Self as Parametrized<P1>>::Item: 'a;
+
+    /// Iterates the values of both parameters, which are the same type.
+    #[allow(clippy::type_complexity)]
+    fn param_iter_union<'a, T: 'a>(
+        &'a self,
+    ) -> core::iter::Map<Self::UnionIter<'a>, fn(Either<&'a T, &'a T>) -> &'a T>
+    where
+        Self: Parametrized<P0, Item = T> + Parametrized<P1, Item = T>,
+    {
+        self.param_iter_either().map(Either::into_inner)
+    }
+}
+
+/// Provide [`ParametrizedUnionMut::param_iter_mut_either()`] method, the
+/// mutable counterpart of [`ParametrizedUnion`]. For user-defined types, this
+/// trait is implemented by [`parametrized`] macro with `union_mut = [P0, P1]`
+/// argument.
+pub trait ParametrizedUnionMut<const P0: usize, const P1: usize>:
+    Parametrized<P0> + Parametrized<P1>
+{
+    type UnionIterMut<'a>: Iterator<
+        Item = Either<
+            &'a mut <Self as Parametrized<P0>>::Item,
+            &'a mut <Self as Parametrized<P1>>::Item,
+        >,
+    >
+    where
+        (Self, <Self as Parametrized<P0>>::Item, <Self as Parametrized<P1>>::Item): 'a;
+    fn param_iter_mut_either<'a>(&'a mut self) -> Self::UnionIterMut<'a>
+    where
+        <Self as Parametrized<P0>>::Item: 'a,
+        <Self as Parametrized<P1>>::Item: 'a;
+
+    /// Iterates the values of both parameters mutably, which are the same type.
+    #[allow(clippy::type_complexity)]
+    fn param_iter_mut_union<'a, T: 'a>(
+        &'a mut self,
+    ) -> core::iter::Map<Self::UnionIterMut<'a>, fn(Either<&'a mut T, &'a mut T>) -> &'a mut T>
+    where
+        Self: Parametrized<P0, Item = T> + Parametrized<P1, Item = T>,
+    {
+        self.param_iter_mut_either().map(Either::into_inner)
+    }
+}
+
+/// Provide [`ParametrizedRetain::param_retain()`] method to remove the values
+/// specified by `PARAM`-th type parameter in place. Values can be removed from
+/// collections and `Option`s, which become `None`. For user-defined types, this
+/// trait is implemented by [`parametrized`] macro with `retain` argument, as long
+/// as every value sits in such a position.
+///
+/// ```
+/// # use parametrized::*;
+/// #[parametrized(retain)]
+/// #[derive(Debug, PartialEq)]
+/// struct S<T>(Vec<T>, Option<T>, Vec<Option<T>>);
+///
+/// let mut s = S(vec![1, 2, 3], Some(4), vec![Some(5), Some(6)]);
+/// s.param_retain(|v| v % 2 == 0);
+/// assert_eq!(s, S(vec![2], Some(4), vec![None, Some(6)]));
+/// ```
+///
+/// Values inside tuples or arrays cannot be removed one by one, so such fields
+/// are rejected, even inside collections.
+///
+/// ```compile_fail
+/// # use parametrized::*;
+/// #[parametrized(retain)]
+/// struct R<T> {
+///     live: Vec<(T, bool)>,
+/// }
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot remove its values of type parameter {PARAM}",
+    note = "values can be removed only from collections and `Option`s"
+)]
+pub trait ParametrizedRetain<const PARAM: usize>: Parametrized<PARAM> {
+    /// Removes the values for which `f` returns `false`.
+    fn param_retain(&mut self, f: impl FnMut(&Self::Item) -> bool);
+}
+
+/// Provide [`ParametrizedFilterMap::param_filter_map()`] method to map values
+/// specified by `PARAM`-th type parameter, removing those for which `f` returns
+/// `None`. Values can be removed from the same positions as
+/// [`ParametrizedRetain`]. For user-defined types, this trait is implemented by
+/// [`parametrized`] macro with `filter_map` argument, which implies `map`.
+///
+/// ```
+/// # use parametrized::*;
+/// #[parametrized(filter_map)]
+/// #[derive(Debug, PartialEq)]
+/// enum Inst<R> {
+///     Jump(Option<R>),
+///     Call(String, Vec<R>),
+/// }
+///
+/// let inst = Inst::Call("f".to_string(), vec!["1", "x", "2"]);
+/// let inst = inst.param_filter_map(|r| r.parse::<u8>().ok());
+/// assert_eq!(inst, Inst::Call("f".to_string(), vec![1, 2]));
+/// assert_eq!(Inst::Jump(Some("x")).param_filter_map(|r| r.parse::<u8>().ok()), Inst::Jump(None));
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot remove its values of type parameter {PARAM}",
+    note = "values can be removed only from collections and `Option`s"
+)]
+pub trait ParametrizedFilterMap<const PARAM: usize, K>: ParametrizedMap<PARAM, K> {
+    /// Maps every value with `f`, removing the values it returns `None` for.
+    fn param_filter_map(self, f: impl FnMut(Self::Item) -> Option<K>) -> Self::Mapped
+    where
+        Self::Item: Sized;
+}
+
+/// Provide [`ParametrizedUpdate::param_update()`] method to replace every value
+/// specified by `PARAM`-th type parameter with the result of a function taking
+/// it by value. Unlike [`ParametrizedIterMut`], values in sequences, options
+/// and maps need not be `Default` or `Clone` to be transformed, and keys of maps
+/// and elements of sets can be updated as well. Collections are taken out and
+/// rebuilt, so values of sets which become equal are merged, and a `Vec` keeps
+/// its allocation. Values held in place, such as in arrays, boxes, results,
+/// tuples and fields, are swapped out with [`Default::default()`] while they
+/// are updated, so they must be `Default`. For user-defined types, this trait is
+/// implemented by [`parametrized`] macro with `update` argument, which requires
+/// the parameter to be `Default` like `#[derive(Default)]` does.
+///
+/// If the function panics, the panic unwinds normally, leaving the value being
+/// updated as its default and collections being rebuilt empty.
+///
+/// ```
+/// # use parametrized::*;
+/// # use std::collections::BTreeSet;
+/// #[derive(Debug, PartialEq)]
+/// struct Reg(String);
+///
+/// #[parametrized(update)]
+/// #[derive(Debug, PartialEq)]
+/// struct Inst<R: Ord> {
+///     dest: R,
+///     uses: BTreeSet<R>,
+/// }
+///
+/// let mut inst = Inst { dest: 0, uses: [1, 2, 3].into_iter().collect() };
+/// inst.param_update(|r| r / 2);
+/// assert_eq!(inst, Inst { dest: 0, uses: [0, 1].into_iter().collect() });
+///
+/// let mut regs = vec![Reg("a".to_string()), Reg("b".to_string())];
+/// regs.param_update(|Reg(name)| Reg(name + "'"));
+/// assert_eq!(regs, vec![Reg("a'".to_string()), Reg("b'".to_string())]);
+/// ```
+pub trait ParametrizedUpdate<const PARAM: usize>: Parametrized<PARAM> {
+    /// Replaces every value with the result of `f`.
+    fn param_update(&mut self, f: impl FnMut(Self::Item) -> Self::Item)
+    where
+        Self::Item: Sized;
+}
+
+/// Provide [`ParametrizedPath::param_iter_with_path()`] method to iterate the
+/// values specified by `PARAM`-th type parameter together with the
+/// [`ParamPath`] telling where each of them lives, and
+/// [`ParametrizedPath::param_get_path()`] method to look a value up by its path.
+/// Fields and variants are named after their idents, and values in sequences,
+/// maps and sets by their positions. For user-defined types, this trait is
+/// implemented by [`parametrized`] macro with `path` argument, whose iterator
+/// of paths is boxed.
+///
+/// ```
+/// # use parametrized::*;
+/// #[parametrized(path, iter_mut)]
+/// enum Inst<R> {
+///     BinaryOp { dest: R, srcs: [R; 2] },
+///     Call(String, Vec<R>),
+/// }
+///
+/// let inst = Inst::BinaryOp { dest: 0, srcs: [1, 2] };
+/// let paths = inst
+///     .param_iter_with_path()
+///     .map(|(path, r)| format!("{path} = {r}"))
+///     .collect::<Vec<_>>();
+/// assert_eq!(paths, vec!["BinaryOp.dest = 0", "BinaryOp.srcs[0] = 1", "BinaryOp.srcs[1] = 2"]);
+///
+/// let mut inst = Inst::Call("f".to_string(), vec![3, 4]);
+/// let (path, _) = inst.param_iter_mut_with_path().find(|(_, r)| **r == 4).unwrap();
+/// assert_eq!(path.to_string(), "Call.1[1]");
+/// assert_eq!(inst.param_get_path(&path), Some(&4));
+/// ```
+pub trait ParametrizedPath<const PARAM: usize>: Parametrized<PARAM> {
+    type PathIter<'a>: Iterator<Item = ParamPath>
+    where
+        Self: 'a;
+
+    /// Iterates the paths of the values, in the order of
+    /// [`Parametrized::param_iter()`].
+    fn param_path_iter(&self) -> Self::PathIter<'_>;
+
+    /// The paths of the values, in the order of [`Parametrized::param_iter()`].
+    fn param_paths(&self) -> Vec<ParamPath> {
+        self.param_path_iter().collect()
+    }
+
+    /// Looks up the value which `path` leads to, returning it with the rest of
+    /// `path` which lies inside the value.
+    #[allow(clippy::type_complexity)]
+    fn param_get_path_prefix<'a, 'p>(
+        &'a self,
+        path: &'p [PathSegment],
+    ) -> Option<(&'a Self::Item, &'p [PathSegment])>;
+
+    /// Looks up the value at `path`.
+    fn param_get_path(&self, path: &ParamPath) -> Option<&Self::Item> {
+        match self.param_get_path_prefix(path.segments()) {
+            Some((item, [])) => Some(item),
+            _ => None,
+        }
+    }
+
+    /// Iterates the values with their paths.
+    #[allow(clippy::type_complexity)]
+    fn param_iter_with_path<'a>(&'a self) -> core::iter::Zip<Self::PathIter<'a>, Self::Iter<'a>>
+    where
+        Self::Item: 'a,
+    {
+        self.param_path_iter().zip(self.param_iter())
+    }
+
+    /// Iterates the values mutably with their paths. The paths are collected
+    /// beforehand, since they cannot be found while the values are borrowed.
+    fn param_iter_mut_with_path<'a>(
+        &'a mut self,
+    ) -> core::iter::Zip<_imp::vec::IntoIter<ParamPath>, Self::IterMut<'a>>
+    where
+        Self: ParametrizedIterMut<PARAM>,
+        Self::Item: 'a,
+    {
+        self.param_paths().into_iter().zip(self.param_iter_mut())
+    }
+}
+
+/// Provide [`ParametrizedDiff::param_diff()`] method to compare two values of
+/// the same type, reporting the paths where the values specified by `PARAM`-th
+/// type parameter differ, and where the shapes differ, such as different
+/// variants or containers of different lengths. Fields without the parameter
+/// are not compared, so compare them separately if needed. Sequences are
+/// compared position by position, in the order of
+/// [`Parametrized::param_iter()`], and maps and sets entry by entry, where the
+/// entries only one of them has are shape differences. `BinaryHeap`s cannot be
+/// compared. For user-defined types, this trait is implemented by
+/// [`parametrized`] macro with `diff` argument.
+///
+/// ```
+/// # use parametrized::*;
+/// #[parametrized(diff)]
+/// enum Inst<R> {
+///     BinaryOp { dest: R, srcs: Vec<R> },
+///     Call(String, Vec<R>),
+/// }
+///
+/// let a = Inst::BinaryOp { dest: 0, srcs: vec![1, 2] };
+/// let b = Inst::BinaryOp { dest: 0, srcs: vec![1, 3, 4] };
+/// assert_eq!(
+///     format!("{:?}", a.param_diff(&b)),
+///     "[BinaryOp.srcs: expected length 2, found 3, BinaryOp.srcs[1]: 2 != 3]",
+/// );
+///
+/// let c = Inst::Call("f".to_string(), vec![0]);
+/// assert_eq!(
+///     format!("{:?}", a.param_diff(&c)),
+///     "[self: expected variant `BinaryOp`, found `Call`]",
+/// );
+/// assert!(c.param_diff(&c).is_empty());
+///
+/// let m: std::collections::BTreeMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+/// let n = [(2, 'b'), (3, 'c')].into_iter().collect();
+/// assert_eq!(
+///     format!("{:?}", ParametrizedDiff::<1>::param_diff(&m, &n)),
+///     "[[1]: expected an entry, found none, [3]: expected no entry, found one]",
+/// );
+/// ```
+pub trait ParametrizedDiff<const PARAM: usize>: Parametrized<PARAM> {
+    /// Pairs the values of `self` and `other` which are at the same paths and
+    /// for which `keep` returns `true`, and reports where the shapes differ.
+    fn param_pair_by<'a>(
+        &'a self,
+        other: &'a Self,
+        keep: &mut dyn FnMut(&Self::Item, &Self::Item) -> bool,
+    ) -> Vec<ParamDiff<&'a Self::Item>>;
+
+    /// Pairs the values of `self` and `other` which are at the same paths,
+    /// whether they are equal or not, and reports where the shapes differ.
+    fn param_pair<'a>(&'a self, other: &'a Self) -> Vec<ParamDiff<&'a Self::Item>> {
+        self.param_pair_by(other, &mut |_, _| true)
+    }
+
+    /// The differences between `self` and `other`, in the order of
+    /// [`Parametrized::param_iter()`].
+    fn param_diff<'a>(&'a self, other: &'a Self) -> Vec<ParamDiff<&'a Self::Item>>
+    where
+        Self::Item: PartialEq,
+    {
+        self.param_pair_by(other, &mut |left, right| left != right)
+    }
+}
+
+/// Iterators which yield exactly `LEN` items when they are created, so that
+/// [`Flatten`] knows its length from the number of inner iterators.
+#[doc(hidden)]
+pub trait FixedLenIterator: Iterator {
+    const LEN: usize;
+}
+
+impl<T> FixedLenIterator for core::iter::Once<T> {
+    const LEN: usize = 1;
+}
+
+impl<T> FixedLenIterator for core::iter::Empty<T> {
+    const LEN: usize = 0;
+}
+
+impl<B, I: FixedLenIterator, F: FnMut(I::Item) -> B> FixedLenIterator for core::iter::Map<I, F> {
+    const LEN: usize = I::LEN;
+}
+
+impl<A, B> FixedLenIterator for Chain<A, B>
+where
+    A: FixedLenIterator,
+    B: FixedLenIterator<Item = A::Item>,
+{
+    const LEN: usize = A::LEN + B::LEN;
+}
+
+impl<T, const N: usize> FixedLenIterator for core::array::IntoIter<T, N> {
+    const LEN: usize = N;
+}
+
+impl<I, Iter, T> FixedLenIterator for Flatten<I, Iter>
+where
+    I: FixedLenIterator,
+    <I as Iterator>::Item: IntoIterator<IntoIter = Iter, Item = T>,
+    Iter: FixedLenIterator<Item = T>,
+{
+    const LEN: usize = I::LEN * Iter::LEN;
+}
+
+/// Like [`std::iter::Chain`], but also an [`ExactSizeIterator`] when both
+/// halves are.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+pub struct Chain<A, B> {
+    a: Option<A>,
+    b: B,
+}
+
+impl<A, B> Chain<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Chain { a: Some(a), b }
+    }
+}
+
+impl<A, B> Iterator for Chain<A, B>
+where
+    A: Iterator,
+    B: Iterator<Item = A::Item>,
+{
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(a) = &mut self.a {
+            match a.next() {
+                None => self.a = None,
+                item => return item,
+            }
+        }
+        self.b.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (b_lower, b_upper) = self.b.size_hint();
+        match &self.a {
+            Some(a) => {
+                let (a_lower, a_upper) = a.size_hint();
+                (
+                    a_lower.saturating_add(b_lower),
+                    a_upper
+                        .zip(b_upper)
+                        .and_then(|(a, b)| a.checked_add(b)),
+                )
+            }
+            None => (b_lower, b_upper),
+        }
+    }
+
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        if let Some(a) = &mut self.a {
+            match a.size_hint() {
+                // Skip the first half at once when its length is known.
+                (lower, Some(upper)) if lower == upper => {
+                    if n < lower {
+                        return a.nth(n);
+                    }
+                    n -= lower;
+                }
+                _ => {
+                    for item in a {
+                        if n == 0 {
+                            return Some(item);
+                        }
+                        n -= 1;
+                    }
+                }
+            }
+            self.a = None;
+        }
+        self.b.nth(n)
+    }
+
+    fn fold<Acc, F>(self, init: Acc, mut f: F) -> Acc
+    where
+        F: FnMut(Acc, Self::Item) -> Acc,
+    {
+        let mut acc = init;
+        if let Some(a) = self.a {
+            acc = a.fold(acc, &mut f);
+        }
+        self.b.fold(acc, f)
+    }
+}
+
+impl<A, B> DoubleEndedIterator for Chain<A, B>
+where
+    A: DoubleEndedIterator,
+    B: DoubleEndedIterator<Item = A::Item>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.b.next_back() {
+            None => self.a.as_mut()?.next_back(),
+            item => item,
+        }
+    }
+
+    fn rfold<Acc, F>(self, init: Acc, mut f: F) -> Acc
+    where
+        F: FnMut(Acc, Self::Item) -> Acc,
+    {
+        let mut acc = self.b.rfold(init, &mut f);
+        if let Some(a) = self.a {
+            acc = a.rfold(acc, f);
+        }
+        acc
+    }
+}
+
+impl<A, B> ExactSizeIterator for Chain<A, B>
+where
+    A: ExactSizeIterator,
+    B: ExactSizeIterator<Item = A::Item>,
+{
+}
+
+impl<A, B> core::iter::FusedIterator for Chain<A, B>
+where
+    A: Iterator,
+    B: core::iter::FusedIterator<Item = A::Item>,
+{
+}
+
+/// The iterator over the elements of an array, which knows the length `N` of
+/// the array for [`FixedLenIterator`].
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+pub struct ArrayIter<I, const N: usize>(I);
+
+impl<I, const N: usize> Iterator for ArrayIter<I, N>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.0.nth(n)
+    }
+
+    fn fold<Acc, F>(self, init: Acc, f: F) -> Acc
+    where
+        F: FnMut(Acc, Self::Item) -> Acc,
+    {
+        self.0.fold(init, f)
+    }
+}
+
+impl<I, const N: usize> DoubleEndedIterator for ArrayIter<I, N>
+where
+    I: DoubleEndedIterator,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.0.nth_back(n)
+    }
+
+    fn rfold<Acc, F>(self, init: Acc, f: F) -> Acc
+    where
+        F: FnMut(Acc, Self::Item) -> Acc,
+    {
+        self.0.rfold(init, f)
+    }
+}
+
+impl<I, const N: usize> ExactSizeIterator for ArrayIter<I, N> where I: ExactSizeIterator {}
+
+impl<I, const N: usize> core::iter::FusedIterator for ArrayIter<I, N> where
+    I: core::iter::FusedIterator
+{
+}
+
+impl<I, const N: usize> FixedLenIterator for ArrayIter<I, N>
+where
+    I: Iterator,
+{
+    const LEN: usize = N;
+}
+
+/// Like [`std::iter::Flatten`], but no where clauses in type definitions.
+#[doc(hidden)]
+#[derive(Clone, Debug)]
+pub struct Flatten<I, Iter> {
+    slot: I,
+    front: Option<Iter>,
+    back: Option<Iter>,
+    /// The length of every inner iterator, if known.
+    inner_len: Option<usize>,
+}
+
+impl<I, Iter> Flatten<I, Iter> {
+    pub fn new(slot: I) -> Self {
+        Flatten {
+            slot,
+            front: None,
+            back: None,
+            inner_len: None,
+        }
+    }
+
+    /// Like [`Self::new()`], given the bounds of the lengths of the inner
+    /// iterators, such as `MIN_LEN` and `MAX_LEN`.
+    pub fn with_inner_len(slot: I, min_len: usize, max_len: Option<usize>) -> Self {
+        Flatten {
+            inner_len: (max_len == Some(min_len)).then_some(min_len),
+            ..Self::new(slot)
+        }
+    }
+}
+
+impl<I, Iter, T> Iterator for Flatten<I, Iter>
+where
+    I: Iterator,
+    <I as Iterator>::Item: IntoIterator<IntoIter = Iter, Item = T>,
+    Iter: Iterator<Item = T>,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.front.as_mut().and_then(Iterator::next) {
+                return Some(item);
+            }
+            match self.slot.next() {
+                Some(inner) => self.front = Some(inner.into_iter()),
+                None => {
+                    self.front = None;
+                    return self.back.as_mut()?.next();
+                }
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let hint = |iter: &Option<Iter>| iter.as_ref().map_or((0, Some(0)), Iterator::size_hint);
+        let (front_lower, front_upper) = hint(&self.front);
+        let (back_lower, back_upper) = hint(&self.back);
+        let lower = front_lower.saturating_add(back_lower);
+        let upper = front_upper
+            .zip(back_upper)
+            .and_then(|(front, back)| front.checked_add(back));
+        match (self.slot.size_hint(), self.inner_len) {
+            ((_, Some(0)), _) => (lower, upper),
+            ((slot_lower, slot_upper), Some(len)) => (
+                lower.saturating_add(slot_lower.saturating_mul(len)),
+                upper
+                    .zip(slot_upper.and_then(|slot| slot.checked_mul(len)))
+                    .and_then(|(upper, slot)| upper.checked_add(slot)),
+            ),
+            (_, None) => (lower, None),
+        }
+    }
+
+    fn nth(&mut self, mut n: usize) -> Option<Self::Item> {
+        loop {
+            if let Some(front) = &mut self.front {
+                match front.size_hint() {
+                    // Skip the whole inner iterator when its length is known.
+                    (lower, Some(upper)) if lower == upper => {
+                        if n < lower {
+                            return front.nth(n);
+                        }
+                        n -= lower;
+                    }
+                    _ => {
+                        for item in front {
+                            if n == 0 {
+                                return Some(item);
+                            }
+                            n -= 1;
+                        }
+                    }
+                }
+                self.front = None;
+            }
+            // Skip as many inner iterators as possible at once, but no more than
+            // are left, so that the rest of `n` is counted in `back`.
+            let inner = match (self.inner_len, self.slot.size_hint()) {
+                (Some(len), (lower, Some(upper))) if len > 0 && lower == upper => {
+                    let skipped = (n / len).min(lower);
+                    n -= skipped * len;
+                    self.slot.nth(skipped)
+                }
+                _ => self.slot.next(),
+            };
+            match inner {
+                Some(inner) => self.front = Some(inner.into_iter()),
+                None => return self.back.as_mut()?.nth(n),
+            }
+        }
+    }
+
+    fn fold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let mut acc = init;
+        if let Some(front) = self.front {
+            acc = front.fold(acc, &mut f);
+        }
+        acc = self
+            .slot
+            .fold(acc, |acc, inner| inner.into_iter().fold(acc, &mut f));
+        if let Some(back) = self.back {
+            acc = back.fold(acc, &mut f);
+        }
+        acc
+    }
+}
+
+impl<I, Iter, T> DoubleEndedIterator for Flatten<I, Iter>
+where
+    I: DoubleEndedIterator,
+    <I as Iterator>::Item: IntoIterator<IntoIter = Iter, Item = T>,
+    Iter: DoubleEndedIterator<Item = T>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.back.as_mut().and_then(DoubleEndedIterator::next_back) {
+                return Some(item);
+            }
+            match self.slot.next_back() {
+                Some(inner) => self.back = Some(inner.into_iter()),
+                None => {
+                    self.back = None;
+                    return self.front.as_mut()?.next_back();
+                }
+            }
+        }
+    }
+
+    fn rfold<B, F>(self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, Self::Item) -> B,
+    {
+        let mut acc = init;
+        if let Some(back) = self.back {
+            acc = back.rfold(acc, &mut f);
+        }
+        acc = self
+            .slot
+            .rfold(acc, |acc, inner| inner.into_iter().rfold(acc, &mut f));
+        if let Some(front) = self.front {
+            acc = front.rfold(acc, &mut f);
+        }
+        acc
+    }
+}
+
+impl<I, Iter, T> ExactSizeIterator for Flatten<I, Iter>
+where
+    I: ExactSizeIterator,
+    <I as Iterator>::Item: IntoIterator<IntoIter = Iter, Item = T>,
+    Iter: ExactSizeIterator<Item = T> + FixedLenIterator,
+{
+    fn len(&self) -> usize {
+        self.front.as_ref().map_or(0, ExactSizeIterator::len)
+            + self.slot.len() * Iter::LEN
+            + self.back.as_ref().map_or(0, ExactSizeIterator::len)
+    }
+}
+
+impl<I, Iter, T> core::iter::FusedIterator for Flatten<I, Iter>
+where
+    I: core::iter::FusedIterator,
+    <I as Iterator>::Item: IntoIterator<IntoIter = Iter, Item = T>,
+    Iter: core::iter::FusedIterator<Item = T>,
+{
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a, const PARAM: usize, T> Parametrized<PARAM> for &'a T
+where
+    T: Parametrized<PARAM>,
+{
+    type Item = <T as Parametrized<PARAM>>::Item;
+    const MIN_LEN: usize = <T as Parametrized<PARAM>>::MIN_LEN;
+    const MAX_LEN: Option<usize> = <T as Parametrized<PARAM>>::MAX_LEN;
+    fn param_len(&self) -> usize {
+        <T as Parametrized<PARAM>>::param_len(self)
+    }
+    type Iter<'b> = <T as Parametrized<PARAM>>::Iter<'b>
+    where
+        (Self, Self::Item): 'b;
+
+    fn param_iter<'b>(&'b self) -> Self::Iter<'b>
+    where
+        Self::Item: 'b,
+    {
+        <T as Parametrized<PARAM>>::param_iter(self)
+    }
+    fn param_get(&self, n: usize) -> Option<&Self::Item> {
+        <T as Parametrized<PARAM>>::param_get(self, n)
+    }
+    fn param_for_each<'b>(&'b self, f: impl FnMut(&'b Self::Item))
+    where
+        Self::Item: 'b,
+    {
+        <T as Parametrized<PARAM>>::param_for_each(self, f)
+    }
+    fn param_try_for_each<'b, B>(
+        &'b self,
+        f: impl FnMut(&'b Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'b,
+    {
+        <T as Parametrized<PARAM>>::param_try_for_each(self, f)
+    }
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a, const PARAM: usize, T> Parametrized<PARAM> for &'a mut T
+where
+    T: Parametrized<PARAM>,
+{
+    type Item = <T as Parametrized<PARAM>>::Item;
+    const MIN_LEN: usize = <T as Parametrized<PARAM>>::MIN_LEN;
+    const MAX_LEN: Option<usize> = <T as Parametrized<PARAM>>::MAX_LEN;
+    fn param_len(&self) -> usize {
+        <T as Parametrized<PARAM>>::param_len(self)
+    }
+    type Iter<'b> = <T as Parametrized<PARAM>>::Iter<'b>
+    where
+        (Self, Self::Item): 'b;
+
+    fn param_iter<'b>(&'b self) -> Self::Iter<'b>
+    where
+        Self::Item: 'b,
+    {
+        <T as Parametrized<PARAM>>::param_iter(self)
+    }
+    fn param_get(&self, n: usize) -> Option<&Self::Item> {
+        <T as Parametrized<PARAM>>::param_get(self, n)
+    }
+    fn param_for_each<'b>(&'b self, f: impl FnMut(&'b Self::Item))
+    where
+        Self::Item: 'b,
+    {
+        <T as Parametrized<PARAM>>::param_for_each(self, f)
+    }
+    fn param_try_for_each<'b, B>(
+        &'b self,
+        f: impl FnMut(&'b Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'b,
+    {
+        <T as Parametrized<PARAM>>::param_try_for_each(self, f)
+    }
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl<'a, const PARAM: usize, T> ParametrizedIterMut<PARAM> for &'a mut T
+where
+    T: ParametrizedIterMut<PARAM>,
+{
+    type IterMut<'b> = <T as ParametrizedIterMut<PARAM>>::IterMut<'b>
+    where
+        (Self, Self::Item): 'b;
+
+    fn param_iter_mut<'b>(&'b mut self) -> Self::IterMut<'b>
+    where
+        Self::Item: 'b,
+    {
+        <T as ParametrizedIterMut<PARAM>>::param_iter_mut(self)
+    }
+    fn param_get_mut(&mut self, n: usize) -> Option<&mut Self::Item> {
+        <T as ParametrizedIterMut<PARAM>>::param_get_mut(self, n)
+    }
+    fn param_for_each_mut<'b>(&'b mut self, f: impl FnMut(&'b mut Self::Item))
+    where
+        Self::Item: 'b,
+    {
+        <T as ParametrizedIterMut<PARAM>>::param_for_each_mut(self, f)
+    }
+}
+
+macro_rules! impl_for_tuple {
+    (@wrap_f $fn:ident[] [] [$($_:expr),*] {$($out:expr),*}) => {($($out,)*)};
+    (@wrap_f $fn:ident[] [$_:ident$(,$params1:ident)*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*}) => {
+        impl_for_tuple!(@wrap_f $fn[] [$($params1),*] [$($t),*] {$($out,)*$rhs})
+    };
+    (@wrap_f $fn:ident[] $param:ident [$($params1:ident),*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*}) => {
+        impl_for_tuple!(@wrap_f $fn[] [$($params1),*] [$($t),*] {$($out,)*($fn($rhs))})
+    };
+    (@wrap_f $fn:ident[$_:ident $(,$params0:ident)*] $param:ident [$($params1:ident),*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*} ) => {
+        impl_for_tuple!(@wrap_f $fn[$($params0),*] $param [$($params1),*] [$($t),*] {$($out,)*$rhs})
+    };
+    (@wrap_try $fn:ident[] [] [$($_:expr),*] {$($out:expr),*}) => {($($out,)*)};
+    (@wrap_try $fn:ident[] [$_:ident$(,$params1:ident)*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*}) => {
+        impl_for_tuple!(@wrap_try $fn[] [$($params1),*] [$($t),*] {$($out,)*$rhs})
+    };
+    (@wrap_try $fn:ident[] $param:ident [$($params1:ident),*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*}) => {
+        impl_for_tuple!(@wrap_try $fn[] [$($params1),*] [$($t),*] {$($out,)*($fn($rhs)?)})
+    };
+    (@wrap_try $fn:ident[$_:ident $(,$params0:ident)*] $param:ident [$($params1:ident),*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*} ) => {
+        impl_for_tuple!(@wrap_try $fn[$($params0),*] $param [$($params1),*] [$($t),*] {$($out,)*$rhs})
+    };
+    (@wrap_ref $fn:ident[] [] [$($_:expr),*] {$($out:expr),*}) => {($($out,)*)};
+    (@wrap_ref $fn:ident[] [$_:ident$(,$params1:ident)*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*}) => {
+        impl_for_tuple!(@wrap_ref $fn[] [$($params1),*] [$($t),*] {$($out,)*(Clone::clone($rhs))})
+    };
+    (@wrap_ref $fn:ident[] $param:ident [$($params1:ident),*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*}) => {
+        impl_for_tuple!(@wrap_ref $fn[] [$($params1),*] [$($t),*] {$($out,)*($fn($rhs))})
+    };
+    (@wrap_ref $fn:ident[$_:ident $(,$params0:ident)*] $param:ident [$($params1:ident),*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*} ) => {
+        impl_for_tuple!(@wrap_ref $fn[$($params0),*] $param [$($params1),*] [$($t),*] {$($out,)*(Clone::clone($rhs))})
+    };
+    (@put $val:ident[] [] [$($_:expr),*] {$($out:expr),*}) => {($($out,)*)};
+    (@put $val:ident[] [$_:ident$(,$params1:ident)*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*}) => {
+        impl_for_tuple!(@put $val[] [$($params1),*] [$($t),*] {$($out,)*$rhs})
+    };
+    (@put $val:ident[] $param:ident [$($params1:ident),*] [$_:expr $(,$t:expr)*] {$($out:expr),*}) => {
+        impl_for_tuple!(@put $val[] [$($params1),*] [$($t),*] {$($out,)*$val})
+    };
+    (@put $val:ident[$_:ident $(,$params0:ident)*] $param:ident [$($params1:ident),*] [$rhs:expr $(,$t:expr)*] {$($out:expr),*} ) => {
+        impl_for_tuple!(@put $val[$($params0),*] $param [$($params1),*] [$($t),*] {$($out,)*$rhs})
+    };
+    (@nth [] [$rhs:expr$(,$_:expr)*]) => { $rhs };
+    (@nth [$_:expr $(,$lhs:expr)*] [$__:expr $(,$rhs:expr)*]) => {
+        impl_for_tuple!(@nth [$($lhs),*] [$($rhs),*])
+    };
+    (@count) => {0usize};
+    (@count $_:ident $(,$t:ident)*) => {
+        impl_for_tuple!(@count $($t),*) + 1
+    };
+    (
+        [$($params0:ident),*] $param:ident [$($params1:ident),*]
+    ) => {
+        impl<$($params0,)* $param $(,$params1)*> Parametrized<{impl_for_tuple!(@count $($params0),*)}>
+        for ($($params0,)* $param, $($params1),*)
+        {
+            type Item = $param;
+            const MIN_LEN: usize = 1;
+            const MAX_LEN: Option<usize> = Some(1);
+            fn param_len(&self) -> usize {
+                1
+            }
+            type Iter<'a> = ::core::iter::Once<&'a Self::Item>
+            where
+                (Self, Self::Item): 'a;
+            fn param_iter<'a>(&'a self) -> Self::Iter<'a> where Self::Item: 'a {
+                ::core::iter::once(impl_for_tuple!(
+                    @nth [$($params0),*]
+                    [
+                        &self.0, &self.1, &self.2, &self.3, &self.4, &self.5, &self.6,
+                        &self.7, &self.8, &self.9, &self.10, &self.11
+                    ]
+                ))
+            }
+            fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+            where
+                Self::Item: 'a,
+            {
+                f(impl_for_tuple!(
+                    @nth [$($params0),*]
+                    [
+                        &self.0, &self.1, &self.2, &self.3, &self.4, &self.5, &self.6,
+                        &self.7, &self.8, &self.9, &self.10, &self.11
+                    ]
+                ))
             }
-            type Iter<'a> = ::core::iter::Once<&'a Self::Item>
+            fn param_try_for_each<'a, B>(
+                &'a self,
+                mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+            ) -> core::ops::ControlFlow<B>
             where
-                (Self, Self::Item): 'a;
-            fn param_iter<'a>(&'a self) -> Self::Iter<'a> where Self::Item: 'a {
-                ::core::iter::once(impl_for_tuple!(
+                Self::Item: 'a,
+            {
+                f(impl_for_tuple!(
                     @nth [$($params0),*]
                     [
                         &self.0, &self.1, &self.2, &self.3, &self.4, &self.5, &self.6,
@@ -265,6 +1959,19 @@ macro_rules! impl_for_tuple {
                     ]
                 ))
             }
+            fn param_for_each_mut<'a>(&'a mut self, mut f: impl FnMut(&'a mut Self::Item))
+            where
+                Self::Item: 'a,
+            {
+                f(impl_for_tuple!(
+                    @nth [$($params0),*]
+                    [
+                        &mut self.0, &mut self.1, &mut self.2, &mut self.3, &mut self.4,
+                        &mut self.5, &mut self.6, &mut self.7, &mut self.8, &mut self.9,
+                        &mut self.10, &mut self.11
+                    ]
+                ))
+            }
         }
         impl<$($params0,)* $param $(,$params1)*>
             ParametrizedIntoIter<{impl_for_tuple!(@count $($params0),*)}>
@@ -282,6 +1989,19 @@ macro_rules! impl_for_tuple {
                     ]
                 ))
             }
+            fn param_fold<B>(self, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B
+            where
+                Self::Item: Sized,
+            {
+                f(init, impl_for_tuple!(
+                    @nth [$($params0),*]
+                    [
+                        self.0, self.1, self.2, self.3, self.4,
+                        self.5, self.6, self.7, self.8, self.9,
+                        self.10, self.11
+                    ]
+                ))
+            }
         }
 
         impl<U, $($params0,)* $param $(,$params1)*>
@@ -298,14 +2018,267 @@ macro_rules! impl_for_tuple {
                 ] {})
             }
         }
+
+        impl<U, $($params0,)* $param $(,$params1)*>
+            ParametrizedTryMap<{impl_for_tuple!(@count $($params0),*)}, U>
+            for ($($params0,)* $param, $($params1),*)
+        {
+            fn try_param_map<E>(
+                self,
+                mut f: impl FnMut(Self::Item) -> Result<U, E>,
+            ) -> Result<Self::Mapped, E>
+            where Self::Item: Sized
+            {
+                Ok(impl_for_tuple!(@wrap_try f[$($params0),*] $param [$($params1),*] [
+                    self.0, self.1, self.2, self.3, self.4, self.5, self.6,
+                    self.7, self.8, self.9, self.10, self.11
+                ] {}))
+            }
+        }
+
+        impl<U, $($params0: Clone,)* $param $(,$params1: Clone)*>
+            ParametrizedMapRef<{impl_for_tuple!(@count $($params0),*)}, U>
+            for ($($params0,)* $param, $($params1),*)
+        {
+            type Mapped = ($($params0,)* U, $($params1),*);
+            fn param_map_ref<'a>(&'a self, mut f: impl FnMut(&'a Self::Item) -> U) -> Self::Mapped
+            where Self::Item: 'a
+            {
+                impl_for_tuple!(@wrap_ref f[$($params0),*] $param [$($params1),*] [
+                    &self.0, &self.1, &self.2, &self.3, &self.4, &self.5, &self.6,
+                    &self.7, &self.8, &self.9, &self.10, &self.11
+                ] {})
+            }
+        }
+
+        impl<U, V, $($params0,)* $param $(,$params1)*>
+            ParametrizedZip<
+                {impl_for_tuple!(@count $($params0),*)},
+                ($($params0,)* V, $($params1),*),
+                U,
+            >
+            for ($($params0,)* $param, $($params1),*)
+        {
+            fn try_param_zip_with<E: From<ShapeMismatch>>(
+                self,
+                other: ($($params0,)* V, $($params1),*),
+                mut f: impl FnMut(Self::Item, V) -> Result<U, E>,
+            ) -> Result<Self::Mapped, E> {
+                let b = impl_for_tuple!(
+                    @nth [$($params0),*]
+                    [
+                        other.0, other.1, other.2, other.3, other.4,
+                        other.5, other.6, other.7, other.8, other.9,
+                        other.10, other.11
+                    ]
+                );
+                let g = |a| f(a, b);
+                Ok(impl_for_tuple!(@wrap_try g[$($params0),*] $param [$($params1),*] [
+                    self.0, self.1, self.2, self.3, self.4, self.5, self.6,
+                    self.7, self.8, self.9, self.10, self.11
+                ] {}))
+            }
+        }
+
+        impl<A, B, $($params0: Clone,)* $($params1: Clone),*>
+            ParametrizedUnzip<{impl_for_tuple!(@count $($params0),*)}, A, B>
+            for ($($params0,)* (A, B), $($params1),*)
+        {
+            type Left = ($($params0,)* A, $($params1),*);
+            type Right = ($($params0,)* B, $($params1),*);
+            fn param_unzip(self) -> (Self::Left, Self::Right) {
+                let (a, b) = impl_for_tuple!(
+                    @nth [$($params0),*]
+                    [
+                        self.0, self.1, self.2, self.3, self.4,
+                        self.5, self.6, self.7, self.8, self.9,
+                        self.10, self.11
+                    ]
+                );
+                let left = impl_for_tuple!(@put a[$($params0),*] $param [$($params1),*] [
+                    Clone::clone(&self.0), Clone::clone(&self.1), Clone::clone(&self.2),
+                    Clone::clone(&self.3), Clone::clone(&self.4), Clone::clone(&self.5),
+                    Clone::clone(&self.6), Clone::clone(&self.7), Clone::clone(&self.8),
+                    Clone::clone(&self.9), Clone::clone(&self.10), Clone::clone(&self.11)
+                ] {});
+                let right = impl_for_tuple!(@put b[$($params0),*] $param [$($params1),*] [
+                    self.0, self.1, self.2, self.3, self.4, self.5, self.6,
+                    self.7, self.8, self.9, self.10, self.11
+                ] {});
+                (left, right)
+            }
+        }
+
+        impl<$($params0,)* $param $(,$params1)*>
+            ParametrizedPath<{impl_for_tuple!(@count $($params0),*)}>
+            for ($($params0,)* $param, $($params1),*)
+        {
+            type PathIter<'a> = core::iter::Once<ParamPath>
+            where
+                Self: 'a;
+
+            fn param_path_iter(&self) -> Self::PathIter<'_> {
+                core::iter::once(ParamPath::from(vec![PathSegment::TupleField(
+                    impl_for_tuple!(@count $($params0),*),
+                )]))
+            }
+            fn param_get_path_prefix<'a, 'p>(
+                &'a self,
+                path: &'p [PathSegment],
+            ) -> Option<(&'a Self::Item, &'p [PathSegment])> {
+                match path {
+                    [PathSegment::TupleField(i), rest @ ..]
+                        if *i == impl_for_tuple!(@count $($params0),*) =>
+                    {
+                        Some((
+                            impl_for_tuple!(
+                                @nth [$($params0),*]
+                                [
+                                    &self.0, &self.1, &self.2, &self.3, &self.4, &self.5, &self.6,
+                                    &self.7, &self.8, &self.9, &self.10, &self.11
+                                ]
+                            ),
+                            rest,
+                        ))
+                    }
+                    _ => None,
+                }
+            }
+        }
+
+        impl<$($params0,)* $param $(,$params1)*>
+            ParametrizedDiff<{impl_for_tuple!(@count $($params0),*)}>
+            for ($($params0,)* $param, $($params1),*)
+        {
+            fn param_pair_by<'a>(
+                &'a self,
+                other: &'a Self,
+                keep: &mut dyn FnMut(&Self::Item, &Self::Item) -> bool,
+            ) -> Vec<ParamDiff<&'a Self::Item>> {
+                let [left, right] = [self, other].map(|value| {
+                    impl_for_tuple!(
+                        @nth [$($params0),*]
+                        [
+                            &value.0, &value.1, &value.2, &value.3, &value.4, &value.5,
+                            &value.6, &value.7, &value.8, &value.9, &value.10, &value.11
+                        ]
+                    )
+                });
+                if !keep(left, right) {
+                    return Vec::new();
+                }
+                vec![ParamDiff::Value {
+                    path: ParamPath::from(vec![PathSegment::TupleField(
+                        impl_for_tuple!(@count $($params0),*),
+                    )]),
+                    left,
+                    right,
+                }]
+            }
+        }
+
+        impl<$($params0,)* $param $(,$params1)*>
+            ParametrizedUpdate<{impl_for_tuple!(@count $($params0),*)}>
+            for ($($params0,)* $param, $($params1),*)
+        where
+            $param: Default,
+        {
+            fn param_update(&mut self, f: impl FnMut(Self::Item) -> Self::Item) {
+                _imp::update_in_place(
+                    impl_for_tuple!(
+                        @nth [$($params0),*]
+                        [
+                            &mut self.0, &mut self.1, &mut self.2, &mut self.3,
+                            &mut self.4, &mut self.5, &mut self.6, &mut self.7,
+                            &mut self.8, &mut self.9, &mut self.10, &mut self.11
+                        ]
+                    ),
+                    f,
+                )
+            }
+        }
+    };
+}
+/// Implements [`ParametrizedBiMap`], [`ParametrizedUnion`] and
+/// [`ParametrizedUnionMut`] for every pair of positions in the tuple of the
+/// given types.
+macro_rules! impl_pairs_for_tuple {
+    (@first [$($l:ident),*] []) => {};
+    (@first [$($l:ident),*] [$a:ident $(,$rest:ident)*]) => {
+        impl_pairs_for_tuple!(@second [$($l),*] $a [] [$($rest),*]);
+        impl_pairs_for_tuple!(@first [$($l,)* $a] [$($rest),*]);
+    };
+    (@second [$($l:ident),*] $a:ident [$($m:ident),*] []) => {};
+    (@second [$($l:ident),*] $a:ident [$($m:ident),*] [$b:ident $(,$r:ident)*]) => {
+        impl<$($l,)* $a, $($m,)* $b, $($r,)* K0, K1> ParametrizedBiMap<
+            {impl_for_tuple!(@count $($l),*)},
+            {impl_for_tuple!(@count $($l,)* $a $(,$m)*)},
+            K0,
+            K1,
+        > for ($($l,)* $a, $($m,)* $b, $($r,)*)
+        {
+            type Mapped = ($($l,)* K0, $($m,)* K1, $($r,)*);
+            #[allow(non_snake_case)]
+            fn param_bimap(
+                self,
+                mut f0: impl FnMut($a) -> K0,
+                mut f1: impl FnMut($b) -> K1,
+            ) -> Self::Mapped {
+                let ($($l,)* a, $($m,)* b, $($r,)*) = self;
+                ($($l,)* f0(a), $($m,)* f1(b), $($r,)*)
+            }
+        }
+        impl<$($l,)* $a, $($m,)* $b, $($r,)*> ParametrizedUnion<
+            {impl_for_tuple!(@count $($l),*)},
+            {impl_for_tuple!(@count $($l,)* $a $(,$m)*)},
+        > for ($($l,)* $a, $($m,)* $b, $($r,)*)
+        {
+            type UnionIter<'a> = core::array::IntoIter<Either<&'a $a, &'a $b>, 2>
+            where
+                (Self, $a, $b): 'a;
+            #[allow(non_snake_case, unused_variables)]
+            fn param_iter_either<'a>(&'a self) -> Self::UnionIter<'a>
+            where
+                $a: 'a,
+                $b: 'a,
+            {
+                let ($($l,)* a, $($m,)* b, $($r,)*) = self;
+                [Either::Left(a), Either::Right(b)].into_iter()
+            }
+        }
+        impl<$($l,)* $a, $($m,)* $b, $($r,)*> ParametrizedUnionMut<
+            {impl_for_tuple!(@count $($l),*)},
+            {impl_for_tuple!(@count $($l,)* $a $(,$m)*)},
+        > for ($($l,)* $a, $($m,)* $b, $($r,)*)
+        {
+            type UnionIterMut<'a> = core::array::IntoIter<Either<&'a mut $a, &'a mut $b>, 2>
+            where
+                (Self, $a, $b): 'a;
+            #[allow(non_snake_case, unused_variables)]
+            fn param_iter_mut_either<'a>(&'a mut self) -> Self::UnionIterMut<'a>
+            where
+                $a: 'a,
+                $b: 'a,
+            {
+                let ($($l,)* a, $($m,)* b, $($r,)*) = self;
+                [Either::Left(a), Either::Right(b)].into_iter()
+            }
+        }
+        impl_pairs_for_tuple!(@second [$($l),*] $a [$($m,)* $b] [$($r),*]);
+    };
+    ($($t:ident),*) => {
+        impl_pairs_for_tuple!(@first [] [$($t),*]);
     };
 }
+
 impl_for_tuple!([] T []);
 impl_for_tuple!([] T [R0]);
 impl_for_tuple!([] T [R0, R1]);
 impl_for_tuple!([L0] T []);
 impl_for_tuple!([L0] T [R0]);
 impl_for_tuple!([L0, L1] T []);
+impl_pairs_for_tuple!(T0, T1);
+impl_pairs_for_tuple!(T0, T1, T2);
 
 #[cfg(feature = "large-tuples")]
 mod large_tuples {
@@ -381,6 +2354,15 @@ mod large_tuples {
     impl_for_tuple!([L0, L1, L2, L3, L4, L5, L6, L7, L8, L9] T []);
     impl_for_tuple!([L0, L1, L2, L3, L4, L5, L6, L7, L8, L9] T [R0]);
     impl_for_tuple!([L0, L1, L2, L3, L4, L5, L6, L7, L8, L9, L10] T []);
+    impl_pairs_for_tuple!(T0, T1, T2, T3);
+    impl_pairs_for_tuple!(T0, T1, T2, T3, T4);
+    impl_pairs_for_tuple!(T0, T1, T2, T3, T4, T5);
+    impl_pairs_for_tuple!(T0, T1, T2, T3, T4, T5, T6);
+    impl_pairs_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
+    impl_pairs_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8);
+    impl_pairs_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9);
+    impl_pairs_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10);
+    impl_pairs_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7, T8, T9, T10, T11);
 }
 
 macro_rules! emit_impl_trait {
@@ -413,6 +2395,26 @@ macro_rules! emit_impl_trait {
             fn param_iter<$lt>(& $lt $self_val) -> Self::Iter<$lt> where Self::Item: $lt {
                 $($param_iter)*
             }
+            fn param_for_each<$lt>(& $lt $self_val, mut f: impl FnMut(& $lt Self::Item))
+            where
+                Self::Item: $lt,
+            {
+                for item in <Self as Parametrized<$n>>::param_iter($self_val) {
+                    f(item)
+                }
+            }
+            fn param_try_for_each<$lt, B>(
+                & $lt $self_val,
+                mut f: impl FnMut(& $lt Self::Item) -> core::ops::ControlFlow<B>,
+            ) -> core::ops::ControlFlow<B>
+            where
+                Self::Item: $lt,
+            {
+                for item in <Self as Parametrized<$n>>::param_iter($self_val) {
+                    f(item)?;
+                }
+                core::ops::ControlFlow::Continue(())
+            }
         }
 
     };
@@ -436,6 +2438,14 @@ macro_rules! emit_impl_trait {
             fn param_iter_mut<$lt>(& $lt mut $self_val) -> Self::IterMut<$lt> where Self::Item: $lt {
                 $($param_iter_mut)*
             }
+            fn param_for_each_mut<$lt>(& $lt mut $self_val, mut f: impl FnMut(& $lt mut Self::Item))
+            where
+                Self::Item: $lt,
+            {
+                for item in <Self as ParametrizedIterMut<$n>>::param_iter_mut($self_val) {
+                    f(item)
+                }
+            }
         }
         emit_impl_trait!(
             [$($acc)*]
@@ -468,6 +2478,13 @@ macro_rules! emit_impl_trait {
             fn param_into_iter($self_val) -> Self::IntoIter {
                 $($param_into_iter)*
             }
+            fn param_fold<B>($self_val, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B {
+                let mut acc = init;
+                for item in <Self as ParametrizedIntoIter<$n>>::param_into_iter($self_val) {
+                    acc = f(acc, item);
+                }
+                acc
+            }
         }
         emit_impl_trait!(
             [$($acc)*]
@@ -495,6 +2512,14 @@ macro_rules! emit_impl_trait {
             T = $arg_ty:ident,
             Mapped = $mapped_ty:ty,
             param_map = {$($param_map:tt)*},
+            param_map_ref = {$($param_map_ref:tt)*},
+            other = $other_val:ident,
+            U = $other_arg_ty:ident,
+            Other = $other_ty:ty,
+            param_zip_with = {$($param_zip_with:tt)*},
+            Unzipped = $unzipped_ty:ty,
+            param_unzip = {$($param_unzip:tt)*},
+            param_filter_map = {$($param_filter_map:tt)*},
         }
     ) => {
         impl<$($tpar)*,$arg_ty> ParametrizedMap<$n, $arg_ty> for $self_ty
@@ -504,6 +2529,56 @@ macro_rules! emit_impl_trait {
                 $($param_map)*
             }
         }
+        // `param_map` collects, which also collects `Result`s.
+        impl<$($tpar)*,$arg_ty> ParametrizedTryMap<$n, $arg_ty> for $self_ty
+        {
+            fn try_param_map<E>(
+                $self_val,
+                $f_val: impl FnMut(Self::Item) -> Result<$arg_ty, E>,
+            ) -> Result<Self::Mapped, E> {
+                $($param_map)*
+            }
+        }
+        impl<$($tpar)*,$arg_ty> ParametrizedMapRef<$n, $arg_ty> for $self_ty
+        {
+            type Mapped = $mapped_ty;
+            fn param_map_ref<'a>(
+                &'a $self_val,
+                $f_val: impl FnMut(&'a Self::Item) -> $arg_ty,
+            ) -> Self::Mapped
+            where
+                Self::Item: 'a,
+            {
+                $($param_map_ref)*
+            }
+        }
+        impl<$($tpar)*,$other_arg_ty,$arg_ty> ParametrizedZip<$n, $other_ty, $arg_ty> for $self_ty
+        {
+            fn try_param_zip_with<E: From<ShapeMismatch>>(
+                $self_val,
+                $other_val: $other_ty,
+                mut $f_val: impl FnMut(Self::Item, $other_arg_ty) -> Result<$arg_ty, E>,
+            ) -> Result<Self::Mapped, E> {
+                $($param_zip_with)*
+            }
+        }
+        impl<$($tpar)*,$arg_ty> ParametrizedFilterMap<$n, $arg_ty> for $self_ty
+        {
+            fn param_filter_map(
+                $self_val,
+                $f_val: impl FnMut(Self::Item) -> Option<$arg_ty>,
+            ) -> Self::Mapped {
+                $($param_filter_map)*
+            }
+        }
+        impl<$arg_ty,$other_arg_ty> ParametrizedUnzip<$n, $arg_ty, $other_arg_ty> for $unzipped_ty
+        {
+            type Left = $mapped_ty;
+            type Right = $other_ty;
+            fn param_unzip($self_val) -> (Self::Left, Self::Right) {
+                $($param_unzip)*
+            }
+        }
         emit_impl_trait!(
             [$($acc)*]
             impl_generics = [$($tpar)*],
@@ -522,7 +2597,11 @@ macro_rules! impl_all {
     ($(
         [$($tpar:tt)*]
         $($fn:ident),*
-        for $self_ty:ty $(,T = $arg_ty:ident, Mapped = $mapped_ty:ty)? ;
+        for $self_ty:ty $(
+            ,T = $arg_ty:ident, Mapped = $mapped_ty:ty,
+            U = $other_arg_ty:ident, Other = $other_ty:ty,
+            Unzipped = $unzipped_ty:ty
+        )? ;
     )*) => {
         $(
             emit_impl_trait!(
@@ -555,21 +2634,458 @@ macro_rules! impl_all {
                     T = $arg_ty,
                     Mapped = $mapped_ty,
                     param_map = { <Self as IntoIterator>::into_iter(self).map(f).collect() },
+                    param_map_ref = { <&'a Self as IntoIterator>::into_iter(self).map(f).collect() },
+                    other = other,
+                    U = $other_arg_ty,
+                    Other = $other_ty,
+                    param_zip_with = {
+                        if self.len() != other.len() {
+                            return Err(ShapeMismatch::Length {
+                                expected: self.len(),
+                                found: other.len(),
+                            }.into());
+                        }
+                        <Self as IntoIterator>::into_iter(self)
+                            .zip(other)
+                            .map(|(a, b)| f(a, b))
+                            .collect()
+                    },
+                    Unzipped = $unzipped_ty,
+                    param_unzip = { <Self as IntoIterator>::into_iter(self).unzip() },
+                    param_filter_map = { <Self as IntoIterator>::into_iter(self).filter_map(f).collect() },
                 })?
             );
         )*
     };
 }
 
-impl_all! {
-    [T] map, into_iter, iter_mut for Vec<T>, T = M, Mapped = Vec<M>;
-    [T] into_iter for std::collections::BTreeSet<T>;
-    [T] into_iter for std::collections::HashSet<T>;
-    [T] into_iter for std::collections::BinaryHeap<T>;
-    [T] map, into_iter, iter_mut for std::collections::LinkedList<T>,
-        T = M, Mapped = std::collections::LinkedList<M>;
-    [T] map, into_iter, iter_mut for std::collections::VecDeque<T>,
-        T = M, Mapped = std::collections::VecDeque<M>;
+/// Implements [`ParametrizedUpdate`] by updating the values yielded by
+/// [`ParametrizedIterMut::param_iter_mut()`] in place, which must be `Default`.
+macro_rules! impl_update_by_iter_mut {
+    ($([$($tpar:tt)*] $param:literal for $self_ty:ty;)*) => {
+        $(
+            impl<$($tpar)*> ParametrizedUpdate<$param> for $self_ty {
+                fn param_update(
+                    &mut self,
+                    mut f: impl FnMut(<Self as Parametrized<$param>>::Item)
+                        -> <Self as Parametrized<$param>>::Item,
+                ) {
+                    <Self as ParametrizedIterMut<$param>>::param_iter_mut(self)
+                        .for_each(|item| _imp::update_in_place(item, &mut f))
+                }
+            }
+        )*
+    };
+}
+
+impl_all! {
+    [T] map, into_iter, iter_mut for Vec<T>, T = M, Mapped = Vec<M>, U = N, Other = Vec<N>,
+        Unzipped = Vec<(M, N)>;
+    [T] into_iter for std::collections::BTreeSet<T>;
+    [T] into_iter for std::collections::HashSet<T>;
+    [T] into_iter for std::collections::BinaryHeap<T>;
+    [T] map, into_iter, iter_mut for std::collections::LinkedList<T>,
+        T = M, Mapped = std::collections::LinkedList<M>,
+        U = N, Other = std::collections::LinkedList<N>,
+        Unzipped = std::collections::LinkedList<(M, N)>;
+    [T] map, into_iter, iter_mut for std::collections::VecDeque<T>,
+        T = M, Mapped = std::collections::VecDeque<M>,
+        U = N, Other = std::collections::VecDeque<N>,
+        Unzipped = std::collections::VecDeque<(M, N)>;
+}
+
+impl<T> ParametrizedRetain<0> for Vec<T> {
+    fn param_retain(&mut self, f: impl FnMut(&Self::Item) -> bool) {
+        self.retain(f)
+    }
+}
+impl<T> ParametrizedRetain<0> for std::collections::VecDeque<T> {
+    fn param_retain(&mut self, f: impl FnMut(&Self::Item) -> bool) {
+        self.retain(f)
+    }
+}
+impl<T> ParametrizedRetain<0> for std::collections::LinkedList<T> {
+    fn param_retain(&mut self, mut f: impl FnMut(&Self::Item) -> bool) {
+        // `LinkedList::retain()` is unstable.
+        *self = core::mem::take(self)
+            .into_iter()
+            .filter(|item| f(item))
+            .collect();
+    }
+}
+
+impl_update_by_iter_mut! {
+    [const N: usize, T: Default] 0 for [T; N];
+    [T: Default] 0 for [T];
+    [T: Default] 0 for Box<T>;
+    [T: Default, E] 0 for Result<T, E>;
+    [T, E: Default] 1 for Result<T, E>;
+}
+
+impl<T> ParametrizedUpdate<0> for Vec<T> {
+    fn param_update(&mut self, f: impl FnMut(Self::Item) -> Self::Item) {
+        // Collecting the mapped `IntoIter` reuses its allocation.
+        *self = core::mem::take(self).into_iter().map(f).collect();
+    }
+}
+impl<T> ParametrizedUpdate<0> for std::collections::VecDeque<T> {
+    fn param_update(&mut self, f: impl FnMut(Self::Item) -> Self::Item) {
+        let values = Vec::from(core::mem::take(self));
+        *self = values.into_iter().map(f).collect::<Vec<_>>().into();
+    }
+}
+impl<T> ParametrizedUpdate<0> for std::collections::LinkedList<T> {
+    fn param_update(&mut self, f: impl FnMut(Self::Item) -> Self::Item) {
+        *self = core::mem::take(self).into_iter().map(f).collect();
+    }
+}
+impl<T> ParametrizedUpdate<0> for core::marker::PhantomData<T> {
+    fn param_update(&mut self, _: impl FnMut(Self::Item) -> Self::Item) {}
+}
+impl<T> ParametrizedUpdate<0> for Option<T> {
+    fn param_update(&mut self, mut f: impl FnMut(Self::Item) -> Self::Item) {
+        if let Some(value) = self.take() {
+            *self = Some(f(value));
+        }
+    }
+}
+impl<K: Ord, V> ParametrizedUpdate<1> for std::collections::BTreeMap<K, V> {
+    fn param_update(&mut self, mut f: impl FnMut(Self::Item) -> Self::Item) {
+        *self = core::mem::take(self)
+            .into_iter()
+            .map(|(k, v)| (k, f(v)))
+            .collect();
+    }
+}
+impl<K: Eq + Hash, V> ParametrizedUpdate<1> for std::collections::HashMap<K, V> {
+    fn param_update(&mut self, mut f: impl FnMut(Self::Item) -> Self::Item) {
+        let hasher = self.hasher().clone();
+        let mut values = std::collections::HashMap::with_capacity_and_hasher(self.len(), hasher);
+        values.extend(core::mem::take(self).into_iter().map(|(k, v)| (k, f(v))));
+        *self = values;
+    }
+}
+
+impl<const PARAM: usize, T> ParametrizedUpdate<PARAM> for &mut T
+where
+    T: ParametrizedUpdate<PARAM>,
+{
+    fn param_update(&mut self, f: impl FnMut(Self::Item) -> Self::Item)
+    where
+        Self::Item: Sized,
+    {
+        <T as ParametrizedUpdate<PARAM>>::param_update(self, f)
+    }
+}
+
+/// Implements [`ParametrizedPath`] for collections, whose values are found by
+/// their positions in [`Parametrized::param_iter()`], given by the segment
+/// `$segment`.
+macro_rules! impl_path_by_index {
+    ($([$($tpar:tt)*] $param:literal for $self_ty:ty, $segment:ident;)*) => {
+        $(
+            impl<$($tpar)*> ParametrizedPath<$param> for $self_ty {
+                type PathIter<'a> = core::iter::Map<core::ops::Range<usize>, fn(usize) -> ParamPath>
+                where
+                    Self: 'a;
+
+                fn param_path_iter(&self) -> Self::PathIter<'_> {
+                    (0..<Self as Parametrized<$param>>::param_len(self))
+                        .map(|i| ParamPath::from(vec![PathSegment::$segment(i)]))
+                }
+                fn param_get_path_prefix<'a, 'p>(
+                    &'a self,
+                    path: &'p [PathSegment],
+                ) -> Option<(&'a <Self as Parametrized<$param>>::Item, &'p [PathSegment])> {
+                    match path {
+                        [PathSegment::$segment(i), rest @ ..] => {
+                            Some((<Self as Parametrized<$param>>::param_get(self, *i)?, rest))
+                        }
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+/// Implements [`ParametrizedPath`] for types holding at most one value, which
+/// add nothing to the path.
+macro_rules! impl_path_transparent {
+    ($([$($tpar:tt)*] $param:literal for $self_ty:ty;)*) => {
+        $(
+            impl<$($tpar)*> ParametrizedPath<$param> for $self_ty {
+                type PathIter<'a> = core::option::IntoIter<ParamPath>
+                where
+                    Self: 'a;
+
+                fn param_path_iter(&self) -> Self::PathIter<'_> {
+                    <Self as Parametrized<$param>>::param_iter(self)
+                        .next()
+                        .map(|_| ParamPath::new())
+                        .into_iter()
+                }
+                fn param_get_path_prefix<'a, 'p>(
+                    &'a self,
+                    path: &'p [PathSegment],
+                ) -> Option<(&'a <Self as Parametrized<$param>>::Item, &'p [PathSegment])> {
+                    Some((<Self as Parametrized<$param>>::param_iter(self).next()?, path))
+                }
+            }
+        )*
+    };
+}
+
+impl_path_by_index! {
+    [T] 0 for Vec<T>, Index;
+    [T] 0 for std::collections::VecDeque<T>, Index;
+    [T] 0 for std::collections::LinkedList<T>, Index;
+    [T] 0 for std::collections::BinaryHeap<T>, Index;
+    [const N: usize, T] 0 for [T; N], Index;
+    [T] 0 for [T], Index;
+    [T] 0 for std::collections::BTreeSet<T>, Entry;
+    [T] 0 for std::collections::HashSet<T>, Entry;
+    [K, V] 0 for std::collections::BTreeMap<K, V>, Entry;
+    [K, V] 1 for std::collections::BTreeMap<K, V>, Entry;
+    [K, V] 0 for std::collections::HashMap<K, V>, Entry;
+    [K, V] 1 for std::collections::HashMap<K, V>, Entry;
+}
+
+impl_path_transparent! {
+    [T] 0 for Box<T>;
+    [T: ?Sized] 0 for core::marker::PhantomData<T>;
+    [T, E] 0 for Result<T, E>;
+    [T, E] 1 for Result<T, E>;
+    [T] 0 for Option<T>;
+}
+
+impl<const PARAM: usize, T> ParametrizedPath<PARAM> for &T
+where
+    T: ParametrizedPath<PARAM>,
+{
+    type PathIter<'a> = T::PathIter<'a>
+    where
+        Self: 'a;
+
+    fn param_path_iter(&self) -> Self::PathIter<'_> {
+        <T as ParametrizedPath<PARAM>>::param_path_iter(self)
+    }
+    fn param_get_path_prefix<'a, 'p>(
+        &'a self,
+        path: &'p [PathSegment],
+    ) -> Option<(&'a Self::Item, &'p [PathSegment])> {
+        <T as ParametrizedPath<PARAM>>::param_get_path_prefix(self, path)
+    }
+}
+
+impl<const PARAM: usize, T> ParametrizedPath<PARAM> for &mut T
+where
+    T: ParametrizedPath<PARAM>,
+{
+    type PathIter<'a> = T::PathIter<'a>
+    where
+        Self: 'a;
+
+    fn param_path_iter(&self) -> Self::PathIter<'_> {
+        <T as ParametrizedPath<PARAM>>::param_path_iter(self)
+    }
+    fn param_get_path_prefix<'a, 'p>(
+        &'a self,
+        path: &'p [PathSegment],
+    ) -> Option<(&'a Self::Item, &'p [PathSegment])> {
+        <T as ParametrizedPath<PARAM>>::param_get_path_prefix(self, path)
+    }
+}
+
+/// Implements [`ParametrizedDiff`] for sequences, whose values are paired by
+/// their positions in [`Parametrized::param_iter()`].
+macro_rules! impl_diff_by_index {
+    ($([$($tpar:tt)*] $param:literal for $self_ty:ty;)*) => {
+        $(
+            impl<$($tpar)*> ParametrizedDiff<$param> for $self_ty {
+                fn param_pair_by<'a>(
+                    &'a self,
+                    other: &'a Self,
+                    keep: &mut dyn FnMut(
+                        &<Self as Parametrized<$param>>::Item,
+                        &<Self as Parametrized<$param>>::Item,
+                    ) -> bool,
+                ) -> Vec<ParamDiff<&'a <Self as Parametrized<$param>>::Item>> {
+                    let expected = <Self as Parametrized<$param>>::param_len(self);
+                    let found = <Self as Parametrized<$param>>::param_len(other);
+                    let mut diffs = Vec::new();
+                    if expected != found {
+                        diffs.push(ParamDiff::Shape {
+                            path: ParamPath::new(),
+                            mismatch: ShapeMismatch::Length { expected, found },
+                        });
+                    }
+                    diffs.extend(
+                        <Self as Parametrized<$param>>::param_iter(self)
+                            .zip(<Self as Parametrized<$param>>::param_iter(other))
+                            .enumerate()
+                            .filter(|(_, (left, right))| keep(left, right))
+                            .map(|(i, (left, right))| ParamDiff::Value {
+                                path: ParamPath::from(vec![PathSegment::Index(i)]),
+                                left,
+                                right,
+                            }),
+                    );
+                    diffs
+                }
+            }
+        )*
+    };
+}
+
+/// Implements [`ParametrizedDiff`] for types holding at most one value, which
+/// add nothing to the path. The variants holding a value and holding none are
+/// named for [`ShapeMismatch::Variant`].
+macro_rules! impl_diff_transparent {
+    ($([$($tpar:tt)*] $param:literal for $self_ty:ty, [$some:literal, $none:literal];)*) => {
+        $(
+            impl<$($tpar)*> ParametrizedDiff<$param> for $self_ty {
+                fn param_pair_by<'a>(
+                    &'a self,
+                    other: &'a Self,
+                    keep: &mut dyn FnMut(
+                        &<Self as Parametrized<$param>>::Item,
+                        &<Self as Parametrized<$param>>::Item,
+                    ) -> bool,
+                ) -> Vec<ParamDiff<&'a <Self as Parametrized<$param>>::Item>> {
+                    let name = |value: &Option<_>| if value.is_some() { $some } else { $none };
+                    match (
+                        <Self as Parametrized<$param>>::param_iter(self).next(),
+                        <Self as Parametrized<$param>>::param_iter(other).next(),
+                    ) {
+                        (Some(left), Some(right)) if keep(left, right) => vec![ParamDiff::Value {
+                            path: ParamPath::new(),
+                            left,
+                            right,
+                        }],
+                        (Some(_), Some(_)) | (None, None) => Vec::new(),
+                        (left, right) => vec![ParamDiff::Shape {
+                            path: ParamPath::new(),
+                            mismatch: ShapeMismatch::Variant {
+                                expected: name(&left),
+                                found: name(&right),
+                            },
+                        }],
+                    }
+                }
+            }
+        )*
+    };
+}
+
+/// Implements [`ParametrizedDiff`] for maps and sets, whose values are paired
+/// by the keys of their entries. `$entries` iterates the keys with the values
+/// of `$s`, and `$get` looks up the value at the key `$k` in `$s`.
+macro_rules! impl_diff_by_key {
+    ($(
+        [$($tpar:tt)*] $param:literal for $self_ty:ty,
+        |$s:ident| $entries:expr, |$k:ident| $get:expr;
+    )*) => {
+        $(
+            impl<$($tpar)*> ParametrizedDiff<$param> for $self_ty {
+                fn param_pair_by<'a>(
+                    &'a self,
+                    other: &'a Self,
+                    keep: &mut dyn FnMut(
+                        &<Self as Parametrized<$param>>::Item,
+                        &<Self as Parametrized<$param>>::Item,
+                    ) -> bool,
+                ) -> Vec<ParamDiff<&'a <Self as Parametrized<$param>>::Item>> {
+                    let path = |key| ParamPath::from(vec![PathSegment::Key(format!("{key:?}"))]);
+                    let mut diffs = Vec::new();
+                    let $s = self;
+                    for ($k, left) in $entries {
+                        let $s = other;
+                        match $get {
+                            Some(right) if keep(left, right) => diffs.push(ParamDiff::Value {
+                                path: path($k),
+                                left,
+                                right,
+                            }),
+                            Some(_) => (),
+                            None => diffs.push(ParamDiff::Shape {
+                                path: path($k),
+                                mismatch: ShapeMismatch::MissingEntry,
+                            }),
+                        }
+                    }
+                    let $s = other;
+                    for ($k, _) in $entries {
+                        let $s = self;
+                        if $get.is_none() {
+                            diffs.push(ParamDiff::Shape {
+                                path: path($k),
+                                mismatch: ShapeMismatch::ExtraEntry,
+                            });
+                        }
+                    }
+                    diffs
+                }
+            }
+        )*
+    };
+}
+
+impl_diff_by_index! {
+    [T] 0 for Vec<T>;
+    [T] 0 for std::collections::VecDeque<T>;
+    [T] 0 for std::collections::LinkedList<T>;
+    [const N: usize, T] 0 for [T; N];
+    [T] 0 for [T];
+}
+
+impl_diff_by_key! {
+    [T: Ord + core::fmt::Debug] 0 for std::collections::BTreeSet<T>,
+        |s| s.iter().map(|t| (t, t)), |t| s.get(t);
+    [T: Eq + core::hash::Hash + core::fmt::Debug] 0 for std::collections::HashSet<T>,
+        |s| s.iter().map(|t| (t, t)), |t| s.get(t);
+    [K: Ord + core::fmt::Debug, V] 0 for std::collections::BTreeMap<K, V>,
+        |m| m.keys().map(|k| (k, k)), |k| m.get_key_value(k).map(|(k, _)| k);
+    [K: Ord + core::fmt::Debug, V] 1 for std::collections::BTreeMap<K, V>,
+        |m| m.iter(), |k| m.get(k);
+    [K: Eq + core::hash::Hash + core::fmt::Debug, V] 0 for std::collections::HashMap<K, V>,
+        |m| m.keys().map(|k| (k, k)), |k| m.get_key_value(k).map(|(k, _)| k);
+    [K: Eq + core::hash::Hash + core::fmt::Debug, V] 1 for std::collections::HashMap<K, V>,
+        |m| m.iter(), |k| m.get(k);
+}
+
+impl_diff_transparent! {
+    [T] 0 for Box<T>, ["Box", "Box"];
+    [T: ?Sized] 0 for core::marker::PhantomData<T>, ["PhantomData", "PhantomData"];
+    [T, E] 0 for Result<T, E>, ["Ok", "Err"];
+    [T, E] 1 for Result<T, E>, ["Err", "Ok"];
+    [T] 0 for Option<T>, ["Some", "None"];
+}
+
+impl<const PARAM: usize, T> ParametrizedDiff<PARAM> for &T
+where
+    T: ParametrizedDiff<PARAM>,
+{
+    fn param_pair_by<'a>(
+        &'a self,
+        other: &'a Self,
+        keep: &mut dyn FnMut(&Self::Item, &Self::Item) -> bool,
+    ) -> Vec<ParamDiff<&'a Self::Item>> {
+        <T as ParametrizedDiff<PARAM>>::param_pair_by(self, other, keep)
+    }
+}
+
+impl<const PARAM: usize, T> ParametrizedDiff<PARAM> for &mut T
+where
+    T: ParametrizedDiff<PARAM>,
+{
+    fn param_pair_by<'a>(
+        &'a self,
+        other: &'a Self,
+        keep: &mut dyn FnMut(&Self::Item, &Self::Item) -> bool,
+    ) -> Vec<ParamDiff<&'a Self::Item>> {
+        <T as ParametrizedDiff<PARAM>>::param_pair_by(self, other, keep)
+    }
 }
 
 impl<const N: usize, T> ParametrizedIntoIter<0> for [T; N] {
@@ -577,16 +3093,37 @@ impl<const N: usize, T> ParametrizedIntoIter<0> for [T; N] {
     fn param_into_iter(self) -> Self::IntoIter {
         <Self as IntoIterator>::into_iter(self)
     }
+    fn param_fold<B>(self, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        let mut acc = init;
+        for item in self {
+            acc = f(acc, item);
+        }
+        acc
+    }
 }
 impl<const N: usize, T> ParametrizedIterMut<0> for [T; N] {
-    type IterMut<'a> = <&'a mut Self as IntoIterator>::IntoIter
+    type IterMut<'a> = ArrayIter<<&'a mut Self as IntoIterator>::IntoIter, N>
     where
         (Self, Self::Item): 'a;
     fn param_iter_mut<'a>(&'a mut self) -> Self::IterMut<'a>
     where
         Self::Item: 'a,
     {
-        <&'a mut Self as IntoIterator>::into_iter(self)
+        ArrayIter(<&'a mut Self as IntoIterator>::into_iter(self))
+    }
+    fn param_get_mut(&mut self, n: usize) -> Option<&mut T> {
+        self.get_mut(n)
+    }
+    fn param_for_each_mut<'a>(&'a mut self, mut f: impl FnMut(&'a mut Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        for item in self {
+            f(item)
+        }
     }
 }
 impl<const N: usize, T> Parametrized<0> for [T; N] {
@@ -596,13 +3133,36 @@ impl<const N: usize, T> Parametrized<0> for [T; N] {
     fn param_len(&self) -> usize {
         self.len()
     }
-    type Iter<'a> = <&'a Self as IntoIterator>::IntoIter
+    type Iter<'a> = ArrayIter<<&'a Self as IntoIterator>::IntoIter, N>
     where (Self, Self::Item): 'a;
     fn param_iter<'a>(&'a self) -> Self::Iter<'a>
     where
         Self::Item: 'a,
     {
-        <&'a Self as IntoIterator>::into_iter(self)
+        ArrayIter(<&'a Self as IntoIterator>::into_iter(self))
+    }
+    fn param_get(&self, n: usize) -> Option<&T> {
+        self.get(n)
+    }
+    fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        for item in self {
+            f(item)
+        }
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        for item in self {
+            f(item)?;
+        }
+        core::ops::ControlFlow::Continue(())
     }
 }
 
@@ -611,6 +3171,12 @@ impl<T> ParametrizedIntoIter<0> for Box<T> {
     fn param_into_iter(self) -> Self::IntoIter {
         core::iter::once(*self)
     }
+    fn param_fold<B>(self, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        f(init, *self)
+    }
 }
 impl<T> ParametrizedIterMut<0> for Box<T> {
     type IterMut<'a> = core::iter::Once<&'a mut T>
@@ -622,6 +3188,12 @@ impl<T> ParametrizedIterMut<0> for Box<T> {
     {
         core::iter::once(&mut *self)
     }
+    fn param_for_each_mut<'a>(&'a mut self, mut f: impl FnMut(&'a mut Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        f(&mut *self)
+    }
 }
 impl<T> Parametrized<0> for Box<T> {
     type Item = T;
@@ -638,6 +3210,166 @@ impl<T> Parametrized<0> for Box<T> {
     {
         core::iter::once(self.as_ref())
     }
+    fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        f(self.as_ref())
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        f(self.as_ref())
+    }
+}
+
+impl<T, M> ParametrizedMap<0, M> for Box<T> {
+    type Mapped = Box<M>;
+    fn param_map(self, mut f: impl FnMut(Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: Sized,
+    {
+        Box::new(f(*self))
+    }
+}
+impl<T, M> ParametrizedTryMap<0, M> for Box<T> {
+    fn try_param_map<E>(
+        self,
+        mut f: impl FnMut(Self::Item) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        Ok(Box::new(f(*self)?))
+    }
+}
+impl<T, M> ParametrizedMapRef<0, M> for Box<T> {
+    type Mapped = Box<M>;
+    fn param_map_ref<'a>(&'a self, mut f: impl FnMut(&'a Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        Box::new(f(self.as_ref()))
+    }
+}
+impl<T, U, M> ParametrizedZip<0, Box<U>, M> for Box<T> {
+    fn try_param_zip_with<E: From<ShapeMismatch>>(
+        self,
+        other: Box<U>,
+        mut f: impl FnMut(Self::Item, U) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        Ok(Box::new(f(*self, *other)?))
+    }
+}
+impl<A, B> ParametrizedUnzip<0, A, B> for Box<(A, B)> {
+    type Left = Box<A>;
+    type Right = Box<B>;
+    fn param_unzip(self) -> (Self::Left, Self::Right) {
+        let (a, b) = *self;
+        (Box::new(a), Box::new(b))
+    }
+}
+
+impl<T: ?Sized> Parametrized<0> for core::marker::PhantomData<T> {
+    type Item = T;
+    const MIN_LEN: usize = 0;
+    const MAX_LEN: Option<usize> = Some(0);
+    fn param_len(&self) -> usize {
+        0
+    }
+    type Iter<'a> = core::iter::Empty<&'a T>
+    where (Self, Self::Item): 'a;
+    fn param_iter<'a>(&'a self) -> Self::Iter<'a>
+    where
+        Self::Item: 'a,
+    {
+        core::iter::empty()
+    }
+    fn param_for_each<'a>(&'a self, _: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        _: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        core::ops::ControlFlow::Continue(())
+    }
+}
+impl<T: ?Sized> ParametrizedIterMut<0> for core::marker::PhantomData<T> {
+    type IterMut<'a> = core::iter::Empty<&'a mut T>
+    where
+        (Self, Self::Item): 'a;
+    fn param_iter_mut<'a>(&'a mut self) -> Self::IterMut<'a>
+    where
+        Self::Item: 'a,
+    {
+        core::iter::empty()
+    }
+    fn param_for_each_mut<'a>(&'a mut self, _: impl FnMut(&'a mut Self::Item))
+    where
+        Self::Item: 'a,
+    {
+    }
+}
+impl<T> ParametrizedIntoIter<0> for core::marker::PhantomData<T> {
+    type IntoIter = core::iter::Empty<T>;
+    fn param_into_iter(self) -> Self::IntoIter {
+        core::iter::empty()
+    }
+    fn param_fold<B>(self, init: B, _: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        init
+    }
+}
+impl<T, M> ParametrizedMap<0, M> for core::marker::PhantomData<T> {
+    type Mapped = core::marker::PhantomData<M>;
+    fn param_map(self, _: impl FnMut(Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: Sized,
+    {
+        core::marker::PhantomData
+    }
+}
+impl<T, M> ParametrizedTryMap<0, M> for core::marker::PhantomData<T> {
+    fn try_param_map<E>(
+        self,
+        _: impl FnMut(Self::Item) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        Ok(core::marker::PhantomData)
+    }
+}
+impl<T: ?Sized, M> ParametrizedMapRef<0, M> for core::marker::PhantomData<T> {
+    type Mapped = core::marker::PhantomData<M>;
+    fn param_map_ref<'a>(&'a self, _: impl FnMut(&'a Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        core::marker::PhantomData
+    }
+}
+impl<T, U, M> ParametrizedZip<0, core::marker::PhantomData<U>, M> for core::marker::PhantomData<T> {
+    fn try_param_zip_with<E: From<ShapeMismatch>>(
+        self,
+        _: core::marker::PhantomData<U>,
+        _: impl FnMut(Self::Item, U) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        Ok(core::marker::PhantomData)
+    }
+}
+impl<A, B> ParametrizedUnzip<0, A, B> for core::marker::PhantomData<(A, B)> {
+    type Left = core::marker::PhantomData<A>;
+    type Right = core::marker::PhantomData<B>;
+    fn param_unzip(self) -> (Self::Left, Self::Right) {
+        (core::marker::PhantomData, core::marker::PhantomData)
+    }
 }
 
 impl<T, M: Ord> ParametrizedMap<0, M> for std::collections::BTreeSet<T> {
@@ -649,6 +3381,55 @@ impl<T, M: Ord> ParametrizedMap<0, M> for std::collections::BTreeSet<T> {
         self.into_iter().map(f).collect()
     }
 }
+impl<T, M: Ord> ParametrizedTryMap<0, M> for std::collections::BTreeSet<T> {
+    fn try_param_map<E>(
+        self,
+        f: impl FnMut(Self::Item) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        self.into_iter().map(f).collect()
+    }
+}
+impl<T, M: Ord> ParametrizedMapRef<0, M> for std::collections::BTreeSet<T> {
+    type Mapped = std::collections::BTreeSet<M>;
+    fn param_map_ref<'a>(&'a self, f: impl FnMut(&'a Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        self.iter().map(f).collect()
+    }
+}
+impl<T, U, M: Ord> ParametrizedZip<0, std::collections::BTreeSet<U>, M>
+    for std::collections::BTreeSet<T>
+{
+    fn try_param_zip_with<E: From<ShapeMismatch>>(
+        self,
+        other: std::collections::BTreeSet<U>,
+        mut f: impl FnMut(Self::Item, U) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        if self.len() != other.len() {
+            return Err(ShapeMismatch::Length {
+                expected: self.len(),
+                found: other.len(),
+            }.into());
+        }
+        self.into_iter().zip(other).map(|(a, b)| f(a, b)).collect()
+    }
+}
+impl<T: Ord> ParametrizedRetain<0> for std::collections::BTreeSet<T> {
+    fn param_retain(&mut self, f: impl FnMut(&Self::Item) -> bool) {
+        self.retain(f)
+    }
+}
+impl<T, M: Ord> ParametrizedFilterMap<0, M> for std::collections::BTreeSet<T> {
+    fn param_filter_map(self, f: impl FnMut(Self::Item) -> Option<M>) -> Self::Mapped {
+        self.into_iter().filter_map(f).collect()
+    }
+}
+impl<T: Ord> ParametrizedUpdate<0> for std::collections::BTreeSet<T> {
+    fn param_update(&mut self, f: impl FnMut(Self::Item) -> Self::Item) {
+        *self = core::mem::take(self).into_iter().map(f).collect();
+    }
+}
 impl<T, M: Eq + Hash> ParametrizedMap<0, M> for std::collections::HashSet<T> {
     type Mapped = std::collections::HashSet<M>;
 
@@ -659,6 +3440,38 @@ impl<T, M: Eq + Hash> ParametrizedMap<0, M> for std::collections::HashSet<T> {
         self.into_iter().map(f).collect()
     }
 }
+impl<T, M: Eq + Hash> ParametrizedTryMap<0, M> for std::collections::HashSet<T> {
+    fn try_param_map<E>(
+        self,
+        f: impl FnMut(Self::Item) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        self.into_iter().map(f).collect()
+    }
+}
+impl<T, M: Eq + Hash> ParametrizedMapRef<0, M> for std::collections::HashSet<T> {
+    type Mapped = std::collections::HashSet<M>;
+    fn param_map_ref<'a>(&'a self, f: impl FnMut(&'a Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        self.iter().map(f).collect()
+    }
+}
+impl<T> ParametrizedRetain<0> for std::collections::HashSet<T> {
+    fn param_retain(&mut self, f: impl FnMut(&Self::Item) -> bool) {
+        self.retain(f)
+    }
+}
+impl<T, M: Eq + Hash> ParametrizedFilterMap<0, M> for std::collections::HashSet<T> {
+    fn param_filter_map(self, f: impl FnMut(Self::Item) -> Option<M>) -> Self::Mapped {
+        self.into_iter().filter_map(f).collect()
+    }
+}
+impl<T: Eq + Hash> ParametrizedUpdate<0> for std::collections::HashSet<T> {
+    fn param_update(&mut self, f: impl FnMut(Self::Item) -> Self::Item) {
+        *self = core::mem::take(self).into_iter().map(f).collect();
+    }
+}
 impl<T, M: Ord> ParametrizedMap<0, M> for std::collections::BinaryHeap<T> {
     type Mapped = std::collections::BinaryHeap<M>;
 
@@ -669,6 +3482,38 @@ impl<T, M: Ord> ParametrizedMap<0, M> for std::collections::BinaryHeap<T> {
         self.into_iter().map(f).collect()
     }
 }
+impl<T, M: Ord> ParametrizedTryMap<0, M> for std::collections::BinaryHeap<T> {
+    fn try_param_map<E>(
+        self,
+        f: impl FnMut(Self::Item) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        self.into_iter().map(f).collect()
+    }
+}
+impl<T, M: Ord> ParametrizedMapRef<0, M> for std::collections::BinaryHeap<T> {
+    type Mapped = std::collections::BinaryHeap<M>;
+    fn param_map_ref<'a>(&'a self, f: impl FnMut(&'a Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        self.iter().map(f).collect()
+    }
+}
+impl<T: Ord> ParametrizedRetain<0> for std::collections::BinaryHeap<T> {
+    fn param_retain(&mut self, f: impl FnMut(&Self::Item) -> bool) {
+        self.retain(f)
+    }
+}
+impl<T, M: Ord> ParametrizedFilterMap<0, M> for std::collections::BinaryHeap<T> {
+    fn param_filter_map(self, f: impl FnMut(Self::Item) -> Option<M>) -> Self::Mapped {
+        self.into_iter().filter_map(f).collect()
+    }
+}
+impl<T: Ord> ParametrizedUpdate<0> for std::collections::BinaryHeap<T> {
+    fn param_update(&mut self, f: impl FnMut(Self::Item) -> Self::Item) {
+        *self = core::mem::take(self).into_iter().map(f).collect();
+    }
+}
 impl<T, E> Parametrized<0> for Result<T, E> {
     type Item = T;
     const MIN_LEN: usize = 0;
@@ -683,6 +3528,26 @@ impl<T, E> Parametrized<0> for Result<T, E> {
     {
         self.iter()
     }
+    fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        if let Ok(item) = self {
+            f(item)
+        }
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        match self {
+            Ok(item) => f(item),
+            _ => core::ops::ControlFlow::Continue(()),
+        }
+    }
 }
 impl<T, E> ParametrizedIterMut<0> for Result<T, E> {
     type IterMut<'a> = std::result::IterMut<'a,T>
@@ -695,6 +3560,14 @@ impl<T, E> ParametrizedIterMut<0> for Result<T, E> {
     {
         self.iter_mut()
     }
+    fn param_for_each_mut<'a>(&'a mut self, mut f: impl FnMut(&'a mut Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        if let Ok(item) = self {
+            f(item)
+        }
+    }
 }
 impl<T, E> ParametrizedIntoIter<0> for Result<T, E> {
     type IntoIter = std::result::IntoIter<T>
@@ -707,6 +3580,15 @@ impl<T, E> ParametrizedIntoIter<0> for Result<T, E> {
     {
         self.into_iter()
     }
+    fn param_fold<B>(self, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        match self {
+            Ok(item) => f(init, item),
+            _ => init,
+        }
+    }
 }
 impl<T, E, M> ParametrizedMap<0, M> for Result<T, E> {
     type Mapped = Result<M, E>;
@@ -718,6 +3600,56 @@ impl<T, E, M> ParametrizedMap<0, M> for Result<T, E> {
         self.map(f)
     }
 }
+impl<T, E, M> ParametrizedTryMap<0, M> for Result<T, E> {
+    fn try_param_map<F>(
+        self,
+        mut f: impl FnMut(Self::Item) -> Result<M, F>,
+    ) -> Result<Self::Mapped, F> {
+        match self {
+            Ok(t) => f(t).map(Ok),
+            Err(e) => Ok(Err(e)),
+        }
+    }
+}
+impl<T, E: Clone, M> ParametrizedMapRef<0, M> for Result<T, E> {
+    type Mapped = Result<M, E>;
+    fn param_map_ref<'a>(&'a self, f: impl FnMut(&'a Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        self.as_ref().map(f).map_err(Clone::clone)
+    }
+}
+impl<T, E, U, M> ParametrizedZip<0, Result<U, E>, M> for Result<T, E> {
+    fn try_param_zip_with<F: From<ShapeMismatch>>(
+        self,
+        other: Result<U, E>,
+        mut f: impl FnMut(Self::Item, U) -> Result<M, F>,
+    ) -> Result<Self::Mapped, F> {
+        match (self, other) {
+            (Ok(a), Ok(b)) => Ok(Ok(f(a, b)?)),
+            (Err(e), Err(_)) => Ok(Err(e)),
+            (Ok(_), Err(_)) => Err(ShapeMismatch::Variant {
+                expected: "Ok",
+                found: "Err",
+            }.into()),
+            (Err(_), Ok(_)) => Err(ShapeMismatch::Variant {
+                expected: "Err",
+                found: "Ok",
+            }.into()),
+        }
+    }
+}
+impl<A, B, E: Clone> ParametrizedUnzip<0, A, B> for Result<(A, B), E> {
+    type Left = Result<A, E>;
+    type Right = Result<B, E>;
+    fn param_unzip(self) -> (Self::Left, Self::Right) {
+        match self {
+            Ok((a, b)) => (Ok(a), Ok(b)),
+            Err(e) => (Err(e.clone()), Err(e)),
+        }
+    }
+}
 
 impl<T, E> Parametrized<1> for Result<T, E> {
     type Item = E;
@@ -734,6 +3666,26 @@ impl<T, E> Parametrized<1> for Result<T, E> {
     {
         self.as_ref().err().into_iter()
     }
+    fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        if let Err(item) = self {
+            f(item)
+        }
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        match self {
+            Err(item) => f(item),
+            _ => core::ops::ControlFlow::Continue(()),
+        }
+    }
 }
 impl<T, E> ParametrizedIterMut<1> for Result<T, E> {
     type IterMut<'a> = std::option::IntoIter<&'a mut E> where (T,E):'a;
@@ -744,6 +3696,14 @@ impl<T, E> ParametrizedIterMut<1> for Result<T, E> {
     {
         self.as_mut().err().into_iter()
     }
+    fn param_for_each_mut<'a>(&'a mut self, mut f: impl FnMut(&'a mut Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        if let Err(item) = self {
+            f(item)
+        }
+    }
 }
 impl<T, E> ParametrizedIntoIter<1> for Result<T, E> {
     type IntoIter = std::option::IntoIter<E>;
@@ -753,14 +3713,112 @@ impl<T, E> ParametrizedIntoIter<1> for Result<T, E> {
     {
         self.err().into_iter()
     }
+    fn param_fold<B>(self, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        match self {
+            Err(item) => f(init, item),
+            _ => init,
+        }
+    }
 }
 impl<T, E, M> ParametrizedMap<1, M> for Result<T, E> {
     type Mapped = Result<T, M>;
-    fn param_map(self, f: impl FnMut(Self::Item) -> M) -> Self::Mapped
+    fn param_map(self, f: impl FnMut(Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: Sized,
+    {
+        self.map_err(f)
+    }
+}
+impl<T, E, M, N> ParametrizedBiMap<0, 1, M, N> for Result<T, E> {
+    type Mapped = Result<M, N>;
+    fn param_bimap(self, f0: impl FnMut(T) -> M, f1: impl FnMut(E) -> N) -> Self::Mapped {
+        self.map(f0).map_err(f1)
+    }
+}
+impl<T, E> ParametrizedUnion<0, 1> for Result<T, E> {
+    type UnionIter<'a> = core::iter::Once<Either<&'a T, &'a E>>
+    where
+        (Self, T, E): 'a;
+    fn param_iter_either<'a>(&'a self) -> Self::UnionIter<'a>
+    where
+        T: 'a,
+        E: 'a,
+    {
+        core::iter::once(match self {
+            Ok(value) => Either::Left(value),
+            Err(err) => Either::Right(err),
+        })
+    }
+}
+impl<T, E> ParametrizedUnionMut<0, 1> for Result<T, E> {
+    type UnionIterMut<'a> = core::iter::Once<Either<&'a mut T, &'a mut E>>
+    where
+        (Self, T, E): 'a;
+    fn param_iter_mut_either<'a>(&'a mut self) -> Self::UnionIterMut<'a>
+    where
+        T: 'a,
+        E: 'a,
+    {
+        core::iter::once(match self {
+            Ok(value) => Either::Left(value),
+            Err(err) => Either::Right(err),
+        })
+    }
+}
+impl<T, E, M> ParametrizedTryMap<1, M> for Result<T, E> {
+    fn try_param_map<F>(
+        self,
+        mut f: impl FnMut(Self::Item) -> Result<M, F>,
+    ) -> Result<Self::Mapped, F> {
+        match self {
+            Ok(t) => Ok(Ok(t)),
+            Err(e) => f(e).map(Err),
+        }
+    }
+}
+impl<T: Clone, E, M> ParametrizedMapRef<1, M> for Result<T, E> {
+    type Mapped = Result<T, M>;
+    fn param_map_ref<'a>(&'a self, mut f: impl FnMut(&'a Self::Item) -> M) -> Self::Mapped
     where
-        Self::Item: Sized,
+        Self::Item: 'a,
     {
-        self.map_err(f)
+        match self {
+            Ok(t) => Ok(t.clone()),
+            Err(e) => Err(f(e)),
+        }
+    }
+}
+impl<T, E, U, M> ParametrizedZip<1, Result<T, U>, M> for Result<T, E> {
+    fn try_param_zip_with<F: From<ShapeMismatch>>(
+        self,
+        other: Result<T, U>,
+        mut f: impl FnMut(Self::Item, U) -> Result<M, F>,
+    ) -> Result<Self::Mapped, F> {
+        match (self, other) {
+            (Ok(t), Ok(_)) => Ok(Ok(t)),
+            (Err(a), Err(b)) => Ok(Err(f(a, b)?)),
+            (Ok(_), Err(_)) => Err(ShapeMismatch::Variant {
+                expected: "Ok",
+                found: "Err",
+            }.into()),
+            (Err(_), Ok(_)) => Err(ShapeMismatch::Variant {
+                expected: "Err",
+                found: "Ok",
+            }.into()),
+        }
+    }
+}
+impl<T: Clone, A, B> ParametrizedUnzip<1, A, B> for Result<T, (A, B)> {
+    type Left = Result<T, A>;
+    type Right = Result<T, B>;
+    fn param_unzip(self) -> (Self::Left, Self::Right) {
+        match self {
+            Ok(t) => (Ok(t.clone()), Ok(t)),
+            Err((a, b)) => (Err(a), Err(b)),
+        }
     }
 }
 impl<const N: usize, T, M> ParametrizedMap<0, M> for [T; N] {
@@ -773,6 +3831,55 @@ impl<const N: usize, T, M> ParametrizedMap<0, M> for [T; N] {
         self.map(f)
     }
 }
+impl<const N: usize, T, M> ParametrizedTryMap<0, M> for [T; N] {
+    fn try_param_map<E>(
+        self,
+        f: impl FnMut(Self::Item) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        let mapped = self.into_iter().map(f).collect::<Result<Vec<_>, E>>()?;
+        match mapped.try_into() {
+            Ok(mapped) => Ok(mapped),
+            Err(_) => unreachable!("the length is kept"),
+        }
+    }
+}
+impl<const N: usize, T, M> ParametrizedMapRef<0, M> for [T; N] {
+    type Mapped = [M; N];
+    fn param_map_ref<'a>(&'a self, f: impl FnMut(&'a Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        self.each_ref().map(f)
+    }
+}
+impl<const N: usize, T, U, M> ParametrizedZip<0, [U; N], M> for [T; N] {
+    fn try_param_zip_with<E: From<ShapeMismatch>>(
+        self,
+        other: [U; N],
+        mut f: impl FnMut(Self::Item, U) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        let mapped = self
+            .into_iter()
+            .zip(other)
+            .map(|(a, b)| f(a, b))
+            .collect::<Result<Vec<_>, E>>()?;
+        match mapped.try_into() {
+            Ok(mapped) => Ok(mapped),
+            Err(_) => unreachable!("the length is kept"),
+        }
+    }
+}
+impl<const N: usize, A, B> ParametrizedUnzip<0, A, B> for [(A, B); N] {
+    type Left = [A; N];
+    type Right = [B; N];
+    fn param_unzip(self) -> (Self::Left, Self::Right) {
+        let (left, right): (Vec<A>, Vec<B>) = self.into_iter().unzip();
+        match (left.try_into(), right.try_into()) {
+            (Ok(left), Ok(right)) => (left, right),
+            _ => unreachable!("the length is kept"),
+        }
+    }
+}
 impl<T> ParametrizedIterMut<0> for [T] {
     type IterMut<'a> = std::slice::IterMut<'a, T> where T: 'a;
     fn param_iter_mut<'a>(&'a mut self) -> Self::IterMut<'a>
@@ -781,6 +3888,17 @@ impl<T> ParametrizedIterMut<0> for [T] {
     {
         self.iter_mut()
     }
+    fn param_get_mut(&mut self, n: usize) -> Option<&mut T> {
+        self.get_mut(n)
+    }
+    fn param_for_each_mut<'a>(&'a mut self, mut f: impl FnMut(&'a mut Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        for item in self {
+            f(item)
+        }
+    }
 }
 impl<T> Parametrized<0> for [T] {
     type Item = T;
@@ -796,12 +3914,44 @@ impl<T> Parametrized<0> for [T] {
     {
         <&'a Self as IntoIterator>::into_iter(self)
     }
+    fn param_get(&self, n: usize) -> Option<&T> {
+        self.get(n)
+    }
+    fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        for item in self {
+            f(item)
+        }
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        for item in self {
+            f(item)?;
+        }
+        core::ops::ControlFlow::Continue(())
+    }
 }
 impl<T> ParametrizedIntoIter<0> for Option<T> {
     type IntoIter = core::option::IntoIter<T>;
     fn param_into_iter(self) -> Self::IntoIter {
         <Self as IntoIterator>::into_iter(self)
     }
+    fn param_fold<B>(self, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        match self {
+            Some(item) => f(init, item),
+            _ => init,
+        }
+    }
 }
 impl<T> ParametrizedIterMut<0> for Option<T> {
     type IterMut<'a> = core::option::IterMut<'a,T> where T:'a;
@@ -811,6 +3961,14 @@ impl<T> ParametrizedIterMut<0> for Option<T> {
     {
         <&'a mut Self as IntoIterator>::into_iter(self)
     }
+    fn param_for_each_mut<'a>(&'a mut self, mut f: impl FnMut(&'a mut Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        if let Some(item) = self {
+            f(item)
+        }
+    }
 }
 impl<T> Parametrized<0> for Option<T> {
     type Item = T;
@@ -826,6 +3984,92 @@ impl<T> Parametrized<0> for Option<T> {
     {
         <&'a Self as IntoIterator>::into_iter(self)
     }
+    fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        if let Some(item) = self {
+            f(item)
+        }
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        match self {
+            Some(item) => f(item),
+            _ => core::ops::ControlFlow::Continue(()),
+        }
+    }
+}
+
+impl<T, M> ParametrizedMap<0, M> for Option<T> {
+    type Mapped = Option<M>;
+    fn param_map(self, f: impl FnMut(Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: Sized,
+    {
+        self.map(f)
+    }
+}
+impl<T, M> ParametrizedTryMap<0, M> for Option<T> {
+    fn try_param_map<E>(
+        self,
+        f: impl FnMut(Self::Item) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        self.map(f).transpose()
+    }
+}
+impl<T, M> ParametrizedMapRef<0, M> for Option<T> {
+    type Mapped = Option<M>;
+    fn param_map_ref<'a>(&'a self, f: impl FnMut(&'a Self::Item) -> M) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        self.as_ref().map(f)
+    }
+}
+impl<T, U, M> ParametrizedZip<0, Option<U>, M> for Option<T> {
+    fn try_param_zip_with<E: From<ShapeMismatch>>(
+        self,
+        other: Option<U>,
+        mut f: impl FnMut(Self::Item, U) -> Result<M, E>,
+    ) -> Result<Self::Mapped, E> {
+        match (self, other) {
+            (Some(a), Some(b)) => Ok(Some(f(a, b)?)),
+            (None, None) => Ok(None),
+            (Some(_), None) => Err(ShapeMismatch::Variant {
+                expected: "Some",
+                found: "None",
+            }.into()),
+            (None, Some(_)) => Err(ShapeMismatch::Variant {
+                expected: "None",
+                found: "Some",
+            }.into()),
+        }
+    }
+}
+impl<A, B> ParametrizedUnzip<0, A, B> for Option<(A, B)> {
+    type Left = Option<A>;
+    type Right = Option<B>;
+    fn param_unzip(self) -> (Self::Left, Self::Right) {
+        self.unzip()
+    }
+}
+impl<T> ParametrizedRetain<0> for Option<T> {
+    fn param_retain(&mut self, mut f: impl FnMut(&Self::Item) -> bool) {
+        if self.as_ref().is_some_and(|item| !f(item)) {
+            *self = None;
+        }
+    }
+}
+impl<T, M> ParametrizedFilterMap<0, M> for Option<T> {
+    fn param_filter_map(self, f: impl FnMut(Self::Item) -> Option<M>) -> Self::Mapped {
+        self.and_then(f)
+    }
 }
 
 impl<K, V> Parametrized<0> for std::collections::BTreeMap<K, V> {
@@ -842,6 +4086,26 @@ impl<K, V> Parametrized<0> for std::collections::BTreeMap<K, V> {
     {
         self.keys()
     }
+    fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        for item in self.keys() {
+            f(item)
+        }
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        for item in self.keys() {
+            f(item)?;
+        }
+        core::ops::ControlFlow::Continue(())
+    }
 }
 
 impl<K, V> ParametrizedIntoIter<0> for std::collections::BTreeMap<K, V> {
@@ -852,6 +4116,16 @@ impl<K, V> ParametrizedIntoIter<0> for std::collections::BTreeMap<K, V> {
     {
         self.into_keys()
     }
+    fn param_fold<B>(self, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        let mut acc = init;
+        for item in self.into_keys() {
+            acc = f(acc, item);
+        }
+        acc
+    }
 }
 impl<L: Ord, K, V> ParametrizedMap<0, L> for std::collections::BTreeMap<K, V> {
     type Mapped = std::collections::BTreeMap<L, V>;
@@ -859,6 +4133,61 @@ impl<L: Ord, K, V> ParametrizedMap<0, L> for std::collections::BTreeMap<K, V> {
         self.into_iter().map(|(k, v)| (f(k), v)).collect()
     }
 }
+impl<L: Ord, K, V> ParametrizedTryMap<0, L> for std::collections::BTreeMap<K, V> {
+    fn try_param_map<E>(
+        self,
+        mut f: impl FnMut(Self::Item) -> Result<L, E>,
+    ) -> Result<Self::Mapped, E> {
+        self.into_iter().map(|(k, v)| Ok((f(k)?, v))).collect()
+    }
+}
+impl<L: Ord, K, V: Clone> ParametrizedMapRef<0, L> for std::collections::BTreeMap<K, V> {
+    type Mapped = std::collections::BTreeMap<L, V>;
+    fn param_map_ref<'a>(&'a self, mut f: impl FnMut(&'a Self::Item) -> L) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        self.iter().map(|(k, v)| (f(k), v.clone())).collect()
+    }
+}
+impl<L: Ord, K, V, U> ParametrizedZip<0, std::collections::BTreeMap<U, V>, L>
+    for std::collections::BTreeMap<K, V>
+{
+    fn try_param_zip_with<E: From<ShapeMismatch>>(
+        self,
+        other: std::collections::BTreeMap<U, V>,
+        mut f: impl FnMut(Self::Item, U) -> Result<L, E>,
+    ) -> Result<Self::Mapped, E> {
+        if self.len() != other.len() {
+            return Err(ShapeMismatch::Length {
+                expected: self.len(),
+                found: other.len(),
+            }.into());
+        }
+        self.into_iter()
+            .zip(other.into_keys())
+            .map(|((a, v), b)| Ok((f(a, b)?, v)))
+            .collect()
+    }
+}
+impl<K: Ord, V> ParametrizedRetain<0> for std::collections::BTreeMap<K, V> {
+    fn param_retain(&mut self, mut f: impl FnMut(&Self::Item) -> bool) {
+        self.retain(|k, _| f(k))
+    }
+}
+impl<L: Ord, K, V> ParametrizedFilterMap<0, L> for std::collections::BTreeMap<K, V> {
+    fn param_filter_map(self, mut f: impl FnMut(Self::Item) -> Option<L>) -> Self::Mapped {
+        self.into_iter().filter_map(|(k, v)| Some((f(k)?, v))).collect()
+    }
+}
+impl<K: Ord, V> ParametrizedUpdate<0> for std::collections::BTreeMap<K, V> {
+    fn param_update(&mut self, mut f: impl FnMut(Self::Item) -> Self::Item) {
+        *self = core::mem::take(self)
+            .into_iter()
+            .map(|(k, v)| (f(k), v))
+            .collect();
+    }
+}
 impl<K, V> Parametrized<1> for std::collections::BTreeMap<K, V> {
     type Item = V;
     const MIN_LEN: usize = 0;
@@ -873,6 +4202,26 @@ impl<K, V> Parametrized<1> for std::collections::BTreeMap<K, V> {
     {
         self.values()
     }
+    fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        for item in self.values() {
+            f(item)
+        }
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        for item in self.values() {
+            f(item)?;
+        }
+        core::ops::ControlFlow::Continue(())
+    }
 }
 impl<K, V> ParametrizedIterMut<1> for std::collections::BTreeMap<K, V> {
     type IterMut<'a> = std::collections::btree_map::ValuesMut<'a, K, V> where (K, V): 'a;
@@ -882,6 +4231,14 @@ impl<K, V> ParametrizedIterMut<1> for std::collections::BTreeMap<K, V> {
     {
         self.values_mut()
     }
+    fn param_for_each_mut<'a>(&'a mut self, mut f: impl FnMut(&'a mut Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        for item in self.values_mut() {
+            f(item)
+        }
+    }
 }
 impl<K, V> ParametrizedIntoIter<1> for std::collections::BTreeMap<K, V> {
     type IntoIter = std::collections::btree_map::IntoValues<K, V>;
@@ -891,6 +4248,16 @@ impl<K, V> ParametrizedIntoIter<1> for std::collections::BTreeMap<K, V> {
     {
         self.into_values()
     }
+    fn param_fold<B>(self, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        let mut acc = init;
+        for item in self.into_values() {
+            acc = f(acc, item);
+        }
+        acc
+    }
 }
 impl<L, K: Ord, V> ParametrizedMap<1, L> for std::collections::BTreeMap<K, V> {
     type Mapped = std::collections::BTreeMap<K, L>;
@@ -898,6 +4265,91 @@ impl<L, K: Ord, V> ParametrizedMap<1, L> for std::collections::BTreeMap<K, V> {
         self.into_iter().map(|(k, v)| (k, f(v))).collect()
     }
 }
+impl<L: Ord, M, K, V> ParametrizedBiMap<0, 1, L, M> for std::collections::BTreeMap<K, V> {
+    type Mapped = std::collections::BTreeMap<L, M>;
+    fn param_bimap(self, mut f0: impl FnMut(K) -> L, mut f1: impl FnMut(V) -> M) -> Self::Mapped {
+        self.into_iter().map(|(k, v)| (f0(k), f1(v))).collect()
+    }
+}
+impl<K, V> ParametrizedUnion<0, 1> for std::collections::BTreeMap<K, V> {
+    #[allow(clippy::type_complexity)]
+    type UnionIter<'a> = core::iter::FlatMap<
+        std::collections::btree_map::Iter<'a, K, V>,
+        [Either<&'a K, &'a V>; 2],
+        fn((&'a K, &'a V)) -> [Either<&'a K, &'a V>; 2],
+    >
+    where
+        (Self, K, V): 'a;
+    fn param_iter_either<'a>(&'a self) -> Self::UnionIter<'a>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        self.iter().flat_map(|(k, v)| [Either::Left(k), Either::Right(v)])
+    }
+}
+impl<L, K: Ord, V> ParametrizedTryMap<1, L> for std::collections::BTreeMap<K, V> {
+    fn try_param_map<E>(
+        self,
+        mut f: impl FnMut(Self::Item) -> Result<L, E>,
+    ) -> Result<Self::Mapped, E> {
+        self.into_iter().map(|(k, v)| Ok((k, f(v)?))).collect()
+    }
+}
+impl<L, K: Ord + Clone, V> ParametrizedMapRef<1, L> for std::collections::BTreeMap<K, V> {
+    type Mapped = std::collections::BTreeMap<K, L>;
+    fn param_map_ref<'a>(&'a self, mut f: impl FnMut(&'a Self::Item) -> L) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        self.iter().map(|(k, v)| (k.clone(), f(v))).collect()
+    }
+}
+impl<L, K: Ord, V, U> ParametrizedZip<1, std::collections::BTreeMap<K, U>, L>
+    for std::collections::BTreeMap<K, V>
+{
+    fn try_param_zip_with<E: From<ShapeMismatch>>(
+        self,
+        other: std::collections::BTreeMap<K, U>,
+        mut f: impl FnMut(Self::Item, U) -> Result<L, E>,
+    ) -> Result<Self::Mapped, E> {
+        if self.len() != other.len() {
+            return Err(ShapeMismatch::Length {
+                expected: self.len(),
+                found: other.len(),
+            }.into());
+        }
+        self.into_iter()
+            .zip(other)
+            .map(|((k, a), (l, b))| {
+                if k == l {
+                    Ok((k, f(a, b)?))
+                } else {
+                    Err(ShapeMismatch::Keys.into())
+                }
+            })
+            .collect()
+    }
+}
+impl<K: Ord + Clone, A, B> ParametrizedUnzip<1, A, B> for std::collections::BTreeMap<K, (A, B)> {
+    type Left = std::collections::BTreeMap<K, A>;
+    type Right = std::collections::BTreeMap<K, B>;
+    fn param_unzip(self) -> (Self::Left, Self::Right) {
+        self.into_iter()
+            .map(|(k, (a, b))| ((k.clone(), a), (k, b)))
+            .unzip()
+    }
+}
+impl<K: Ord, V> ParametrizedRetain<1> for std::collections::BTreeMap<K, V> {
+    fn param_retain(&mut self, mut f: impl FnMut(&Self::Item) -> bool) {
+        self.retain(|_, v| f(v))
+    }
+}
+impl<L, K: Ord, V> ParametrizedFilterMap<1, L> for std::collections::BTreeMap<K, V> {
+    fn param_filter_map(self, mut f: impl FnMut(Self::Item) -> Option<L>) -> Self::Mapped {
+        self.into_iter().filter_map(|(k, v)| Some((k, f(v)?))).collect()
+    }
+}
 impl<K, V> Parametrized<0> for std::collections::HashMap<K, V> {
     type Item = K;
     const MIN_LEN: usize = 0;
@@ -912,6 +4364,26 @@ impl<K, V> Parametrized<0> for std::collections::HashMap<K, V> {
     {
         self.keys()
     }
+    fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        for item in self.keys() {
+            f(item)
+        }
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        for item in self.keys() {
+            f(item)?;
+        }
+        core::ops::ControlFlow::Continue(())
+    }
 }
 
 impl<K, V> ParametrizedIntoIter<0> for std::collections::HashMap<K, V> {
@@ -922,6 +4394,16 @@ impl<K, V> ParametrizedIntoIter<0> for std::collections::HashMap<K, V> {
     {
         self.into_keys()
     }
+    fn param_fold<B>(self, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        let mut acc = init;
+        for item in self.into_keys() {
+            acc = f(acc, item);
+        }
+        acc
+    }
 }
 impl<L: Hash + Eq, K, V> ParametrizedMap<0, L> for std::collections::HashMap<K, V> {
     type Mapped = std::collections::HashMap<L, V>;
@@ -929,6 +4411,41 @@ impl<L: Hash + Eq, K, V> ParametrizedMap<0, L> for std::collections::HashMap<K,
         self.into_iter().map(|(k, v)| (f(k), v)).collect()
     }
 }
+impl<L: Hash + Eq, K, V> ParametrizedTryMap<0, L> for std::collections::HashMap<K, V> {
+    fn try_param_map<E>(
+        self,
+        mut f: impl FnMut(Self::Item) -> Result<L, E>,
+    ) -> Result<Self::Mapped, E> {
+        self.into_iter().map(|(k, v)| Ok((f(k)?, v))).collect()
+    }
+}
+impl<L: Hash + Eq, K, V: Clone> ParametrizedMapRef<0, L> for std::collections::HashMap<K, V> {
+    type Mapped = std::collections::HashMap<L, V>;
+    fn param_map_ref<'a>(&'a self, mut f: impl FnMut(&'a Self::Item) -> L) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        self.iter().map(|(k, v)| (f(k), v.clone())).collect()
+    }
+}
+impl<K, V> ParametrizedRetain<0> for std::collections::HashMap<K, V> {
+    fn param_retain(&mut self, mut f: impl FnMut(&Self::Item) -> bool) {
+        self.retain(|k, _| f(k))
+    }
+}
+impl<L: Hash + Eq, K, V> ParametrizedFilterMap<0, L> for std::collections::HashMap<K, V> {
+    fn param_filter_map(self, mut f: impl FnMut(Self::Item) -> Option<L>) -> Self::Mapped {
+        self.into_iter().filter_map(|(k, v)| Some((f(k)?, v))).collect()
+    }
+}
+impl<K: Eq + Hash, V> ParametrizedUpdate<0> for std::collections::HashMap<K, V> {
+    fn param_update(&mut self, mut f: impl FnMut(Self::Item) -> Self::Item) {
+        *self = core::mem::take(self)
+            .into_iter()
+            .map(|(k, v)| (f(k), v))
+            .collect();
+    }
+}
 impl<K, V> Parametrized<1> for std::collections::HashMap<K, V> {
     type Item = V;
     const MIN_LEN: usize = 0;
@@ -943,6 +4460,26 @@ impl<K, V> Parametrized<1> for std::collections::HashMap<K, V> {
     {
         self.values()
     }
+    fn param_for_each<'a>(&'a self, mut f: impl FnMut(&'a Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        for item in self.values() {
+            f(item)
+        }
+    }
+    fn param_try_for_each<'a, B>(
+        &'a self,
+        mut f: impl FnMut(&'a Self::Item) -> core::ops::ControlFlow<B>,
+    ) -> core::ops::ControlFlow<B>
+    where
+        Self::Item: 'a,
+    {
+        for item in self.values() {
+            f(item)?;
+        }
+        core::ops::ControlFlow::Continue(())
+    }
 }
 impl<K, V> ParametrizedIterMut<1> for std::collections::HashMap<K, V> {
     type IterMut<'a> = std::collections::hash_map::ValuesMut<'a, K, V> where (K, V): 'a;
@@ -952,6 +4489,14 @@ impl<K, V> ParametrizedIterMut<1> for std::collections::HashMap<K, V> {
     {
         self.values_mut()
     }
+    fn param_for_each_mut<'a>(&'a mut self, mut f: impl FnMut(&'a mut Self::Item))
+    where
+        Self::Item: 'a,
+    {
+        for item in self.values_mut() {
+            f(item)
+        }
+    }
 }
 impl<K, V> ParametrizedIntoIter<1> for std::collections::HashMap<K, V> {
     type IntoIter = std::collections::hash_map::IntoValues<K, V>;
@@ -961,6 +4506,16 @@ impl<K, V> ParametrizedIntoIter<1> for std::collections::HashMap<K, V> {
     {
         self.into_values()
     }
+    fn param_fold<B>(self, init: B, mut f: impl FnMut(B, Self::Item) -> B) -> B
+    where
+        Self::Item: Sized,
+    {
+        let mut acc = init;
+        for item in self.into_values() {
+            acc = f(acc, item);
+        }
+        acc
+    }
 }
 impl<L: Hash + Eq, K: Hash + Eq, V> ParametrizedMap<1, L> for std::collections::HashMap<K, V> {
     type Mapped = std::collections::HashMap<K, L>;
@@ -968,8 +4523,132 @@ impl<L: Hash + Eq, K: Hash + Eq, V> ParametrizedMap<1, L> for std::collections::
         self.into_iter().map(|(k, v)| (k, f(v))).collect()
     }
 }
+impl<L: Hash + Eq, M, K, V> ParametrizedBiMap<0, 1, L, M> for std::collections::HashMap<K, V> {
+    type Mapped = std::collections::HashMap<L, M>;
+    fn param_bimap(self, mut f0: impl FnMut(K) -> L, mut f1: impl FnMut(V) -> M) -> Self::Mapped {
+        self.into_iter().map(|(k, v)| (f0(k), f1(v))).collect()
+    }
+}
+impl<K, V> ParametrizedUnion<0, 1> for std::collections::HashMap<K, V> {
+    #[allow(clippy::type_complexity)]
+    type UnionIter<'a> = core::iter::FlatMap<
+        std::collections::hash_map::Iter<'a, K, V>,
+        [Either<&'a K, &'a V>; 2],
+        fn((&'a K, &'a V)) -> [Either<&'a K, &'a V>; 2],
+    >
+    where
+        (Self, K, V): 'a;
+    fn param_iter_either<'a>(&'a self) -> Self::UnionIter<'a>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        self.iter().flat_map(|(k, v)| [Either::Left(k), Either::Right(v)])
+    }
+}
+impl<L: Hash + Eq, K: Hash + Eq, V> ParametrizedTryMap<1, L> for std::collections::HashMap<K, V> {
+    fn try_param_map<E>(
+        self,
+        mut f: impl FnMut(Self::Item) -> Result<L, E>,
+    ) -> Result<Self::Mapped, E> {
+        self.into_iter().map(|(k, v)| Ok((k, f(v)?))).collect()
+    }
+}
+impl<L, K: Hash + Eq + Clone, V> ParametrizedMapRef<1, L> for std::collections::HashMap<K, V> {
+    type Mapped = std::collections::HashMap<K, L>;
+    fn param_map_ref<'a>(&'a self, mut f: impl FnMut(&'a Self::Item) -> L) -> Self::Mapped
+    where
+        Self::Item: 'a,
+    {
+        self.iter().map(|(k, v)| (k.clone(), f(v))).collect()
+    }
+}
+impl<L: Hash + Eq, K: Hash + Eq, V, U> ParametrizedZip<1, std::collections::HashMap<K, U>, L>
+    for std::collections::HashMap<K, V>
+{
+    fn try_param_zip_with<E: From<ShapeMismatch>>(
+        self,
+        mut other: std::collections::HashMap<K, U>,
+        mut f: impl FnMut(Self::Item, U) -> Result<L, E>,
+    ) -> Result<Self::Mapped, E> {
+        if self.len() != other.len() {
+            return Err(ShapeMismatch::Length {
+                expected: self.len(),
+                found: other.len(),
+            }.into());
+        }
+        self.into_iter()
+            .map(|(k, a)| match other.remove(&k) {
+                Some(b) => Ok((k, f(a, b)?)),
+                None => Err(ShapeMismatch::Keys.into()),
+            })
+            .collect()
+    }
+}
+impl<K: Hash + Eq + Clone, A, B> ParametrizedUnzip<1, A, B>
+    for std::collections::HashMap<K, (A, B)>
+{
+    type Left = std::collections::HashMap<K, A>;
+    type Right = std::collections::HashMap<K, B>;
+    fn param_unzip(self) -> (Self::Left, Self::Right) {
+        self.into_iter()
+            .map(|(k, (a, b))| ((k.clone(), a), (k, b)))
+            .unzip()
+    }
+}
+impl<K, V> ParametrizedRetain<1> for std::collections::HashMap<K, V> {
+    fn param_retain(&mut self, mut f: impl FnMut(&Self::Item) -> bool) {
+        self.retain(|_, v| f(v))
+    }
+}
+impl<L: Hash + Eq, K: Hash + Eq, V> ParametrizedFilterMap<1, L> for std::collections::HashMap<K, V> {
+    fn param_filter_map(self, mut f: impl FnMut(Self::Item) -> Option<L>) -> Self::Mapped {
+        self.into_iter().filter_map(|(k, v)| Some((k, f(v)?))).collect()
+    }
+}
 
 #[doc(hidden)]
 pub mod _imp {
-    pub use sumtype::{sumtype, traits};
+    pub use std::boxed::Box;
+    pub use std::vec::{self, Vec};
+
+    /// Replaces `*slot` with `f(*slot)`, leaving the default there if `f`
+    /// panics.
+    pub fn update_in_place<T: Default>(slot: &mut T, f: impl FnOnce(T) -> T) {
+        *slot = f(core::mem::take(slot));
+    }
+
+    /// Keeps the pairs of `diffs` for which `keep` returns `true`.
+    pub fn pair_kept<'a, T: ?Sized>(
+        mut diffs: Vec<crate::ParamDiff<&'a T>>,
+        keep: &mut dyn FnMut(&T, &T) -> bool,
+    ) -> Vec<crate::ParamDiff<&'a T>> {
+        diffs.retain(|diff| match diff {
+            crate::ParamDiff::Value { left, right, .. } => keep(left, right),
+            crate::ParamDiff::Shape { .. } => true,
+        });
+        diffs
+    }
+
+    /// Replaces the pairs of `diffs` with what `f` finds inside them, keeping
+    /// their paths in front.
+    pub fn pair_nested<T, U>(
+        diffs: Vec<crate::ParamDiff<T>>,
+        mut f: impl FnMut(T, T) -> Vec<crate::ParamDiff<U>>,
+    ) -> Vec<crate::ParamDiff<U>> {
+        let mut out = Vec::new();
+        for diff in diffs {
+            match diff {
+                crate::ParamDiff::Value { path, left, right } => out.extend(
+                    f(left, right)
+                        .into_iter()
+                        .map(|diff| diff.prefixed(path.segments())),
+                ),
+                crate::ParamDiff::Shape { path, mismatch } => {
+                    out.push(crate::ParamDiff::Shape { path, mismatch })
+                }
+            }
+        }
+        out
+    }
 }
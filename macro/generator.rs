@@ -26,6 +26,17 @@ pub trait Emitter: PartialEq + Debug + Hash + Any {
 
     fn do_deref(&self, elem: &Self::Elem) -> Self::Elem;
 
+    /// Emits the element for a type which refers back to the annotated item.
+    /// Returning `None` traverses it like any other type.
+    fn emit_recursive(&self, _ty: &Type, _elem: &Self::Elem) -> Option<Self::Elem> {
+        None
+    }
+
+    /// Another parameter which is traversed together with `replacing_ty`.
+    fn other_replacing_ty(&self) -> Option<&Type> {
+        None
+    }
+
     fn fold_item(
         &self,
         base_ty: Type,
@@ -47,6 +58,7 @@ pub struct EmitContext<K> {
     pub kind: K,
     pub krate: Path,
     pub replacing_ty: Type,
+    pub recursive: Vec<Path>,
 }
 
 pub trait ParseQuote<T> {
@@ -74,7 +86,7 @@ where
         ty: &Type,
         expr: &<Self as Emitter>::Elem,
     ) -> Option<<Self as Emitter>::Elem> {
-        if &self.replacing_ty == ty {
+        if &self.replacing_ty == ty || self.other_replacing_ty() == Some(ty) {
             Some(self.emit_pure(ty, expr))
         } else if let Type::Reference(TypeReference {
             mutability, elem, ..
@@ -94,6 +106,58 @@ where
         }
     }
 
+    /// Whether `ty` names one of the recursive types by its full path and
+    /// depends on the parameter.
+    fn is_recursive(&self, ty: &Type) -> bool {
+        if let Type::Path(TypePath { qself: None, path }) = ty {
+            self.recursive
+                .iter()
+                .any(|recursive| super::same_path(path, recursive))
+                && self.mentions_param(ty)
+        } else {
+            false
+        }
+    }
+
+    /// The two arguments of `base_ty` mentioning a parameter, if nothing else
+    /// does.
+    fn mentioning_pair(&self, base_ty: &Type) -> Option<[(usize, Type); 2]> {
+        let args = type_args(base_ty)
+            .into_iter()
+            .enumerate()
+            .filter(|(_, ty)| self.mentions_param(ty))
+            .collect::<Vec<_>>();
+        if let [(i, ty_i), (j, ty_j)] = args.as_slice() {
+            Some([(*i, (*ty_i).clone()), (*j, (*ty_j).clone())])
+        } else {
+            None
+        }
+    }
+
+    /// The two arguments of `base_ty` mentioning a parameter, if they mention
+    /// distinct ones of `replacing_ty` and [`Emitter::other_replacing_ty()`]
+    /// and nothing else does.
+    fn disjoint_pair(&self, base_ty: &Type) -> Option<[(usize, Type); 2]> {
+        let other = self.other_replacing_ty()?;
+        let mentioned = |ty: &Type| {
+            (
+                super::contains_type(ty, &self.replacing_ty),
+                super::contains_type(ty, other),
+            )
+        };
+        let pair = self.mentioning_pair(base_ty)?;
+        let (p_i, p_j) = (mentioned(&pair[0].1), mentioned(&pair[1].1));
+        (p_i.0 != p_i.1 && p_j.0 != p_j.1 && p_i != p_j).then_some(pair)
+    }
+
+    /// Whether `ty` mentions `replacing_ty` or [`Emitter::other_replacing_ty()`].
+    fn mentions_param(&self, ty: &Type) -> bool {
+        super::contains_type(ty, &self.replacing_ty)
+            || self
+                .other_replacing_ty()
+                .is_some_and(|other| super::contains_type(ty, other))
+    }
+
     fn emit_with_tys<'a>(
         &self,
         base_ty: &Type,
@@ -101,20 +165,18 @@ where
         expr: &<Self as Emitter>::Elem,
     ) -> Result<Option<<Self as Emitter>::Elem>> {
         let base_expr = expr.clone();
-        Ok(tys.into_iter().fold(Ok(None), |acc, (index, ty)| {
-            self.fold_item(base_ty.clone(), &base_expr, ty, acc?, index)
-        })?)
+        tys.into_iter().try_fold(None, |acc, (index, ty)| {
+            self.fold_item(base_ty.clone(), &base_expr, ty, acc, index)
+        })
     }
-    pub fn emit_for_tys_exprs<'a>(
+    pub fn fold_elems(
         &self,
-        tys_exprs: impl IntoIterator<Item = (Type, <Self as Emitter>::Elem)>,
-    ) -> Result<Option<<Self as Emitter>::Elem>> {
-        tys_exprs.into_iter().fold(Ok(None), |acc, (ty, expr)| {
-            match (acc?, self.emit(&ty, &expr)?) {
-                (Some(acc), Some(item)) => Ok(Some(self.fold(&acc, &item))),
-                (Some(o), None) | (None, Some(o)) => Ok(Some(o)),
-                _ => Ok(None),
-            }
+        elems: impl IntoIterator<Item = Option<<Self as Emitter>::Elem>>,
+    ) -> Option<<Self as Emitter>::Elem> {
+        elems.into_iter().fold(None, |acc, elem| match (acc, elem) {
+            (Some(acc), Some(item)) => Some(self.fold(&acc, &item)),
+            (Some(o), None) | (None, Some(o)) => Some(o),
+            _ => None,
         })
     }
     pub fn emit(
@@ -125,6 +187,15 @@ where
         if let Some(out) = self.check_pure_and_emit(ty, expr) {
             return Ok(Some(out));
         }
+        if self.is_recursive(ty) {
+            if let Some(out) = self.emit_recursive(ty, expr) {
+                return Ok(Some(out));
+            }
+        }
+        if !self.mentions_param(ty) {
+            // Whatever the syntax, there is nothing to traverse.
+            return Ok(None);
+        }
         match ty {
             Type::Slice(TypeSlice { elem, .. }) | Type::Array(TypeArray { elem, .. }) => {
                 self.emit_with_tys(ty, core::iter::once((0, elem.as_ref())), expr)
@@ -151,11 +222,10 @@ where
                             expr,
                         ),
                         PathArguments::Parenthesized(parenthesized) => {
-                            if let Some(_) = self.emit_with_tys(
-                                ty,
-                                parenthesized.inputs.iter().enumerate(),
-                                expr,
-                            )? {
+                            if self
+                                .emit_with_tys(ty, parenthesized.inputs.iter().enumerate(), expr)?
+                                .is_some()
+                            {
                                 Err(Error::new(
                                     ty.span(),
                                     "Cannot infer Parametrized of closures",
@@ -189,19 +259,17 @@ where
             Type::Tuple(TypeTuple { elems, .. }) => {
                 self.emit_with_tys(ty, elems.iter().enumerate(), expr)
             }
-            Type::Never(_) => return Ok(None),
+            Type::Never(_) => Ok(None),
             Type::ImplTrait(TypeImplTrait { bounds, .. }) => {
-                if let Some(_) = self.emit_with_tys(
-                    ty,
-                    bounds
-                        .iter()
-                        .filter_map(|tpb| {
-                            if let TypeParamBound::Trait(tb) = tpb {
-                                Some(
-                                    tb.path
-                                        .segments
-                                        .iter()
-                                        .map(|seg| match &seg.arguments {
+                if self
+                    .emit_with_tys(
+                        ty,
+                        bounds
+                            .iter()
+                            .filter_map(|tpb| {
+                                if let TypeParamBound::Trait(tb) = tpb {
+                                    Some(tb.path.segments.iter().flat_map(|seg| {
+                                        match &seg.arguments {
                                             PathArguments::None => vec![],
                                             PathArguments::AngleBracketed(ab) => ab
                                                 .args
@@ -225,26 +293,43 @@ where
                                                     },
                                                 )
                                                 .collect(),
-                                        })
-                                        .flatten(),
-                                )
-                            } else {
-                                None
-                            }
-                        })
-                        .flatten()
-                        .enumerate(),
-                    expr,
-                )? {
+                                        }
+                                    }))
+                                } else {
+                                    None
+                                }
+                            })
+                            .flatten()
+                            .enumerate(),
+                        expr,
+                    )?
+                    .is_some()
+                {
                     Err(Error::new(ty.span(), "Cannot parametrize over impl trait"))
                 } else {
                     Ok(None)
                 }
             }
-            _ => Err(Error::new(
-                ty.span(),
-                "Cannot infer Parametrized for this type",
-            )),
+            _ => {
+                let replacing_ty = &self.replacing_ty;
+                let kind = match ty {
+                    Type::TraitObject(_) => "a trait object",
+                    Type::BareFn(_) => "a function pointer",
+                    Type::Ptr(_) => "a raw pointer",
+                    Type::Macro(_) => "a type macro",
+                    _ => "this type",
+                };
+                Err(Error::new(
+                    ty.span(),
+                    format!(
+                        "Cannot infer Parametrized, because the parameter `{}` appears inside {}. \
+                         Consider `#[param(skip)]`, `#[param(opaque)]` or `#[param(with = ..)]` \
+                         on the field",
+                        quote!(#replacing_ty),
+                        kind
+                    ),
+                ))
+            }
         }
     }
 }
@@ -287,6 +372,10 @@ impl Emitter for EmitContext<EmitMaxLen> {
         parse_quote!(::core::option::Option::Some(1usize))
     }
 
+    fn emit_recursive(&self, _ty: &Type, _expr: &Expr) -> Option<Expr> {
+        Some(parse_quote!(::core::option::Option::<usize>::None))
+    }
+
     fn access_over_ref(&self) -> bool {
         true
     }
@@ -328,6 +417,10 @@ impl Emitter for EmitContext<EmitMinLen> {
         parse_quote!(1usize)
     }
 
+    fn emit_recursive(&self, _ty: &Type, _expr: &Expr) -> Option<Expr> {
+        Some(parse_quote!(0usize))
+    }
+
     fn access_over_ref(&self) -> bool {
         true
     }
@@ -344,6 +437,7 @@ impl Emitter for EmitContext<EmitMinLen> {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn fold_iter_like<T>(
     ctx: &EmitContext<T>,
     base_ty: &Type,
@@ -361,13 +455,40 @@ where
     let arg = Ident::new("__parametrized_arg", Span::call_site());
     if let Some(inner) = ctx.emit(ty, &quote!(#arg).parse_quote())? {
         let krate = &ctx.krate;
+        let slot: Expr = parse_quote! {
+            <#base_ty as #krate::#trait_name<#index>>::#fn_name(#expr)
+            .map(__parametrized_fn)
+        };
+        // The length of the iterator of each `ty`, which is exact when its
+        // `MIN_LEN` and `MAX_LEN` agree.
+        let exact_len = if ctx.other_replacing_ty().is_none() {
+            let min_len = EmitContext {
+                kind: EmitMinLen,
+                krate: krate.clone(),
+                replacing_ty: ctx.replacing_ty.clone(),
+                recursive: ctx.recursive.clone(),
+            }
+            .emit(ty, expr)?;
+            let max_len = EmitContext {
+                kind: EmitMaxLen,
+                krate: krate.clone(),
+                replacing_ty: ctx.replacing_ty.clone(),
+                recursive: ctx.recursive.clone(),
+            }
+            .emit(ty, expr)?;
+            min_len.zip(max_len)
+        } else {
+            None
+        };
+        let flatten: Expr = if let Some((min_len, max_len)) = exact_len {
+            parse_quote!(#krate::Flatten::with_inner_len(#slot, #min_len, #max_len))
+        } else {
+            parse_quote!(#krate::Flatten::new(#slot))
+        };
         Ok(Some(parse_quote! {
             {
                 let __parametrized_fn: fn(#and #ty) -> _ = |#arg| {#inner};
-                #krate::Flatten::new(
-                    <#base_ty as #krate::#trait_name<#index>>::#fn_name(#expr)
-                    .map(__parametrized_fn)
-                )
+                #flatten
             }
         }))
     } else {
@@ -384,7 +505,11 @@ impl Emitter for EmitContext<EmitLen> {
     fn item(&self, base_ty: &Type, index: usize, ty: &Type, expr: &Expr) -> Result<Option<Expr>> {
         let krate = &self.krate;
         let arg = Ident::new("__parametrized_arg", Span::call_site());
-        if let Some(inner) = self.emit(ty, &parse_quote!(#arg))? {
+        if ty == &self.replacing_ty {
+            Ok(Some(parse_quote!(
+                <#base_ty as #krate::Parametrized<#index>>::param_len(#expr)
+            )))
+        } else if let Some(inner) = self.emit(ty, &parse_quote!(#arg))? {
             Ok(Some(parse_quote!(
                 <#base_ty as #krate::Parametrized<#index>>::param_iter(#expr)
                 .map(|#arg| #inner)
@@ -419,6 +544,110 @@ impl Emitter for EmitContext<EmitLen> {
     }
 }
 
+/// Emits the body of `param_get` (or `param_get_mut` if `.0`), finding the
+/// value at the index held by `__parametrized_n`. The elements taken are the
+/// shared and the (maybe mutable) access expressions of a field, and the
+/// elements made keep the former and replace the latter with the lookup, which
+/// subtracts the number of values of the field from the index unless it finds
+/// the value there.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct EmitGet(pub bool);
+
+impl Emitter for EmitContext<EmitGet> {
+    type Elem = (Expr, Expr);
+
+    fn item(
+        &self,
+        base_ty: &Type,
+        index: usize,
+        ty: &Type,
+        (shared, expr): &(Expr, Expr),
+    ) -> Result<Option<(Expr, Expr)>> {
+        let krate = &self.krate;
+        let arg = Ident::new("__parametrized_arg", Span::call_site());
+        let n = Ident::new("__parametrized_n", Span::call_site());
+        let (trait_name, get_fn, iter_fn) = if self.kind.0 {
+            (
+                quote!(ParametrizedIterMut),
+                quote!(param_get_mut),
+                quote!(param_iter_mut),
+            )
+        } else {
+            (quote!(Parametrized), quote!(param_get), quote!(param_iter))
+        };
+        if ty == &self.replacing_ty {
+            Ok(Some((
+                shared.clone(),
+                parse_quote!({
+                    let __parametrized_len =
+                        <#base_ty as #krate::Parametrized<#index>>::param_len(#shared);
+                    if #n < __parametrized_len {
+                        <#base_ty as #krate::#trait_name<#index>>::#get_fn(#expr, #n)
+                    } else {
+                        #n -= __parametrized_len;
+                        ::core::option::Option::None
+                    }
+                }),
+            )))
+        } else if let Some((_, get)) = self.emit(ty, &(parse_quote!(&*#arg), parse_quote!(#arg)))? {
+            Ok(Some((
+                shared.clone(),
+                parse_quote!(
+                    <#base_ty as #krate::#trait_name<#index>>::#iter_fn(#expr)
+                        .find_map(|#arg| #get)
+                ),
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn fold(&self, (shared, acc): &(Expr, Expr), (_, item): &(Expr, Expr)) -> (Expr, Expr) {
+        (
+            shared.clone(),
+            parse_quote!(match #acc {
+                ::core::option::Option::Some(__parametrized_found) => {
+                    ::core::option::Option::Some(__parametrized_found)
+                }
+                ::core::option::Option::None => #item,
+            }),
+        )
+    }
+
+    fn emit_pure(&self, _ty: &Type, (shared, expr): &(Expr, Expr)) -> (Expr, Expr) {
+        let n = Ident::new("__parametrized_n", Span::call_site());
+        (
+            shared.clone(),
+            parse_quote!(if #n == 0 {
+                ::core::option::Option::Some(#expr)
+            } else {
+                #n -= 1;
+                ::core::option::Option::None
+            }),
+        )
+    }
+
+    fn access_over_ref(&self) -> bool {
+        !self.kind.0
+    }
+
+    fn access_over_ref_mut(&self) -> bool {
+        true
+    }
+
+    fn native_reference(&self) -> TokenStream {
+        if self.kind.0 {
+            quote!(&mut)
+        } else {
+            quote!(&)
+        }
+    }
+    fn do_deref(&self, (shared, expr): &Self::Elem) -> Self::Elem {
+        (parse_quote!(*#shared), parse_quote!(*#expr))
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 fn fold_iter_ty_like<T>(
     ctx: &EmitContext<T>,
     lt: &Lifetime,
@@ -472,7 +701,8 @@ impl Emitter for EmitContext<EmitIterTy> {
     }
 
     fn fold(&self, acc: &Type, item: &Type) -> Type {
-        parse_quote!(::core::iter::Chain<#acc, #item>)
+        let krate = &self.krate;
+        parse_quote!(#krate::Chain<#acc, #item>)
     }
 
     fn emit_pure(&self, _ty: &Type, _expr: &Type) -> Type {
@@ -517,7 +747,8 @@ impl Emitter for EmitContext<EmitIter> {
     }
 
     fn fold(&self, acc: &Expr, item: &Expr) -> Expr {
-        parse_quote!(#acc.chain(#item))
+        let krate = &self.krate;
+        parse_quote!(#krate::Chain::new(#acc, #item))
     }
 
     fn emit_pure(&self, _ty: &Type, expr: &Expr) -> Expr {
@@ -540,6 +771,137 @@ impl Emitter for EmitContext<EmitIter> {
     }
 }
 
+/// Emits the iterator of `param_iter_either` (or `param_iter_mut_either` if
+/// `.0`), yielding the values of `replacing_ty` on the left and those of `.1` on
+/// the right.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct EmitUnion(pub bool, pub Type);
+
+impl Emitter for EmitContext<EmitUnion> {
+    type Elem = Expr;
+
+    fn item(&self, base_ty: &Type, index: usize, ty: &Type, expr: &Expr) -> Result<Option<Expr>> {
+        if self.kind.0 {
+            fold_iter_like(
+                self,
+                base_ty,
+                index,
+                ty,
+                expr,
+                &quote!(ParametrizedIterMut),
+                &quote!(param_iter_mut),
+                &quote!(& '__parametrized_lt mut),
+            )
+        } else {
+            fold_iter_like(
+                self,
+                base_ty,
+                index,
+                ty,
+                expr,
+                &quote! {Parametrized},
+                &quote!(param_iter),
+                &quote!(& '__parametrized_lt),
+            )
+        }
+    }
+
+    /// Iterates the two arguments of `base_ty` mentioning a parameter together,
+    /// so that each entry of a map yields the values of its key and its value
+    /// in turn. Any other number of such arguments is iterated one after
+    /// another.
+    fn fold_item(
+        &self,
+        base_ty: Type,
+        expr: &Expr,
+        ty: &Type,
+        elem: Option<Expr>,
+        index: usize,
+    ) -> Result<Option<Expr>> {
+        let Some([(i, ty_i), (j, ty_j)]) = &self.mentioning_pair(&base_ty) else {
+            return Ok(match (elem, self.item(&base_ty, index, ty, expr)?) {
+                (Some(elem), Some(item)) => Some(self.fold(&elem, &item)),
+                (Some(o), None) | (None, Some(o)) => Some(o),
+                _ => None,
+            });
+        };
+        if index == *j {
+            // Already iterated together with the `i`-th argument.
+            return Ok(elem);
+        }
+        let krate = &self.krate;
+        let arg = Ident::new("__parametrized_arg", Span::call_site());
+        let (Some(inner_i), Some(inner_j)) = (
+            self.emit(ty_i, &parse_quote!(#arg))?,
+            self.emit(ty_j, &parse_quote!(#arg))?,
+        ) else {
+            unreachable!("both arguments mention a parameter")
+        };
+        let (trait_name, fn_name, and) = if self.kind.0 {
+            (
+                quote!(ParametrizedUnionMut),
+                quote!(param_iter_mut_either),
+                quote!(& '__parametrized_lt mut),
+            )
+        } else {
+            (
+                quote!(ParametrizedUnion),
+                quote!(param_iter_either),
+                quote!(& '__parametrized_lt),
+            )
+        };
+        Ok(Some(parse_quote! {
+            {
+                let __parametrized_fn: fn(#krate::Either<#and #ty_i, #and #ty_j>) -> _ =
+                    |__parametrized_either| match __parametrized_either {
+                        #krate::Either::Left(#arg) => #krate::Either::Left(#inner_i),
+                        #krate::Either::Right(#arg) => #krate::Either::Right(#inner_j),
+                    };
+                #krate::Flatten::new(
+                    <#base_ty as #krate::#trait_name<#i, #j>>::#fn_name(#expr)
+                    .map(__parametrized_fn)
+                )
+            }
+        }))
+    }
+
+    fn fold(&self, acc: &Expr, item: &Expr) -> Expr {
+        parse_quote!(#acc.chain(#item))
+    }
+
+    fn emit_pure(&self, ty: &Type, expr: &Expr) -> Expr {
+        let krate = &self.krate;
+        if ty == &self.replacing_ty {
+            parse_quote!(::core::iter::once(#krate::Either::Left(#expr)))
+        } else {
+            parse_quote!(::core::iter::once(#krate::Either::Right(#expr)))
+        }
+    }
+
+    fn other_replacing_ty(&self) -> Option<&Type> {
+        Some(&self.kind.1)
+    }
+
+    fn access_over_ref(&self) -> bool {
+        !self.kind.0
+    }
+
+    fn access_over_ref_mut(&self) -> bool {
+        true
+    }
+
+    fn native_reference(&self) -> TokenStream {
+        if self.kind.0 {
+            quote!(&mut)
+        } else {
+            quote!(&)
+        }
+    }
+    fn do_deref(&self, elem: &Self::Elem) -> Self::Elem {
+        parse_quote!(*#elem)
+    }
+}
+
 #[derive(PartialEq, Eq, Hash, Debug)]
 pub struct EmitIterMutTy(pub Lifetime, pub Type);
 
@@ -562,7 +924,8 @@ impl Emitter for EmitContext<EmitIterMutTy> {
     }
 
     fn fold(&self, acc: &Type, item: &Type) -> Type {
-        parse_quote!(::core::iter::Chain<#acc, #item>)
+        let krate = &self.krate;
+        parse_quote!(#krate::Chain<#acc, #item>)
     }
 
     fn emit_pure(&self, _ty: &Type, _expr: &Type) -> Type {
@@ -607,7 +970,8 @@ impl Emitter for EmitContext<EmitIterMut> {
     }
 
     fn fold(&self, acc: &Expr, item: &Expr) -> Expr {
-        parse_quote!(#acc.chain(#item))
+        let krate = &self.krate;
+        parse_quote!(#krate::Chain::new(#acc, #item))
     }
 
     fn emit_pure(&self, _ty: &Type, expr: &Expr) -> Expr {
@@ -654,7 +1018,8 @@ impl Emitter for EmitContext<EmitIntoIterTy> {
     }
 
     fn fold(&self, acc: &Type, item: &Type) -> Type {
-        parse_quote!(::core::iter::Chain<#acc, #item>)
+        let krate = &self.krate;
+        parse_quote!(#krate::Chain<#acc, #item>)
     }
 
     fn emit_pure(&self, _ty: &Type, _expr: &Type) -> Type {
@@ -696,7 +1061,8 @@ impl Emitter for EmitContext<EmitIntoIter> {
     }
 
     fn fold(&self, acc: &Expr, item: &Expr) -> Expr {
-        parse_quote!(#acc.chain(#item))
+        let krate = &self.krate;
+        parse_quote!(#krate::Chain::new(#acc, #item))
     }
 
     fn emit_pure(&self, _ty: &Type, expr: &Expr) -> Expr {
@@ -718,16 +1084,42 @@ impl Emitter for EmitContext<EmitIntoIter> {
         unreachable!()
     }
 }
+/// Emits the body of `param_retain`, calling the function `.0`.
 #[derive(PartialEq, Eq, Hash, Debug)]
-pub struct EmitMap(pub Ident, pub Ident);
-impl Emitter for EmitContext<EmitMap> {
-    type Elem = (Expr, Type);
+pub struct EmitRetain(pub Ident);
 
-    fn emit_pure(&self, _ty: &Type, expr: &(Expr, Type)) -> (Expr, Type) {
-        let map_fn = &self.kind.0;
-        let expr = expr.clone().0;
-        let map_param = &self.kind.1;
-        (parse_quote!(#map_fn(#expr)), parse_quote!(#map_param))
+impl Emitter for EmitContext<EmitRetain> {
+    type Elem = Expr;
+
+    fn item(&self, base_ty: &Type, index: usize, ty: &Type, expr: &Expr) -> Result<Option<Expr>> {
+        let krate = &self.krate;
+        let retain_fn = &self.kind.0;
+        let arg = Ident::new("__parametrized_arg", Span::call_site());
+        if ty == &self.replacing_ty {
+            let retain_fn: Expr = if self.recursive.is_empty() {
+                parse_quote!(&mut #retain_fn)
+            } else {
+                parse_quote!(&mut *#retain_fn)
+            };
+            Ok(Some(parse_quote!(
+                <#base_ty as #krate::ParametrizedRetain<#index>>::param_retain(#expr, #retain_fn)
+            )))
+        } else if let Some(inner) = self.emit(ty, &parse_quote!(#arg))? {
+            Ok(Some(parse_quote!(
+                <#base_ty as #krate::ParametrizedIterMut<#index>>::param_iter_mut(#expr)
+                .for_each(|#arg| { #inner; })
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn fold(&self, acc: &Expr, item: &Expr) -> Expr {
+        parse_quote!({ #acc; #item; })
+    }
+
+    fn emit_pure(&self, _ty: &Type, _expr: &Expr) -> Expr {
+        unreachable!("fields of the parameter itself are rejected")
     }
 
     fn access_over_ref(&self) -> bool {
@@ -737,63 +1129,826 @@ impl Emitter for EmitContext<EmitMap> {
     fn access_over_ref_mut(&self) -> bool {
         false
     }
+
     fn native_reference(&self) -> TokenStream {
-        quote!()
+        quote!(&mut)
+    }
+    fn do_deref(&self, elem: &Self::Elem) -> Self::Elem {
+        parse_quote!(*#elem)
     }
+}
 
-    fn item(
-        &self,
-        _base_ty: &Type,
-        _index: usize,
-        _ty: &Type,
-        _: &Self::Elem,
-    ) -> Result<Option<Self::Elem>> {
-        unreachable!()
+/// Emits the body of `param_update`, calling the function `.0`.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct EmitUpdate(pub Ident);
+
+impl EmitContext<EmitUpdate> {
+    fn update_fn(&self) -> Expr {
+        let update_fn = &self.kind.0;
+        if self.recursive.is_empty() {
+            parse_quote!(&mut #update_fn)
+        } else {
+            parse_quote!(&mut *#update_fn)
+        }
     }
 
-    fn fold_item(
-        &self,
-        base_ty: Type,
-        (expr0, _): &<Self as Emitter>::Elem,
-        ty: &Type,
-        elem: Option<<Self as Emitter>::Elem>,
-        index: usize,
-    ) -> Result<Option<<Self as Emitter>::Elem>> {
+    /// Whether the `index`-th parameter of `base_ty` can be updated through
+    /// `ParametrizedIterMut`, which leaves the values in their places rather
+    /// than requiring them to be `Default`.
+    fn updates_by_iter_mut(base_ty: &Type, index: usize) -> bool {
+        match base_ty {
+            Type::Array(_) | Type::Slice(_) | Type::Tuple(_) => true,
+            Type::Path(TypePath { qself: None, path }) => path.segments.last().is_some_and(|seg| {
+                [
+                    "Box",
+                    "Option",
+                    "Result",
+                    "Vec",
+                    "VecDeque",
+                    "LinkedList",
+                    "PhantomData",
+                ]
+                .iter()
+                .any(|name| seg.ident == name)
+                    || index == 1 && (seg.ident == "BTreeMap" || seg.ident == "HashMap")
+            }),
+            _ => false,
+        }
+    }
+}
+
+impl Emitter for EmitContext<EmitUpdate> {
+    type Elem = Expr;
+
+    fn item(&self, base_ty: &Type, index: usize, ty: &Type, expr: &Expr) -> Result<Option<Expr>> {
         let krate = &self.krate;
-        let map_param = &self.kind.1;
-        let replaced_ty = super::replace_type(
-            ty.clone(),
-            self.replacing_ty.clone(),
-            parse_quote!(#map_param),
-        );
         let arg = Ident::new("__parametrized_arg", Span::call_site());
-        let expr = if let Some((expr1, _)) = elem {
-            expr1
-        } else {
-            expr0.clone()
-        };
-        if let Some((inner_exp, inner_ty)) = self.emit(ty, &(parse_quote!(#arg), ty.clone()))? {
-            Ok(Some((
-                parse_quote!(
-                    <#base_ty as #krate::ParametrizedMap<
-                        #index,
-                        #replaced_ty
-                    >>::param_map(
-                        #expr,
-                        |#arg| { #inner_exp }
-                    )
-                ),
-                inner_ty,
+        let owned = Ident::new("__parametrized_owned", Span::call_site());
+        if ty == &self.replacing_ty {
+            let update_fn = self.update_fn();
+            Ok(Some(parse_quote!(
+                <#base_ty as #krate::ParametrizedUpdate<#index>>::param_update(#expr, #update_fn)
+            )))
+        } else if let Some(inner) = self.emit(ty, &parse_quote!(#arg))? {
+            if Self::updates_by_iter_mut(base_ty, index) {
+                return Ok(Some(parse_quote!(
+                    <#base_ty as #krate::ParametrizedIterMut<#index>>::param_iter_mut(#expr)
+                        .for_each(|#arg| {
+                            #inner;
+                        })
+                )));
+            }
+            Ok(Some(parse_quote!(
+                <#base_ty as #krate::ParametrizedUpdate<#index>>::param_update(
+                    #expr,
+                    |mut #owned| {
+                        {
+                            let #arg = &mut #owned;
+                            #inner;
+                        }
+                        #owned
+                    },
+                )
             )))
         } else {
-            Ok(Some((expr, ty.clone())))
+            Ok(None)
         }
     }
 
-    fn fold(&self, _acc: &Self::Elem, _item: &Self::Elem) -> Self::Elem {
-        unreachable!()
+    fn fold(&self, acc: &Expr, item: &Expr) -> Expr {
+        parse_quote!({ #acc; #item; })
     }
-    fn do_deref(&self, (elem, ty): &Self::Elem) -> Self::Elem {
-        (parse_quote!(*#elem), ty.clone())
+
+    fn emit_pure(&self, _ty: &Type, expr: &Expr) -> Expr {
+        let krate = &self.krate;
+        let update_fn = self.update_fn();
+        parse_quote!(#krate::_imp::update_in_place(#expr, #update_fn))
+    }
+
+    fn access_over_ref(&self) -> bool {
+        false
+    }
+
+    fn access_over_ref_mut(&self) -> bool {
+        false
+    }
+
+    fn native_reference(&self) -> TokenStream {
+        quote!(&mut)
+    }
+    fn do_deref(&self, elem: &Self::Elem) -> Self::Elem {
+        parse_quote!(*#elem)
+    }
+}
+
+/// Which method [`EmitLoop`] emits.
+#[derive(PartialEq, Eq, Hash, Debug, Clone, Copy)]
+pub enum LoopKind {
+    /// `param_for_each`
+    ForEach,
+    /// `param_for_each_mut`
+    ForEachMut,
+    /// `param_try_for_each`, whose elements evaluate to `ControlFlow`s.
+    TryForEach,
+    /// `param_fold`, whose elements evaluate to the accumulator, starting from
+    /// the one bound to `__parametrized_acc`.
+    Fold,
+}
+
+/// Emits the body of an internal iteration method selected by [`LoopKind`],
+/// calling the function `.0` on every value in nested loops.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct EmitLoop(pub Ident, pub LoopKind);
+
+impl EmitContext<EmitLoop> {
+    fn loop_fn(&self) -> Expr {
+        let loop_fn = &self.kind.0;
+        if self.recursive.is_empty() {
+            parse_quote!(&mut #loop_fn)
+        } else {
+            parse_quote!(&mut *#loop_fn)
+        }
+    }
+}
+
+impl Emitter for EmitContext<EmitLoop> {
+    type Elem = Expr;
+
+    fn item(&self, base_ty: &Type, index: usize, ty: &Type, expr: &Expr) -> Result<Option<Expr>> {
+        let krate = &self.krate;
+        let arg = Ident::new("__parametrized_arg", Span::call_site());
+        let acc = Ident::new("__parametrized_acc", Span::call_site());
+        let (trait_name, fn_name) = match self.kind.1 {
+            LoopKind::ForEach => (quote!(Parametrized), quote!(param_for_each)),
+            LoopKind::ForEachMut => (quote!(ParametrizedIterMut), quote!(param_for_each_mut)),
+            LoopKind::TryForEach => (quote!(Parametrized), quote!(param_try_for_each)),
+            LoopKind::Fold => (quote!(ParametrizedIntoIter), quote!(param_fold)),
+        };
+        let path = quote!(<#base_ty as #krate::#trait_name<#index>>::#fn_name);
+        let inner = if ty == &self.replacing_ty {
+            self.loop_fn()
+        } else if let Some(inner) = self.emit(ty, &parse_quote!(#arg))? {
+            match self.kind.1 {
+                LoopKind::ForEach | LoopKind::ForEachMut => parse_quote!(|#arg| { #inner; }),
+                LoopKind::TryForEach => parse_quote!(|#arg| #inner),
+                LoopKind::Fold => parse_quote!(|#acc, #arg| #inner),
+            }
+        } else {
+            return Ok(None);
+        };
+        if self.kind.1 == LoopKind::Fold {
+            Ok(Some(parse_quote!(#path(#expr, #acc, #inner))))
+        } else {
+            Ok(Some(parse_quote!(#path(#expr, #inner))))
+        }
+    }
+
+    fn fold(&self, acc: &Expr, item: &Expr) -> Expr {
+        match self.kind.1 {
+            LoopKind::ForEach | LoopKind::ForEachMut => parse_quote!({ #acc; #item; }),
+            LoopKind::TryForEach => parse_quote!({ #acc?; #item }),
+            LoopKind::Fold => parse_quote!({
+                let __parametrized_acc = #acc;
+                #item
+            }),
+        }
+    }
+
+    fn emit_pure(&self, _ty: &Type, expr: &Expr) -> Expr {
+        let loop_fn = &self.kind.0;
+        if self.kind.1 == LoopKind::Fold {
+            parse_quote!(#loop_fn(__parametrized_acc, #expr))
+        } else {
+            parse_quote!(#loop_fn(#expr))
+        }
+    }
+
+    fn access_over_ref(&self) -> bool {
+        matches!(self.kind.1, LoopKind::ForEach | LoopKind::TryForEach)
+    }
+
+    fn access_over_ref_mut(&self) -> bool {
+        self.kind.1 != LoopKind::Fold
+    }
+
+    fn native_reference(&self) -> TokenStream {
+        match self.kind.1 {
+            LoopKind::ForEach | LoopKind::TryForEach => quote!(&),
+            LoopKind::ForEachMut => quote!(&mut),
+            LoopKind::Fold => quote!(),
+        }
+    }
+    fn do_deref(&self, elem: &Self::Elem) -> Self::Elem {
+        parse_quote!(*#elem)
+    }
+}
+
+/// Emits the iterator of `param_path_iter`.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct EmitPaths;
+
+impl Emitter for EmitContext<EmitPaths> {
+    type Elem = Expr;
+
+    fn item(&self, base_ty: &Type, index: usize, ty: &Type, expr: &Expr) -> Result<Option<Expr>> {
+        let krate = &self.krate;
+        let arg = Ident::new("__parametrized_arg", Span::call_site());
+        let path = Ident::new("__parametrized_path", Span::call_site());
+        let inner_path = Ident::new("__parametrized_inner_path", Span::call_site());
+        if ty == &self.replacing_ty {
+            Ok(Some(parse_quote!(
+                <#base_ty as #krate::ParametrizedPath<#index>>::param_path_iter(#expr)
+            )))
+        } else if let Some(inner) = self.emit(ty, &parse_quote!(#arg))? {
+            Ok(Some(parse_quote!(
+                <#base_ty as #krate::Parametrized<#index>>::param_iter(#expr)
+                    .zip(<#base_ty as #krate::ParametrizedPath<#index>>::param_path_iter(#expr))
+                    .flat_map(|(#arg, #path)| {
+                        ::core::iter::Iterator::map(#inner, move |#inner_path| {
+                            #inner_path.prefixed(#path.segments())
+                        })
+                    })
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn fold(&self, acc: &Expr, item: &Expr) -> Expr {
+        parse_quote!(::core::iter::Iterator::chain(#acc, #item))
+    }
+
+    fn emit_pure(&self, _ty: &Type, _expr: &Expr) -> Expr {
+        let krate = &self.krate;
+        parse_quote!(::core::iter::once(#krate::ParamPath::new()))
+    }
+
+    fn access_over_ref(&self) -> bool {
+        true
+    }
+
+    fn access_over_ref_mut(&self) -> bool {
+        true
+    }
+
+    fn native_reference(&self) -> TokenStream {
+        quote!(&)
+    }
+    fn do_deref(&self, elem: &Self::Elem) -> Self::Elem {
+        parse_quote!(*#elem)
+    }
+}
+
+/// Emits the lookup of `param_get_path_prefix`, following the path `.0`.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct EmitGetPath(pub Ident);
+
+impl Emitter for EmitContext<EmitGetPath> {
+    type Elem = Expr;
+
+    fn item(&self, base_ty: &Type, index: usize, ty: &Type, expr: &Expr) -> Result<Option<Expr>> {
+        let krate = &self.krate;
+        let path = &self.kind.0;
+        let arg = Ident::new("__parametrized_arg", Span::call_site());
+        if ty == &self.replacing_ty {
+            Ok(Some(parse_quote!(
+                <#base_ty as #krate::ParametrizedPath<#index>>::param_get_path_prefix(#expr, #path)
+            )))
+        } else if let Some(inner) = self.emit(ty, &parse_quote!(#arg))? {
+            Ok(Some(parse_quote!(
+                <#base_ty as #krate::ParametrizedPath<#index>>::param_get_path_prefix(#expr, #path)
+                    .and_then(|(#arg, #path)| #inner)
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn fold(&self, acc: &Expr, item: &Expr) -> Expr {
+        parse_quote!(#acc.or_else(|| #item))
+    }
+
+    fn emit_pure(&self, _ty: &Type, expr: &Expr) -> Expr {
+        let path = &self.kind.0;
+        parse_quote!(::core::option::Option::Some((#expr, #path)))
+    }
+
+    fn access_over_ref(&self) -> bool {
+        true
+    }
+
+    fn access_over_ref_mut(&self) -> bool {
+        true
+    }
+
+    fn native_reference(&self) -> TokenStream {
+        quote!(&)
+    }
+    fn do_deref(&self, elem: &Self::Elem) -> Self::Elem {
+        parse_quote!(*#elem)
+    }
+}
+
+/// Emits the pairs of `param_pair`. Expressions are pairs `(a, b)` of the
+/// fields of both values.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct EmitPair(pub Ident);
+
+impl Emitter for EmitContext<EmitPair> {
+    type Elem = Expr;
+
+    fn item(&self, base_ty: &Type, index: usize, ty: &Type, expr: &Expr) -> Result<Option<Expr>> {
+        let krate = &self.krate;
+        let keep = &self.kind.0;
+        let (left, right) = split_pair(expr);
+        let arg_left = Ident::new("__parametrized_arg_left", Span::call_site());
+        let arg_right = Ident::new("__parametrized_arg_right", Span::call_site());
+        if ty == &self.replacing_ty {
+            Ok(Some(parse_quote!(
+                <#base_ty as #krate::ParametrizedDiff<#index>>::param_pair_by(
+                    #left,
+                    #right,
+                    &mut *#keep,
+                )
+            )))
+        } else if let Some(inner) = self.emit(ty, &parse_quote!((#arg_left, #arg_right)))? {
+            Ok(Some(parse_quote!(
+                #krate::_imp::pair_nested(
+                    <#base_ty as #krate::ParametrizedDiff<#index>>::param_pair(#left, #right),
+                    |#arg_left, #arg_right| #inner,
+                )
+            )))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn fold(&self, acc: &Expr, item: &Expr) -> Expr {
+        parse_quote!({
+            let mut __parametrized_pairs = #acc;
+            __parametrized_pairs.extend(#item);
+            __parametrized_pairs
+        })
+    }
+
+    fn emit_pure(&self, _ty: &Type, expr: &Expr) -> Expr {
+        let krate = &self.krate;
+        let keep = &self.kind.0;
+        let (left, right) = split_pair(expr);
+        parse_quote!({
+            let (__parametrized_left, __parametrized_right) = (#left, #right);
+            if #keep(__parametrized_left, __parametrized_right) {
+                #krate::_imp::Vec::from([#krate::ParamDiff::Value {
+                    path: #krate::ParamPath::new(),
+                    left: __parametrized_left,
+                    right: __parametrized_right,
+                }])
+            } else {
+                #krate::_imp::Vec::new()
+            }
+        })
+    }
+
+    fn access_over_ref(&self) -> bool {
+        true
+    }
+
+    fn access_over_ref_mut(&self) -> bool {
+        true
+    }
+
+    fn native_reference(&self) -> TokenStream {
+        quote!(&)
+    }
+    fn do_deref(&self, elem: &Self::Elem) -> Self::Elem {
+        let (left, right) = split_pair(elem);
+        parse_quote!((*#left, *#right))
+    }
+}
+
+/// Which method [`EmitMap`] emits.
+#[derive(PartialEq, Eq, Hash, Debug, Clone)]
+pub enum MapKind {
+    /// `param_map`
+    Map,
+    /// `try_param_map`, with the ident of the error type.
+    TryMap(Ident),
+    /// `param_map_ref`, whose expressions are references.
+    MapRef,
+    /// `try_param_zip_with`, with the idents of the parameter of the other value
+    /// and of the error type. Expressions are pairs `(a, b)` of the fields of
+    /// both values.
+    Zip(Ident, Ident),
+    /// `param_filter_map`, which removes the values directly inside the types
+    /// implementing `ParametrizedFilterMap`.
+    FilterMap,
+    /// `param_unzip`, with the idents of the parameters of both halves.
+    /// Expressions evaluate to pairs `(a, b)` of the halves.
+    Unzip(Ident, Ident),
+}
+
+/// Splits the expression `(a, b)` of [`MapKind::Zip`].
+fn split_pair(expr: &Expr) -> (Expr, Expr) {
+    match expr {
+        Expr::Tuple(ExprTuple { elems, .. }) if elems.len() == 2 => {
+            (elems[0].clone(), elems[1].clone())
+        }
+        _ => unreachable!("zipped expressions are pairs"),
+    }
+}
+
+/// Emits the body of a mapping method, selected by [`MapKind`].
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct EmitMap(pub Ident, pub Ident, pub MapKind);
+impl Emitter for EmitContext<EmitMap> {
+    type Elem = (Expr, Type);
+
+    fn emit_pure(&self, _ty: &Type, expr: &(Expr, Type)) -> (Expr, Type) {
+        let map_fn = &self.kind.0;
+        let expr = expr.clone().0;
+        let map_param = &self.kind.1;
+        match &self.kind.2 {
+            MapKind::TryMap(_) => (parse_quote!(#map_fn(#expr)?), parse_quote!(#map_param)),
+            MapKind::Zip(..) => {
+                let (a, b) = split_pair(&expr);
+                (parse_quote!(#map_fn(#a, #b)?), parse_quote!(#map_param))
+            }
+            MapKind::Unzip(..) => (expr, parse_quote!(#map_param)),
+            _ => (parse_quote!(#map_fn(#expr)), parse_quote!(#map_param)),
+        }
+    }
+
+    fn access_over_ref(&self) -> bool {
+        false
+    }
+
+    fn access_over_ref_mut(&self) -> bool {
+        false
+    }
+    fn native_reference(&self) -> TokenStream {
+        quote!()
+    }
+
+    fn item(
+        &self,
+        _base_ty: &Type,
+        _index: usize,
+        _ty: &Type,
+        _: &Self::Elem,
+    ) -> Result<Option<Self::Elem>> {
+        unreachable!()
+    }
+
+    fn fold_item(
+        &self,
+        base_ty: Type,
+        (expr0, _): &<Self as Emitter>::Elem,
+        ty: &Type,
+        elem: Option<<Self as Emitter>::Elem>,
+        index: usize,
+    ) -> Result<Option<<Self as Emitter>::Elem>> {
+        let krate = &self.krate;
+        let map_param = &self.kind.1;
+        let replaced_ty = super::replace_type(
+            ty.clone(),
+            self.replacing_ty.clone(),
+            parse_quote!(#map_param),
+        );
+        let arg = Ident::new("__parametrized_arg", Span::call_site());
+        let other_arg = Ident::new("__parametrized_other_arg", Span::call_site());
+        let expr = if let Some((expr1, _)) = elem {
+            expr1
+        } else {
+            expr0.clone()
+        };
+        if let MapKind::Unzip(left, right) = &self.kind.2 {
+            return self.fold_unzip_item(base_ty, expr0, expr, ty, index, left, right);
+        }
+        let arg_expr: Expr = if let MapKind::Zip(..) = &self.kind.2 {
+            parse_quote!((#arg, #other_arg))
+        } else {
+            parse_quote!(#arg)
+        };
+        if let Some((inner_exp, inner_ty)) = self.emit(ty, &(arg_expr, ty.clone()))? {
+            let map_fn = &self.kind.0;
+            let inner_fn: Expr = if !self.recursive.is_empty() && ty == &self.replacing_ty {
+                // Pass the type-erased function itself, because a fresh closure
+                // would instantiate `param_map` of recursive types endlessly.
+                parse_quote!(&mut *#map_fn)
+            } else if self.kind.2 == MapKind::FilterMap && ty == &self.replacing_ty {
+                parse_quote!(&mut #map_fn)
+            } else if let MapKind::TryMap(map_err) = &self.kind.2 {
+                parse_quote!(|#arg| ::core::result::Result::Ok::<_, #map_err>({ #inner_exp }))
+            } else if let MapKind::Zip(_, zip_err) = &self.kind.2 {
+                parse_quote!(
+                    |#arg, #other_arg| ::core::result::Result::Ok::<_, #zip_err>({ #inner_exp })
+                )
+            } else {
+                parse_quote!(|#arg| { #inner_exp })
+            };
+            let expr = match &self.kind.2 {
+                MapKind::FilterMap if ty == &self.replacing_ty => parse_quote!(
+                    <#base_ty as #krate::ParametrizedFilterMap<
+                        #index,
+                        #replaced_ty
+                    >>::param_filter_map(
+                        #expr,
+                        #inner_fn
+                    )
+                ),
+                MapKind::Map | MapKind::FilterMap => parse_quote!(
+                    <#base_ty as #krate::ParametrizedMap<
+                        #index,
+                        #replaced_ty
+                    >>::param_map(
+                        #expr,
+                        #inner_fn
+                    )
+                ),
+                MapKind::TryMap(_) => parse_quote!(
+                    <#base_ty as #krate::ParametrizedTryMap<
+                        #index,
+                        #replaced_ty
+                    >>::try_param_map(
+                        #expr,
+                        #inner_fn
+                    )?
+                ),
+                MapKind::MapRef => parse_quote!(
+                    <#base_ty as #krate::ParametrizedMapRef<
+                        #index,
+                        #replaced_ty
+                    >>::param_map_ref(
+                        #expr,
+                        #inner_fn
+                    )
+                ),
+                MapKind::Unzip(..) => unreachable!("unzipped by `fold_unzip_item`"),
+                MapKind::Zip(..) if !matches!(&expr, Expr::Tuple(_)) => {
+                    return Err(Error::new(
+                        base_ty.span(),
+                        "Cannot zip a type having the parameter in several arguments",
+                    ));
+                }
+                MapKind::Zip(other_param, _) => {
+                    let other_ty = super::replace_type(
+                        base_ty.clone(),
+                        self.replacing_ty.clone(),
+                        parse_quote!(#other_param),
+                    );
+                    let (a, b) = split_pair(&expr);
+                    parse_quote!(
+                        <#base_ty as #krate::ParametrizedZip<
+                            #index,
+                            #other_ty,
+                            #replaced_ty
+                        >>::try_param_zip_with(
+                            #a,
+                            #b,
+                            #inner_fn
+                        )?
+                    )
+                }
+            };
+            Ok(Some((expr, inner_ty)))
+        } else {
+            Ok(Some((expr, ty.clone())))
+        }
+    }
+
+    fn fold(&self, _acc: &Self::Elem, _item: &Self::Elem) -> Self::Elem {
+        unreachable!()
+    }
+    fn do_deref(&self, (elem, ty): &Self::Elem) -> Self::Elem {
+        (parse_quote!(*#elem), ty.clone())
+    }
+}
+
+/// Emits the body of `param_bimap`, which maps `replacing_ty` with the function
+/// `f0` to `k0`, and `ty1` with `f1` to `k1`.
+#[derive(PartialEq, Eq, Hash, Debug)]
+pub struct EmitBiMap {
+    pub f0: Ident,
+    pub k0: Ident,
+    pub ty1: Type,
+    pub f1: Ident,
+    pub k1: Ident,
+}
+
+impl EmitContext<EmitBiMap> {
+    /// `ty` with both parameters replaced.
+    fn mapped_ty(&self, ty: &Type) -> Type {
+        let EmitBiMap { k0, ty1, k1, .. } = &self.kind;
+        let ty = super::replace_type(ty.clone(), self.replacing_ty.clone(), parse_quote!(#k0));
+        super::replace_type(ty, ty1.clone(), parse_quote!(#k1))
+    }
+
+    /// The function mapping the values of `ty`, if `ty` is a parameter itself.
+    fn param_fn(&self, ty: &Type) -> Option<&Ident> {
+        if ty == &self.replacing_ty {
+            Some(&self.kind.f0)
+        } else if ty == &self.kind.ty1 {
+            Some(&self.kind.f1)
+        } else {
+            None
+        }
+    }
+
+    /// The function passed for the `index`-th argument `ty`, which maps its values
+    /// with `inner`.
+    fn inner_fn(&self, ty: &Type, inner: &Expr) -> Expr {
+        let arg = Ident::new("__parametrized_arg", Span::call_site());
+        match self.param_fn(ty) {
+            // Pass the type-erased function itself, because a fresh closure
+            // would instantiate `param_bimap` of recursive types endlessly.
+            Some(f) if !self.recursive.is_empty() => parse_quote!(&mut *#f),
+            _ => parse_quote!(|#arg| { #inner }),
+        }
+    }
+}
+
+impl Emitter for EmitContext<EmitBiMap> {
+    type Elem = (Expr, Type);
+
+    fn emit_pure(&self, ty: &Type, (expr, _): &(Expr, Type)) -> (Expr, Type) {
+        let f = self.param_fn(ty).expect("a parameter");
+        (parse_quote!(#f(#expr)), self.mapped_ty(ty))
+    }
+
+    fn access_over_ref(&self) -> bool {
+        false
+    }
+
+    fn access_over_ref_mut(&self) -> bool {
+        false
+    }
+
+    fn native_reference(&self) -> TokenStream {
+        quote!()
+    }
+
+    fn other_replacing_ty(&self) -> Option<&Type> {
+        Some(&self.kind.ty1)
+    }
+
+    fn item(
+        &self,
+        _base_ty: &Type,
+        _index: usize,
+        _ty: &Type,
+        _: &Self::Elem,
+    ) -> Result<Option<Self::Elem>> {
+        unreachable!()
+    }
+
+    /// Maps two arguments of `base_ty` mentioning one parameter each at once by
+    /// `ParametrizedBiMap`, and any other argument by `ParametrizedMap`.
+    fn fold_item(
+        &self,
+        base_ty: Type,
+        (expr0, _): &<Self as Emitter>::Elem,
+        ty: &Type,
+        elem: Option<<Self as Emitter>::Elem>,
+        index: usize,
+    ) -> Result<Option<<Self as Emitter>::Elem>> {
+        let krate = &self.krate;
+        let arg = Ident::new("__parametrized_arg", Span::call_site());
+        let Some((inner, _)) = self.emit(ty, &(parse_quote!(#arg), ty.clone()))? else {
+            return Ok(elem);
+        };
+        if let Some([(i, ty_i), (j, ty_j)]) = &self.disjoint_pair(&base_ty) {
+            if index == *j {
+                // Already mapped together with the `i`-th argument.
+                return Ok(elem);
+            }
+            let (Some((inner_i, _)), Some((inner_j, _))) = (
+                self.emit(ty_i, &(parse_quote!(#arg), ty_i.clone()))?,
+                self.emit(ty_j, &(parse_quote!(#arg), ty_j.clone()))?,
+            ) else {
+                unreachable!("both arguments mention a parameter")
+            };
+            let (mapped_i, mapped_j) = (self.mapped_ty(ty_i), self.mapped_ty(ty_j));
+            let (fn_i, fn_j) = (self.inner_fn(ty_i, &inner_i), self.inner_fn(ty_j, &inner_j));
+            return Ok(Some((
+                parse_quote!(
+                    <#base_ty as #krate::ParametrizedBiMap<
+                        #i,
+                        #j,
+                        #mapped_i,
+                        #mapped_j
+                    >>::param_bimap(
+                        #expr0,
+                        #fn_i,
+                        #fn_j
+                    )
+                ),
+                self.mapped_ty(&base_ty),
+            )));
+        }
+        // The arguments are mapped one after another, so the type of the
+        // partially mapped value is left to inference.
+        let expr = elem.map_or_else(|| expr0.clone(), |(expr, _)| expr);
+        let mapped = self.mapped_ty(ty);
+        let inner_fn = self.inner_fn(ty, &inner);
+        Ok(Some((
+            parse_quote!(
+                #krate::ParametrizedMap::<#index, #mapped>::param_map(#expr, #inner_fn)
+            ),
+            self.mapped_ty(&base_ty),
+        )))
+    }
+
+    fn fold(&self, _acc: &Self::Elem, _item: &Self::Elem) -> Self::Elem {
+        unreachable!()
+    }
+
+    fn do_deref(&self, (elem, ty): &Self::Elem) -> Self::Elem {
+        (parse_quote!(*#elem), ty.clone())
+    }
+}
+
+/// The arguments of `ty` in the order [`EmitContext::emit`] visits them.
+fn type_args(ty: &Type) -> Vec<&Type> {
+    match ty {
+        Type::Slice(TypeSlice { elem, .. }) | Type::Array(TypeArray { elem, .. }) => {
+            vec![elem.as_ref()]
+        }
+        Type::Path(TypePath { path, .. }) => match path.segments.last().map(|seg| &seg.arguments) {
+            Some(PathArguments::AngleBracketed(abga)) => abga
+                .args
+                .iter()
+                .filter_map(|ga| {
+                    if let GenericArgument::Type(ty) = ga {
+                        Some(ty)
+                    } else {
+                        None
+                    }
+                })
+                .collect(),
+            _ => Vec::new(),
+        },
+        Type::Tuple(TypeTuple { elems, .. }) => elems.iter().collect(),
+        _ => Vec::new(),
+    }
+}
+
+impl EmitContext<EmitMap> {
+    /// Unzips the `index`-th argument of `base_ty`, by unzipping the values of
+    /// the argument into pairs first unless they are the parameter itself.
+    #[allow(clippy::too_many_arguments)]
+    fn fold_unzip_item(
+        &self,
+        base_ty: Type,
+        expr0: &Expr,
+        expr: Expr,
+        ty: &Type,
+        index: usize,
+        left: &Ident,
+        right: &Ident,
+    ) -> Result<Option<(Expr, Type)>> {
+        let krate = &self.krate;
+        let arg = Ident::new("__parametrized_arg", Span::call_site());
+        let Some((inner_exp, _)) = self.emit(ty, &(parse_quote!(#arg), ty.clone()))? else {
+            return Ok(Some((expr, ty.clone())));
+        };
+        if &expr != expr0 {
+            return Err(Error::new(
+                base_ty.span(),
+                "Cannot unzip a type having the parameter in several arguments",
+            ));
+        }
+        let replace =
+            |ty: &Type, to: Type| super::replace_type(ty.clone(), self.replacing_ty.clone(), to);
+        let left_ty = replace(ty, parse_quote!(#left));
+        let right_ty = replace(ty, parse_quote!(#right));
+        let base_ty = replace(&base_ty, parse_quote!((#left, #right)));
+        let (pairs, pairs_ty): (Expr, Type) = if ty == &self.replacing_ty {
+            (expr, base_ty)
+        } else {
+            (
+                parse_quote!(
+                    <#base_ty as #krate::ParametrizedMap<
+                        #index,
+                        (#left_ty, #right_ty)
+                    >>::param_map(
+                        #expr,
+                        |#arg| { #inner_exp }
+                    )
+                ),
+                parse_quote!(
+                    <#base_ty as #krate::ParametrizedMap<#index, (#left_ty, #right_ty)>>::Mapped
+                ),
+            )
+        };
+        Ok(Some((
+            parse_quote!(
+                <#pairs_ty as #krate::ParametrizedUnzip<
+                    #index,
+                    #left_ty,
+                    #right_ty
+                >>::param_unzip(#pairs)
+            ),
+            ty.clone(),
+        )))
     }
 }
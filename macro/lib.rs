@@ -16,12 +16,25 @@ enum TraitTarget {
     IterMut,
     IntoIter,
     Map,
+    TryMap,
+    MapRef,
+    Zip,
+    Unzip,
+    Retain,
+    FilterMap,
+    Update,
+    Path,
+    Diff,
+    /// `ParametrizedBiMap`, with the index of the other parameter.
+    BiMap(usize),
+    /// `ParametrizedUnion`, with the index of the other parameter.
+    Union(usize),
+    /// `ParametrizedUnionMut`, with the index of the other parameter.
+    UnionMut(usize),
 }
 
 fn squash_minlens(outs: &[Expr]) -> Expr {
-    if outs.len() == 0 {
-        abort!(Span::call_site(), "needs one or more variants");
-    }
+    assert!(!outs.is_empty(), "empty enums are rejected by `inner()`");
     let mut acc = outs[outs.len() - 1].clone();
     if outs.len() >= 2 {
         for out in outs[0..(outs.len() - 1)].iter().rev() {
@@ -38,9 +51,7 @@ fn squash_minlens(outs: &[Expr]) -> Expr {
     }
 }
 fn squash_maxlens(outs: &[Expr]) -> Expr {
-    if outs.len() == 0 {
-        abort!(Span::call_site(), "needs one or more variants");
-    }
+    assert!(!outs.is_empty(), "empty enums are rejected by `inner()`");
     let mut acc = outs[outs.len() - 1].clone();
     if outs.len() >= 2 {
         for out in outs[0..(outs.len() - 1)].iter().rev() {
@@ -60,31 +71,825 @@ fn squash_maxlens(outs: &[Expr]) -> Expr {
     }
 }
 
+/// Replaces the type `.0` with `.1` wherever it appears.
+struct TypeReplacer(Type, Type);
+
+impl syn::visit_mut::VisitMut for TypeReplacer {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        if ty == &self.0 {
+            *ty = self.1.clone();
+        } else {
+            syn::visit_mut::visit_type_mut(self, ty)
+        }
+    }
+}
+
 fn replace_type(mut ty: Type, from: Type, to: Type) -> Type {
     use syn::visit_mut::VisitMut;
-    struct Visitor(Type, Type);
-    impl VisitMut for Visitor {
-        fn visit_type_mut(&mut self, ty: &mut Type) {
-            if ty == &self.0 {
-                *ty = self.1.clone();
+    TypeReplacer(from, to).visit_type_mut(&mut ty);
+    ty
+}
+
+fn contains_type(ty: &Type, target: &Type) -> bool {
+    use proc_macro2::TokenTree;
+    use syn::visit::Visit;
+    fn mentions(tokens: TokenStream, ident: &Ident) -> bool {
+        tokens.into_iter().any(|tt| match tt {
+            TokenTree::Ident(i) => &i == ident,
+            TokenTree::Group(g) => mentions(g.stream(), ident),
+            _ => false,
+        })
+    }
+    struct Visitor<'a>(&'a Type, bool);
+    impl<'ast> Visit<'ast> for Visitor<'_> {
+        fn visit_type(&mut self, ty: &'ast Type) {
+            if ty == self.0 {
+                self.1 = true;
             } else {
-                syn::visit_mut::visit_type_mut(self, ty)
+                syn::visit::visit_type(self, ty)
+            }
+        }
+        // The tokens of macros are opaque, so look for the ident of a type parameter.
+        fn visit_macro(&mut self, mac: &'ast Macro) {
+            if let Type::Path(TypePath { qself: None, path }) = self.0 {
+                if let Some(ident) = path.get_ident() {
+                    self.1 |= mentions(mac.tokens.clone(), ident);
+                }
             }
         }
     }
-    Visitor(from, to).visit_type_mut(&mut ty);
-    ty
+    let mut visitor = Visitor(target, false);
+    visitor.visit_type(ty);
+    visitor.1
+}
+
+/// Whether `path` names the type `target`, whatever the generic arguments.
+fn same_path(path: &Path, target: &Path) -> bool {
+    path.leading_colon.is_some() == target.leading_colon.is_some()
+        && path.segments.len() == target.segments.len()
+        && path
+            .segments
+            .iter()
+            .zip(&target.segments)
+            .all(|(seg, target)| seg.ident == target.ident)
+}
+
+fn mentions_path(ty: &Type, paths: &[Path]) -> bool {
+    use syn::visit::Visit;
+    struct Visitor<'a>(&'a [Path], bool);
+    impl<'ast> Visit<'ast> for Visitor<'_> {
+        fn visit_type_path(&mut self, ty: &'ast TypePath) {
+            if ty.qself.is_none() && self.0.iter().any(|path| same_path(&ty.path, path)) {
+                self.1 = true;
+            }
+            syn::visit::visit_type_path(self, ty)
+        }
+    }
+    let mut visitor = Visitor(paths, false);
+    visitor.visit_type(ty);
+    visitor.1
+}
+
+/// The containers which this crate implements the traits for, which are never
+/// recursive.
+const CONTAINERS: &[&str] = &[
+    "Box",
+    "Option",
+    "Result",
+    "Vec",
+    "VecDeque",
+    "LinkedList",
+    "BTreeSet",
+    "HashSet",
+    "BinaryHeap",
+    "BTreeMap",
+    "HashMap",
+    "PhantomData",
+];
+
+/// The types held in a `Box` by `ty` which mention `param`, other than the
+/// containers of this crate. Mutually recursive types usually refer to each
+/// other this way, so these are taken to be recursive.
+fn boxed_paths(ty: &Type, param: &Type) -> Vec<Path> {
+    use syn::visit::Visit;
+    struct Visitor<'a>(&'a Type, Vec<Path>);
+    impl<'ast> Visit<'ast> for Visitor<'_> {
+        fn visit_type_path(&mut self, ty: &'ast TypePath) {
+            if let Some(PathSegment {
+                ident,
+                arguments: PathArguments::AngleBracketed(args),
+            }) = ty.path.segments.last()
+            {
+                if ident == "Box" {
+                    if let Some(GenericArgument::Type(Type::Path(TypePath { qself: None, path }))) =
+                        args.args.first()
+                    {
+                        let inner = Type::Path(TypePath {
+                            qself: None,
+                            path: path.clone(),
+                        });
+                        if &inner != self.0
+                            && contains_type(&inner, self.0)
+                            && path
+                                .segments
+                                .last()
+                                .is_some_and(|seg| !CONTAINERS.iter().any(|c| seg.ident == c))
+                        {
+                            let mut path = path.clone();
+                            for seg in &mut path.segments {
+                                seg.arguments = PathArguments::None;
+                            }
+                            self.1.push(path);
+                        }
+                    }
+                }
+            }
+            syn::visit::visit_type_path(self, ty)
+        }
+    }
+    let mut visitor = Visitor(param, Vec::new());
+    visitor.visit_type(ty);
+    visitor.1
+}
+
+/// Everything [`TraitTarget::emit`] needs to know about the annotated item.
+struct ImplInput<'a> {
+    krate: &'a Path,
+    ident: &'a Ident,
+    generics: &'a Generics,
+    /// The `PARAM` of the implemented traits.
+    param_index: usize,
+    replacing_ty: Type,
+    /// Whether `PARAM` counts all generic parameters, by the `legacy_index`
+    /// option.
+    legacy_index: bool,
+    self_val: Ident,
+    tys_exprs: Vec<Vec<(Type, Expr)>>,
+    attrs: &'a FieldAttrs,
+    needs_ref: bool,
+    /// The `PathSegment`s leading to each field of each item, separated by
+    /// commas.
+    segments: Vec<Vec<TokenStream>>,
+    /// Paths of the types which refer back to the annotated item. Empty unless
+    /// some field actually mentions one of them.
+    recursive: Vec<Path>,
+    /// Whether the iterators are boxed by the `boxed` option or feature.
+    boxed: bool,
+    /// Whether the `named_iter` option asks for named iterator types.
+    named_iter: bool,
+    vis: &'a Visibility,
+    /// Names of the variants, or empty for structs.
+    variants: Vec<Ident>,
+}
+
+impl<'a> ImplInput<'a> {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        krate: &'a Path,
+        ident: &'a Ident,
+        generics: &'a Generics,
+        param_index: usize,
+        tys_exprs: Vec<Vec<(Type, Expr)>>,
+        attrs: &'a FieldAttrs,
+        needs_ref: bool,
+        segments: Vec<Vec<TokenStream>>,
+        vis: &'a Visibility,
+        variants: Vec<Ident>,
+        args: &Arguments,
+    ) -> Self {
+        let (_, ty_generics, _) = generics.split_for_impl();
+        let self_ty: Type = parse_quote!(#ident #ty_generics);
+        let tys_exprs = tys_exprs
+            .into_iter()
+            .map(|item| {
+                item.into_iter()
+                    .map(|(ty, expr)| (replace_type(ty, parse_quote!(Self), self_ty.clone()), expr))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let replacing_ty = get_replacing_ty(generics, param_index);
+        let traversed = || {
+            tys_exprs
+                .iter()
+                .flatten()
+                .zip(attrs.modes.iter().flatten())
+                .filter(|(_, mode)| matches!(mode, FieldMode::Traverse))
+                .map(|((ty, _), _)| ty)
+        };
+        let mut recursive = core::iter::once(Path::from(ident.clone()))
+            .chain(args.recursive.iter().cloned())
+            .collect::<Vec<_>>();
+        for path in traversed().flat_map(|ty| boxed_paths(ty, &replacing_ty)) {
+            if !recursive.iter().any(|known| same_path(&path, known)) {
+                recursive.push(path);
+            }
+        }
+        let is_recursive = traversed().any(|ty| mentions_path(ty, &recursive));
+        ImplInput {
+            krate,
+            ident,
+            generics,
+            param_index: trait_index(generics, param_index, args.legacy_index),
+            replacing_ty,
+            legacy_index: args.legacy_index,
+            self_val: Ident::new("self", Span::call_site()),
+            tys_exprs,
+            attrs,
+            needs_ref,
+            segments,
+            recursive: if is_recursive { recursive } else { Vec::new() },
+            boxed: args.boxed || cfg!(feature = "boxed"),
+            named_iter: args.named_iter,
+            vis,
+            variants,
+        }
+    }
+
+    fn is_recursive(&self) -> bool {
+        !self.recursive.is_empty()
+    }
+
+    /// Whether the iterators are boxed (or collected, for `IntoIter` of types
+    /// which are not recursive), by the `boxed` option or for recursive types.
+    fn is_boxed(&self) -> bool {
+        self.boxed || self.is_recursive()
+    }
+
+    /// Whether the iterators of the variants are chained into a single type by
+    /// [`Self::chain_variants()`], which is the case for enums with variants
+    /// under `#[cfg(..)]` unless the iterators are `boxed`.
+    fn is_chained(&self, boxed: bool) -> bool {
+        !boxed && self.attrs.item_cfgs.iter().any(Option::is_some)
+    }
+
+    /// Chains the iterators `exprs` of the variants, of the types `tys`, into a
+    /// single type, where each variant fills its own slot and leaves the others
+    /// empty. The iterator type then does not depend on which variants are
+    /// compiled in.
+    fn chain_variants(&self, exprs: Vec<Expr>, tys: Vec<Type>) -> (Vec<Expr>, Vec<Type>) {
+        let krate = self.krate;
+        let slot = |expr: Option<&Expr>| -> Expr {
+            let option = match expr {
+                Some(expr) => quote!(::core::option::Option::Some(#expr)),
+                None => quote!(::core::option::Option::None),
+            };
+            parse_quote!(#krate::Flatten::new(::core::option::Option::into_iter(#option)))
+        };
+        let chained = (0..exprs.len())
+            .map(|index| {
+                exprs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, expr)| slot((i == index).then_some(expr)))
+                    .reduce(|acc, item| parse_quote!(#krate::Chain::new(#acc, #item)))
+                    .unwrap()
+            })
+            .collect();
+        let ty = tys
+            .iter()
+            .map(|ty| cfg_iter_ty(krate, ty))
+            .reduce(|acc, ty| parse_quote!(#krate::Chain<#acc, #ty>))
+            .unwrap();
+        (chained, vec![ty])
+    }
+
+    /// The name of the iterator type ending with `suffix`, such as `InstParamIter`,
+    /// if the `named_iter` option asks for it. The name of the parameter replaces
+    /// `Param` when the item has several type parameters.
+    fn named_iter(&self, suffix: &str) -> Option<Ident> {
+        self.named_iter.then(|| self.iter_name("", suffix))
+    }
+
+    /// The name of the iterator type ending with `suffix`, if the `named_iter`
+    /// option asks for it or the item is an enum with several variants whose
+    /// iterators are not `erased` into a single type. The named type forwards
+    /// the iterator traits of the variants, and is hidden unless asked for.
+    fn enum_iter_name(&self, erased: bool, suffix: &str) -> Option<Ident> {
+        if !self.named_iter && !erased && self.tys_exprs.len() > 1 {
+            Some(self.iter_name("__Parametrized", suffix))
+        } else {
+            self.named_iter(suffix)
+        }
+    }
+
+    /// The name of the iterator type ending with `suffix`, whether the
+    /// `named_iter` option asks for it or not, starting with `prefix`.
+    fn iter_name(&self, prefix: &str, suffix: &str) -> Ident {
+        let param = if self.generics.type_params().count() > 1 {
+            let replacing_ty = &self.replacing_ty;
+            quote!(#replacing_ty).to_string()
+        } else {
+            "Param".to_owned()
+        };
+        Ident::new(
+            &format!(
+                "{}{}{}{}",
+                prefix,
+                self.ident,
+                param.trim_start_matches("r#"),
+                suffix
+            ),
+            self.ident.span(),
+        )
+    }
+
+    /// Whether the named iterator type is a struct rather than an enum, which is
+    /// the case for structs and for iterators `erased` into a single type.
+    fn is_named_iter_struct(&self, erased: bool) -> bool {
+        erased || self.variants.is_empty() && self.tys_exprs.len() == 1
+    }
+
+    /// Wraps the iterator `expr` of the `index`-th item into the named iterator
+    /// type `name`, which is a struct if `erased`.
+    fn wrap_named_iter(
+        &self,
+        name: &Ident,
+        erased: bool,
+        index: usize,
+        expr: &TokenStream,
+    ) -> TokenStream {
+        if self.is_named_iter_struct(erased) {
+            quote!(#name(#expr, ::core::marker::PhantomData))
+        } else {
+            quote!(#name::#{&self.variants[index]}(#expr))
+        }
+    }
+
+    /// Emits the iterator type `name` of the `named_iter` option, which wraps the
+    /// iterator types `tys` of the items, or the single one if `erased`, and
+    /// yields `item`, documented as the iterator of `method`. Returns the
+    /// definition and the type naming it, whose generic arguments are `lt`
+    /// followed by those of the item.
+    fn emit_named_iter(
+        &self,
+        name: &Ident,
+        erased: bool,
+        lt: Option<&Lifetime>,
+        tys: &[Type],
+        item: &Type,
+        method: &str,
+    ) -> (TokenStream, Type) {
+        let mut generics = self.generics.clone();
+        if let Some(lt) = lt {
+            // The same as `Self: 'lt`, which the associated type requires.
+            let outlives = generics
+                .params
+                .iter()
+                .filter_map(|param| match param {
+                    GenericParam::Lifetime(LifetimeParam { lifetime, .. }) => {
+                        Some(parse_quote!(#lifetime: #lt))
+                    }
+                    GenericParam::Type(TypeParam { ident, .. }) => Some(parse_quote!(#ident: #lt)),
+                    GenericParam::Const(_) => None,
+                })
+                .collect::<Vec<WherePredicate>>();
+            generics.params.insert(0, parse_quote!(#lt));
+            generics.make_where_clause().predicates.extend(outlives);
+        }
+        let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let phantom = quote! {
+            ::core::marker::PhantomData<fn() -> (
+                #(for param in &generics.params) {
+                    #(if let GenericParam::Lifetime(LifetimeParam { lifetime, .. }) = param) {
+                        &#lifetime (),
+                    }
+                    #(if let GenericParam::Type(TypeParam { ident, .. }) = param) {
+                        ::core::marker::PhantomData<#ident>,
+                    }
+                }
+            )>
+        };
+        // `where` clauses requiring `bound` of every wrapped iterator. The item is
+        // given again, since these clauses hide the `Iterator` impl of the type.
+        let bounded = |bound: TokenStream| -> WhereClause {
+            let mut where_clause = where_clause.cloned().unwrap_or(parse_quote!(where));
+            where_clause.predicates.extend(
+                tys.iter()
+                    .map(|ty| -> WherePredicate { parse_quote!(#ty: #bound) }),
+            );
+            where_clause
+        };
+        let inner: Ident = parse_quote!(__parametrized_inner);
+        let is_struct = self.is_named_iter_struct(erased);
+        // Forwards to the wrapped iterator, which `body` refers as `inner`.
+        let forward = |by_value: bool, body: TokenStream| -> TokenStream {
+            if is_struct {
+                quote! {
+                    match self {
+                        #name(#inner, _) => #body,
+                    }
+                }
+            } else {
+                quote! {
+                    match self {
+                        #(for variant in &self.variants) {
+                            #name::#variant(#inner) => #body,
+                        }
+                        #name::__ParametrizedPhantom(__parametrized_never, _) => {
+                            #(if by_value) { match __parametrized_never {} }
+                            #(else) { match *__parametrized_never {} }
+                        }
+                    }
+                }
+            }
+        };
+        let item_ident = self.ident;
+        let def = quote! {
+            #[doc = #{format!("The iterator of `{}` for [`{}`].", method, item_ident)}]
+            #(if is_struct) {
+                #{self.vis} struct #name #impl_generics (
+                    #(#tys)*,
+                    #phantom,
+                ) #where_clause;
+            } #(else) {
+                #{self.vis} enum #name #impl_generics #where_clause {
+                    #(for (variant, ty) in self.variants.iter().zip(tys)) {
+                        #[doc = #{format!("Iterates the variant [`{}::{}`].", item_ident, variant)}]
+                        #variant(#ty),
+                    }
+                    #[doc(hidden)]
+                    #[allow(dead_code)]
+                    __ParametrizedPhantom(::core::convert::Infallible, #phantom),
+                }
+            }
+            impl #impl_generics ::core::iter::Iterator for #name #ty_generics #where_clause {
+                type Item = #item;
+                fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                    #{forward(false, quote!(::core::iter::Iterator::next(#inner)))}
+                }
+                fn size_hint(&self) -> (usize, ::core::option::Option<usize>) {
+                    #{forward(false, quote!(::core::iter::Iterator::size_hint(#inner)))}
+                }
+                fn nth(&mut self, __parametrized_n: usize) -> ::core::option::Option<Self::Item> {
+                    #{forward(false, quote!(::core::iter::Iterator::nth(#inner, __parametrized_n)))}
+                }
+                fn fold<__ParametrizedB, __ParametrizedF>(
+                    self,
+                    __parametrized_init: __ParametrizedB,
+                    __parametrized_f: __ParametrizedF,
+                ) -> __ParametrizedB
+                where
+                    __ParametrizedF: ::core::ops::FnMut(__ParametrizedB, Self::Item) -> __ParametrizedB,
+                {
+                    #{forward(true, quote!(
+                        ::core::iter::Iterator::fold(#inner, __parametrized_init, __parametrized_f)
+                    ))}
+                }
+            }
+            impl #impl_generics ::core::iter::DoubleEndedIterator for #name #ty_generics
+            #{bounded(quote!(::core::iter::Iterator<Item = #item> + ::core::iter::DoubleEndedIterator))}
+            {
+                fn next_back(&mut self) -> ::core::option::Option<Self::Item> {
+                    #{forward(false, quote!(::core::iter::DoubleEndedIterator::next_back(#inner)))}
+                }
+            }
+            impl #impl_generics ::core::iter::ExactSizeIterator for #name #ty_generics
+            #{bounded(quote!(::core::iter::Iterator<Item = #item> + ::core::iter::ExactSizeIterator))}
+            {
+                fn len(&self) -> usize {
+                    #{forward(false, quote!(::core::iter::ExactSizeIterator::len(#inner)))}
+                }
+            }
+            impl #impl_generics ::core::iter::FusedIterator for #name #ty_generics
+            #{bounded(quote!(::core::iter::Iterator<Item = #item> + ::core::iter::FusedIterator))}
+            {}
+            impl #impl_generics ::core::clone::Clone for #name #ty_generics
+            #{bounded(quote!(::core::clone::Clone))}
+            {
+                fn clone(&self) -> Self {
+                    #(if is_struct) {
+                        #name(::core::clone::Clone::clone(&self.0), ::core::marker::PhantomData)
+                    } #(else) {
+                        match self {
+                            #(for variant in &self.variants) {
+                                #name::#variant(#inner) => #name::#variant(::core::clone::Clone::clone(#inner)),
+                            }
+                            #name::__ParametrizedPhantom(__parametrized_never, _) => match *__parametrized_never {},
+                        }
+                    }
+                }
+            }
+        };
+        (def, parse_quote!(#name #ty_generics))
+    }
+
+    fn context<K>(&self, kind: K) -> generator::EmitContext<K> {
+        generator::EmitContext {
+            kind,
+            krate: self.krate.clone(),
+            replacing_ty: self.replacing_ty.clone(),
+            recursive: self.recursive.clone(),
+        }
+    }
+
+    /// Rejects the traversed fields holding values which cannot be removed,
+    /// such as the parameter itself or the elements of `Vec<(T, bool)>`.
+    fn check_removable(&self) -> Result<()> {
+        for ((ty, expr), mode) in self
+            .tys_exprs
+            .iter()
+            .flatten()
+            .zip(self.attrs.modes.iter().flatten())
+        {
+            if mode == &FieldMode::Traverse && holds_unremovable(ty, &self.replacing_ty) {
+                return Err(Error::new(
+                    ty.span(),
+                    format!(
+                        "The values of field `{}` cannot be removed, because some of them are \
+                         not directly inside a collection or an `Option`",
+                        field_name(expr)
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// The parameter renamed to `new`, keeping its bounds.
+    fn renamed_param(&self, new: &Ident) -> TokenStream {
+        self.renamed_param_of(&self.replacing_ty, new)
+    }
+
+    /// The parameter `ty` renamed to `new`, keeping its bounds.
+    fn renamed_param_of(&self, ty: &Type, new: &Ident) -> TokenStream {
+        self.generics
+            .type_params()
+            .find(|p| {
+                let ident = &p.ident;
+                &parse_quote!(#ident) as &Type == ty
+            })
+            .map(|p| {
+                let mut p = p.clone();
+                p.ident = new.clone();
+                quote!(#p)
+            })
+            .unwrap_or(quote!(#new))
+    }
+
+    /// Extends `where_clause` like `#[derive(Clone)]`, requiring the other type
+    /// parameters to be `Clone`, for the methods cloning the fields which
+    /// mention them.
+    fn clone_where_clause(&self, where_clause: Option<WhereClause>) -> WhereClause {
+        let mut where_clause = where_clause.unwrap_or(WhereClause {
+            where_token: Default::default(),
+            predicates: Default::default(),
+        });
+        for p in self.generics.type_params() {
+            let ident = &p.ident;
+            if &parse_quote!(#ident) as &Type != &self.replacing_ty {
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#ident: ::core::clone::Clone));
+            }
+        }
+        where_clause
+    }
+
+    /// Field types with their access expressions, borrowed by `reference` when
+    /// the expressions are places rather than bindings.
+    fn borrowed_tys_exprs(
+        &self,
+        reference: TokenStream,
+    ) -> impl Iterator<Item = Vec<(Type, Expr)>> + '_ {
+        self.tys_exprs.iter().map(move |item| {
+            item.iter()
+                .map(|(ty, expr)| {
+                    if self.needs_ref {
+                        (ty.clone(), parse_quote!(#reference #expr))
+                    } else {
+                        (ty.clone(), expr.clone())
+                    }
+                })
+                .collect()
+        })
+    }
+
+    /// Field types paired with the parameter type, as consumed by the emitters
+    /// of iterator types.
+    fn param_tys(&self) -> impl Iterator<Item = Vec<(Type, Type)>> + '_ {
+        self.tys_exprs.iter().map(move |item| {
+            item.iter()
+                .map(|(ty, _)| (ty.clone(), self.replacing_ty.clone()))
+                .collect()
+        })
+    }
+
+    /// The bodies of `param_get` for each item, or of `param_get_mut` if
+    /// `mutable`, which skip whole fields by their lengths.
+    fn emit_get(&self, mutable: bool) -> Result<Vec<TokenStream>> {
+        let n: Ident = parse_quote!(__parametrized_n);
+        let items = self
+            .borrowed_tys_exprs(quote!(&))
+            .zip(self.borrowed_tys_exprs(if mutable { quote!(&mut) } else { quote!(&) }))
+            .map(|(shared, exprs)| {
+                shared
+                    .into_iter()
+                    .zip(exprs)
+                    .map(|((ty, shared), (_, expr))| (ty, (shared, expr)))
+                    .collect::<Vec<_>>()
+            });
+        Ok(self
+            .emit_items(
+                &self.context(generator::EmitGet(mutable)),
+                items,
+                |path, (shared, expr)| {
+                    let iter_fn = if mutable {
+                        quote!(iter_mut)
+                    } else {
+                        quote!(iter)
+                    };
+                    (
+                        shared.clone(),
+                        parse_quote!({
+                            let __parametrized_len = #path::len(#shared);
+                            if #n < __parametrized_len {
+                                #path::#iter_fn(#expr).nth(#n)
+                            } else {
+                                #n -= __parametrized_len;
+                                ::core::option::Option::None
+                            }
+                        }),
+                    )
+                },
+                |cfg, (shared, get)| {
+                    (
+                        shared,
+                        cfg_select(cfg, get, quote!(::core::option::Option::None)),
+                    )
+                },
+            )?
+            .into_iter()
+            .map(|elem| match elem {
+                Some((_, get)) => quote!(#get),
+                None => quote!(::core::option::Option::None),
+            })
+            .collect())
+    }
+
+    /// The bodies of the internal iteration method selected by `kind` for each
+    /// item, calling `loop_fn` on every value.
+    fn emit_loop(&self, loop_fn: &Ident, kind: generator::LoopKind) -> Result<Vec<TokenStream>> {
+        use generator::LoopKind;
+        let acc: Ident = parse_quote!(__parametrized_acc);
+        let loop_fn_ref: Expr = if self.is_recursive() {
+            parse_quote!(&mut *#loop_fn)
+        } else {
+            parse_quote!(&mut #loop_fn)
+        };
+        let (items, empty): (Vec<_>, Expr) = match kind {
+            LoopKind::ForEach => (
+                self.borrowed_tys_exprs(quote!(&)).collect(),
+                parse_quote!(()),
+            ),
+            LoopKind::ForEachMut => (
+                self.borrowed_tys_exprs(quote!(&mut)).collect(),
+                parse_quote!(()),
+            ),
+            LoopKind::TryForEach => (
+                self.borrowed_tys_exprs(quote!(&)).collect(),
+                parse_quote!(::core::ops::ControlFlow::Continue(())),
+            ),
+            LoopKind::Fold => (self.tys_exprs.clone(), parse_quote!(#acc)),
+        };
+        Ok(self
+            .emit_items(
+                &self.context(generator::EmitLoop(loop_fn.clone(), kind)),
+                items.into_iter(),
+                |path, expr| match kind {
+                    LoopKind::ForEach => parse_quote!(#path::iter(#expr).for_each(#loop_fn_ref)),
+                    LoopKind::ForEachMut => {
+                        parse_quote!(#path::iter_mut(#expr).for_each(#loop_fn_ref))
+                    }
+                    LoopKind::TryForEach => {
+                        parse_quote!(#path::iter(#expr).try_for_each(#loop_fn_ref))
+                    }
+                    LoopKind::Fold => {
+                        parse_quote!(#path::into_iter(#expr).fold(#acc, #loop_fn_ref))
+                    }
+                },
+                |cfg, expr| cfg_select(cfg, expr, &empty),
+            )?
+            .into_iter()
+            .map(|expr| {
+                let expr = expr.unwrap_or_else(|| empty.clone());
+                if matches!(kind, LoopKind::ForEach | LoopKind::ForEachMut) {
+                    quote!(#expr;)
+                } else {
+                    quote!(#expr)
+                }
+            })
+            .collect())
+    }
+
+    /// Collects the `Vec`s given by `elems` for each item, putting the segments
+    /// of the field in front of the path of every element.
+    fn collect_prefixed(&self, elems: Vec<Vec<Option<Expr>>>) -> Vec<TokenStream> {
+        let krate = self.krate;
+        let out: Ident = parse_quote!(__parametrized_out);
+        elems
+            .into_iter()
+            .zip(&self.segments)
+            .zip(&self.attrs.cfgs)
+            .map(|((elems, segments), cfgs)| {
+                quote! {
+                    #[allow(unused_mut)]
+                    let mut #out = #krate::_imp::Vec::new();
+                    #(for ((elem, segments), cfg) in elems.iter().zip(segments).zip(cfgs)) {
+                        #(if let Some(elem) = elem) {
+                            #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                            #out.extend(
+                                #krate::_imp::Vec::into_iter(#elem)
+                                    .map(|__parametrized_elem| __parametrized_elem.prefixed(&[#segments])),
+                            );
+                        }
+                    }
+                    #out
+                }
+            })
+            .collect()
+    }
+
+    /// Emits the element of every field of each item with `ctx`. Fields marked
+    /// with `#[param(skip)]` or `#[param(opaque)]` are left out, and the element
+    /// of fields marked with `#[param(with = ..)]` is given by `with`.
+    fn emit_fields<K, E: Clone>(
+        &self,
+        ctx: &generator::EmitContext<K>,
+        items: impl Iterator<Item = Vec<(Type, E)>>,
+        with: impl Fn(&Path, &E) -> E,
+    ) -> Result<Vec<Vec<Option<E>>>>
+    where
+        generator::EmitContext<K>: generator::Emitter<Elem = E>,
+    {
+        items
+            .zip(&self.attrs.modes)
+            .map(|(item, modes)| {
+                item.iter()
+                    .zip(modes)
+                    .map(|((ty, elem), mode)| match mode {
+                        FieldMode::Traverse => ctx.emit(ty, elem),
+                        FieldMode::Skip | FieldMode::Opaque => Ok(None),
+                        FieldMode::With(path) => Ok(Some(with(path, elem))),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Folds the elements given by [`Self::emit_fields()`] for each item. The
+    /// element of a field under `#[cfg(..)]` is passed to `erase` with its
+    /// predicate, which must return an element valid whether the field exists
+    /// or not.
+    fn emit_items<K, E: Clone>(
+        &self,
+        ctx: &generator::EmitContext<K>,
+        items: impl Iterator<Item = Vec<(Type, E)>>,
+        with: impl Fn(&Path, &E) -> E,
+        erase: impl Fn(&TokenStream, E) -> E,
+    ) -> Result<Vec<Option<E>>>
+    where
+        generator::EmitContext<K>: generator::Emitter<Elem = E>,
+    {
+        Ok(self
+            .emit_fields(ctx, items, with)?
+            .into_iter()
+            .zip(&self.attrs.cfgs)
+            .map(|(elems, cfgs)| {
+                ctx.fold_elems(elems.into_iter().zip(cfgs).map(|(elem, cfg)| match cfg {
+                    Some(cfg) => elem.map(|elem| erase(cfg, elem)),
+                    None => elem,
+                }))
+            })
+            .collect())
+    }
 }
 
 impl TraitTarget {
     fn make_enough(mut set: HashSet<Self>) -> HashSet<Self> {
+        if set.contains(&Self::TryMap)
+            || set.contains(&Self::Zip)
+            || set.contains(&Self::FilterMap)
+            || set.iter().any(|tr| matches!(tr, Self::BiMap(_)))
+        {
+            set.insert(Self::Map);
+        }
         if set.contains(&Self::Map) {
             set.insert(Self::IntoIter);
         }
         if set.contains(&Self::IntoIter) {
             set.insert(Self::Default);
         }
-        if set.contains(&Self::IterMut) {
+        if set.contains(&Self::IterMut)
+            || set.contains(&Self::MapRef)
+            || set.contains(&Self::Unzip)
+            || set.contains(&Self::Retain)
+            || set.contains(&Self::Update)
+            || set.contains(&Self::Path)
+            || set.contains(&Self::Diff)
+            || set
+                .iter()
+                .any(|tr| matches!(tr, Self::Union(_) | Self::UnionMut(_)))
+        {
             set.insert(Self::Default);
         }
         set
@@ -92,128 +897,162 @@ impl TraitTarget {
 
     fn emit(
         &self,
-        krate: &Path,
-        ident: &Ident,
-        generics: &Generics,
-        param_index: usize,
-        replacing_ty: &Type,
-        self_val: &Ident,
-        tys_exprs: &[Vec<(Type, Expr)>],
+        input: &ImplInput,
         mut f: impl FnMut(&[TokenStream]) -> TokenStream,
         mut emit_map_f: impl FnMut(&[Vec<Expr>]) -> TokenStream,
-        needs_ref: bool,
+        mut emit_zip_f: impl FnMut(&[Vec<Expr>]) -> TokenStream,
+        mut emit_unzip_f: impl FnMut(&[Vec<Expr>]) -> TokenStream,
+        mut emit_pair_f: impl FnMut(&[TokenStream]) -> TokenStream,
     ) -> Result<TokenStream> {
+        let ImplInput {
+            krate,
+            ident,
+            generics,
+            param_index,
+            replacing_ty,
+            self_val,
+            tys_exprs,
+            ..
+        } = input;
         let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+        let boxed = input.is_boxed();
         match self {
             Self::Default => {
-                let out_len = tys_exprs
-                    .iter()
-                    .map(|item| {
-                        Ok(generator::EmitContext {
-                            kind: generator::EmitLen,
-                            krate: krate.clone(),
-                            replacing_ty: replacing_ty.clone(),
-                        }
-                        .emit_for_tys_exprs(item.iter().map(|(a, b)| {
-                            (
-                                a.clone(),
-                                if needs_ref {
-                                    parse_quote! {&#b}
-                                } else {
-                                    parse_quote! {#b}
-                                },
-                            )
-                        }))?
-                        .unwrap_or(parse_quote!(0usize)))
-                    })
-                    .collect::<Result<Vec<_>>>()?
+                let out_len = input
+                    .emit_items(
+                        &input.context(generator::EmitLen),
+                        input.borrowed_tys_exprs(quote!(&)),
+                        |path, expr| parse_quote!(#path::len(#expr)),
+                        |cfg, expr| cfg_select(cfg, expr, quote!(0usize)),
+                    )?
                     .into_iter()
-                    .map(|expr| quote!(#expr))
+                    .map(|expr| {
+                        let expr = expr.unwrap_or(parse_quote!(0usize));
+                        quote!(#expr)
+                    })
                     .collect::<Vec<_>>();
-                let out_max_len = tys_exprs
-                    .iter()
-                    .map(|item| {
-                        Ok(generator::EmitContext {
-                            kind: generator::EmitMaxLen,
-                            krate: krate.clone(),
-                            replacing_ty: replacing_ty.clone(),
+                // Variants compiled out must not affect the bounds.
+                let out_max_len = input
+                    .emit_items(
+                        &input.context(generator::EmitMaxLen),
+                        input.borrowed_tys_exprs(quote!(&)),
+                        |_, _| parse_quote!(::core::option::Option::<usize>::None),
+                        |cfg, expr| {
+                            cfg_select(cfg, expr, quote!(::core::option::Option::Some(0usize)))
+                        },
+                    )?
+                    .into_iter()
+                    .map(|expr| expr.unwrap_or(parse_quote!(::core::option::Option::Some(0usize))))
+                    .zip(&input.attrs.item_cfgs)
+                    .map(|(expr, cfg)| match cfg {
+                        Some(cfg) => {
+                            cfg_select(cfg, expr, quote!(::core::option::Option::Some(0usize)))
                         }
-                        .emit_for_tys_exprs(item.iter().map(|(a, b)| {
-                            (
-                                a.clone(),
-                                if needs_ref {
-                                    parse_quote!(&#b)
-                                } else {
-                                    parse_quote!(#b)
-                                },
-                            )
-                        }))?
-                        .unwrap_or(parse_quote!(::core::option::Option::Some(0usize))))
+                        None => expr,
                     })
-                    .collect::<Result<Vec<_>>>()?;
-                let out_min_len = tys_exprs
-                    .iter()
-                    .map(|item| {
-                        Ok(generator::EmitContext {
-                            kind: generator::EmitMinLen,
-                            krate: krate.clone(),
-                            replacing_ty: replacing_ty.clone(),
-                        }
-                        .emit_for_tys_exprs(item.iter().map(|(a, b)| {
-                            (
-                                a.clone(),
-                                if needs_ref {
-                                    parse_quote!(&#b)
-                                } else {
-                                    parse_quote!(#b)
-                                },
-                            )
-                        }))?
-                        .unwrap_or(parse_quote!(0usize)))
+                    .collect::<Vec<_>>();
+                let out_min_len = input
+                    .emit_items(
+                        &input.context(generator::EmitMinLen),
+                        input.borrowed_tys_exprs(quote!(&)),
+                        |_, _| parse_quote!(0usize),
+                        |cfg, expr| cfg_select(cfg, expr, quote!(0usize)),
+                    )?
+                    .into_iter()
+                    .map(|expr| expr.unwrap_or(parse_quote!(0usize)))
+                    .zip(&input.attrs.item_cfgs)
+                    .map(|(expr, cfg)| match cfg {
+                        Some(cfg) => cfg_select(cfg, expr, quote!(::core::primitive::usize::MAX)),
+                        None => expr,
                     })
-                    .collect::<Result<Vec<_>>>()?;
+                    .collect::<Vec<_>>();
                 let iter_ty_lt: Lifetime = parse_quote!('__parametrized_lt);
-                let out_iter_ty = tys_exprs
-                    .iter()
-                    .map(|item| {
-                        Ok(generator::EmitContext {
-                            kind: generator::EmitIterTy(iter_ty_lt.clone(), replacing_ty.clone()),
-                            krate: krate.clone(),
-                            replacing_ty: replacing_ty.clone(),
-                        }
-                        .emit_for_tys_exprs(
-                            item.iter().map(|(a, _)| (a.clone(), replacing_ty.clone())),
-                        )?
-                        .unwrap_or(parse_quote!(::core::iter::Empty<&#iter_ty_lt #replacing_ty>)))
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-                let out_iter = tys_exprs
-                    .iter()
-                    .map(|item| {
-                        Ok(generator::EmitContext {
-                            kind: generator::EmitIter,
-                            krate: krate.clone(),
-                            replacing_ty: replacing_ty.clone(),
-                        }
-                        .emit_for_tys_exprs(
-                            item.iter().map(|(a, b)| (a.clone(), if needs_ref{parse_quote!(&#b)}else{parse_quote!(#b)})),
-                        )?
-                        .unwrap_or(parse_quote!(::core::iter::empty())))
+                // Where the iterators are boxed, the values are collected into a
+                // `Vec` instead of naming the structural type.
+                let collected_iter_ty: Type =
+                    parse_quote!(#krate::_imp::vec::IntoIter<&#iter_ty_lt #replacing_ty>);
+                let boxed_iter_ty: Type = parse_quote! {
+                    #krate::_imp::Box<
+                        dyn ::core::iter::Iterator<Item = &#iter_ty_lt #replacing_ty> + #iter_ty_lt
+                    >
+                };
+                let out_iter_ty = input
+                    .emit_items(
+                        &input.context(generator::EmitIterTy(
+                            iter_ty_lt.clone(),
+                            replacing_ty.clone(),
+                        )),
+                        input.param_tys(),
+                        |_, _| boxed_iter_ty.clone(),
+                        |_, ty| cfg_iter_ty(krate, &ty),
+                    )?
+                    .into_iter()
+                    .map(|ty| {
+                        ty.unwrap_or(parse_quote!(::core::iter::Empty<&#iter_ty_lt #replacing_ty>))
                     })
-                    .collect::<Result<Vec<_>>>()?
+                    .collect::<Vec<_>>();
+                let out_iter = input
+                    .emit_items(
+                        &input.context(generator::EmitIter),
+                        input.borrowed_tys_exprs(quote!(&)),
+                        |path, expr| {
+                            parse_quote!(#krate::_imp::Box::new(#path::iter(#expr)) as #boxed_iter_ty)
+                        },
+                        |cfg, expr| cfg_iter(krate, cfg, expr, boxed.then(|| quote!(&#iter_ty_lt #replacing_ty))),
+                    )?
+                    .into_iter()
+                    .map(|expr| expr.unwrap_or(parse_quote!(::core::iter::empty())))
+                    .collect::<Vec<_>>();
+                let chained = input.is_chained(boxed);
+                let (out_iter, out_iter_ty) = if chained {
+                    input.chain_variants(out_iter, out_iter_ty)
+                } else {
+                    (out_iter, out_iter_ty)
+                };
+                let erased = boxed || chained;
+                let named = input.enum_iter_name(erased, "Iter");
+                let out_iter = out_iter
                     .into_iter()
-                    .zip(&out_iter_ty)
-                    .map(|(expr, ty)| {
-                        if tys_exprs.len() > 1 {
-                            quote!(sumtype!(#expr, for<#iter_ty_lt> #ty where #replacing_ty: #iter_ty_lt))
+                    .enumerate()
+                    .map(|(index, expr)| {
+                        let expr = if boxed {
+                            quote! {
+                                <#krate::_imp::Vec<&#iter_ty_lt #replacing_ty> as ::core::iter::FromIterator<&#iter_ty_lt #replacing_ty>>
+                                    ::from_iter(#expr).into_iter()
+                            }
                         } else {
                             quote!(#expr)
+                        };
+                        if let Some(name) = &named {
+                            input.wrap_named_iter(name, erased, index, &expr)
+                        } else {
+                            expr
                         }
                     })
                     .collect::<Vec<_>>();
+                let out_get = input.emit_get(false)?;
+                let loop_fn: Ident = parse_quote!(__parametrized_loop_fn);
+                let out_for_each = input.emit_loop(&loop_fn, generator::LoopKind::ForEach)?;
+                let out_try_for_each =
+                    input.emit_loop(&loop_fn, generator::LoopKind::TryForEach)?;
+                let named = named.map(|name| {
+                    input.emit_named_iter(
+                        &name,
+                        erased,
+                        Some(&iter_ty_lt),
+                        if boxed {
+                            core::slice::from_ref(&collected_iter_ty)
+                        } else {
+                            &out_iter_ty
+                        },
+                        &parse_quote!(&#iter_ty_lt #replacing_ty),
+                        "Parametrized::param_iter()",
+                    )
+                });
                 Ok(quote! {
-                    #(if tys_exprs.len() > 1) {
-                        #[#krate::_imp::sumtype(#krate::_imp::traits::Iterator)]
+                    #(if let Some((def, _)) = &named) {
+                        #(if !input.named_iter) { #[doc(hidden)] }
+                        #def
                     }
                     impl #impl_generics #krate::Parametrized<#param_index> for #ident
                     #ty_generics #where_clause {
@@ -223,8 +1062,10 @@ impl TraitTarget {
                         fn param_len(&#self_val) -> usize {
                             #{f(out_len.as_slice())}
                         }
-                        #(if tys_exprs.len() > 1) {
-                            type Iter<#iter_ty_lt> = sumtype![#iter_ty_lt] where (Self, Self::Item): #iter_ty_lt;
+                        #(if let Some((_, ty)) = &named) {
+                            type Iter<#iter_ty_lt> = #ty where (Self, Self::Item): #iter_ty_lt;
+                        } #(else if boxed) {
+                            type Iter<#iter_ty_lt> = #collected_iter_ty where (Self, Self::Item): #iter_ty_lt;
                         } #(else) {
                             type Iter<#iter_ty_lt> = #(#out_iter_ty)* where (Self, Self::Item): #iter_ty_lt;
                         }
@@ -234,61 +1075,137 @@ impl TraitTarget {
                         {
                             #{f(out_iter.as_slice())}
                         }
+                        #[allow(unused_variables, unused_mut, unused_assignments)]
+                        fn param_get(&#self_val, mut __parametrized_n: usize) -> ::core::option::Option<&Self::Item> {
+                            #{f(out_get.as_slice())}
+                        }
+                        #[allow(unused_variables, unused_mut)]
+                        fn param_for_each<'__parametrized_lt>(
+                            &'__parametrized_lt #self_val,
+                            mut #loop_fn: impl FnMut(&'__parametrized_lt Self::Item),
+                        )
+                        where
+                            Self::Item: '__parametrized_lt
+                        {
+                            #(if input.is_recursive()) {
+                                let #loop_fn: &mut dyn ::core::ops::FnMut(&'__parametrized_lt #replacing_ty) =
+                                    &mut #loop_fn;
+                            }
+                            #{f(out_for_each.as_slice())}
+                        }
+                        #[allow(unused_variables, unused_mut)]
+                        fn param_try_for_each<'__parametrized_lt, __PARAMETRIZED_BREAK>(
+                            &'__parametrized_lt #self_val,
+                            mut #loop_fn: impl FnMut(&'__parametrized_lt Self::Item)
+                                -> ::core::ops::ControlFlow<__PARAMETRIZED_BREAK>,
+                        ) -> ::core::ops::ControlFlow<__PARAMETRIZED_BREAK>
+                        where
+                            Self::Item: '__parametrized_lt
+                        {
+                            #(if input.is_recursive()) {
+                                let #loop_fn: &mut dyn ::core::ops::FnMut(&'__parametrized_lt #replacing_ty)
+                                    -> ::core::ops::ControlFlow<__PARAMETRIZED_BREAK> = &mut #loop_fn;
+                            }
+                            #{f(out_try_for_each.as_slice())}
+                        }
                     }
                 })
             }
             Self::IterMut => {
                 let iter_ty_lt: Lifetime = parse_quote!('__parametrized_lt);
-                let out_iter_mut_ty = tys_exprs
-                    .iter()
-                    .map(|item| {
-                        Ok(generator::EmitContext {
-                            kind: generator::EmitIterMutTy(
-                                iter_ty_lt.clone(),
-                                replacing_ty.clone(),
-                            ),
-                            krate: krate.clone(),
-                            replacing_ty: replacing_ty.clone(),
-                        }
-                        .emit_for_tys_exprs(
-                            item.iter().map(|(a, _)| (a.clone(), replacing_ty.clone())),
-                        )?
-                        .unwrap_or(
+                // Where the iterators are boxed, the values are collected into a
+                // `Vec` instead of naming the structural type.
+                let collected_iter_mut_ty: Type =
+                    parse_quote!(#krate::_imp::vec::IntoIter<&#iter_ty_lt mut #replacing_ty>);
+                let boxed_iter_mut_ty: Type = parse_quote! {
+                    #krate::_imp::Box<
+                        dyn ::core::iter::Iterator<Item = &#iter_ty_lt mut #replacing_ty> + #iter_ty_lt
+                    >
+                };
+                let out_iter_mut_ty = input
+                    .emit_items(
+                        &input.context(generator::EmitIterMutTy(
+                            iter_ty_lt.clone(),
+                            replacing_ty.clone(),
+                        )),
+                        input.param_tys(),
+                        |_, _| boxed_iter_mut_ty.clone(),
+                        |_, ty| cfg_iter_ty(krate, &ty),
+                    )?
+                    .into_iter()
+                    .map(|ty| {
+                        ty.unwrap_or(
                             parse_quote!(::core::iter::Empty<& #iter_ty_lt mut #replacing_ty>),
-                        ))
-                    })
-                    .collect::<Result<Vec<_>>>()?;
-                let out_iter_mut = tys_exprs
-                    .iter()
-                    .map(|item| {
-                        Ok(generator::EmitContext {
-                            kind: generator::EmitIterMut,
-                            krate: krate.clone(),
-                            replacing_ty: replacing_ty.clone(),
-                        }
-                        .emit_for_tys_exprs(
-                            item.iter().map(|(a, b)| (a.clone(), if needs_ref{parse_quote!(&mut #b)}else{parse_quote!(#b)})),
-                        )?
-                        .unwrap_or(parse_quote!(::core::iter::empty())))
+                        )
                     })
-                    .collect::<Result<Vec<_>>>()?
+                    .collect::<Vec<_>>();
+                let out_iter_mut = input
+                    .emit_items(
+                        &input.context(generator::EmitIterMut),
+                        input.borrowed_tys_exprs(quote!(&mut)),
+                        |path, expr| {
+                            parse_quote!(#krate::_imp::Box::new(#path::iter_mut(#expr)) as #boxed_iter_mut_ty)
+                        },
+                        |cfg, expr| cfg_iter(krate, cfg, expr, boxed.then(|| quote!(&#iter_ty_lt mut #replacing_ty))),
+                    )?
+                    .into_iter()
+                    .map(|expr| expr.unwrap_or(parse_quote!(::core::iter::empty())))
+                    .collect::<Vec<_>>();
+                let chained = input.is_chained(boxed);
+                let (out_iter_mut, out_iter_mut_ty) = if chained {
+                    input.chain_variants(out_iter_mut, out_iter_mut_ty)
+                } else {
+                    (out_iter_mut, out_iter_mut_ty)
+                };
+                let erased = boxed || chained;
+                let named = input.enum_iter_name(erased, "IterMut");
+                let out_iter_mut = out_iter_mut
                     .into_iter()
-                    .zip(&out_iter_mut_ty)
-                    .map(|(expr, ty)| {
-                        if tys_exprs.len() > 1 {
-                            quote!(sumtype!(#expr, for<#iter_ty_lt> #ty where #replacing_ty: #iter_ty_lt))
+                    .enumerate()
+                    .map(|(index, expr)| {
+                        let expr = if boxed {
+                            quote! {
+                                <#krate::_imp::Vec<&#iter_ty_lt mut #replacing_ty> as ::core::iter::FromIterator<&#iter_ty_lt mut #replacing_ty>>
+                                    ::from_iter(#expr).into_iter()
+                            }
                         } else {
                             quote!(#expr)
+                        };
+                        if let Some(name) = &named {
+                            input.wrap_named_iter(name, erased, index, &expr)
+                        } else {
+                            expr
                         }
                     })
                     .collect::<Vec<_>>();
+                let out_get_mut = input.emit_get(true)?;
+                let loop_fn: Ident = parse_quote!(__parametrized_loop_fn);
+                let out_for_each_mut =
+                    input.emit_loop(&loop_fn, generator::LoopKind::ForEachMut)?;
+                let named = named.map(|name| {
+                    input.emit_named_iter(
+                        &name,
+                        erased,
+                        Some(&iter_ty_lt),
+                        if boxed {
+                            core::slice::from_ref(&collected_iter_mut_ty)
+                        } else {
+                            &out_iter_mut_ty
+                        },
+                        &parse_quote!(&#iter_ty_lt mut #replacing_ty),
+                        "ParametrizedIterMut::param_iter_mut()",
+                    )
+                });
                 Ok(quote! {
-                    #(if tys_exprs.len() > 1) {
-                        #[#krate::_imp::sumtype(#krate::_imp::traits::Iterator)]
+                    #(if let Some((def, _)) = &named) {
+                        #(if !input.named_iter) { #[doc(hidden)] }
+                        #def
                     }
                     impl #impl_generics #krate::ParametrizedIterMut<#param_index> for #ident #ty_generics #where_clause {
-                        #(if tys_exprs.len() > 1) {
-                            type IterMut<#iter_ty_lt> = sumtype![#iter_ty_lt] where (Self, Self::Item): #iter_ty_lt;
+                        #(if let Some((_, ty)) = &named) {
+                            type IterMut<#iter_ty_lt> = #ty where (Self, Self::Item): #iter_ty_lt;
+                        } #(else if boxed) {
+                            type IterMut<#iter_ty_lt> = #collected_iter_mut_ty where (Self, Self::Item): #iter_ty_lt;
                         } #(else) {
                             type IterMut<#iter_ty_lt> = #(#out_iter_mut_ty)* where (Self, Self::Item): #iter_ty_lt;
                         }
@@ -298,53 +1215,128 @@ impl TraitTarget {
                         {
                             #{f(out_iter_mut.as_slice())}
                         }
+                        #[allow(unused_variables, unused_mut, unused_assignments)]
+                        fn param_get_mut(&mut #self_val, mut __parametrized_n: usize) -> ::core::option::Option<&mut Self::Item> {
+                            #{f(out_get_mut.as_slice())}
+                        }
+                        #[allow(unused_variables, unused_mut)]
+                        fn param_for_each_mut<'__parametrized_lt>(
+                            &'__parametrized_lt mut #self_val,
+                            mut #loop_fn: impl FnMut(&'__parametrized_lt mut Self::Item),
+                        )
+                        where
+                            Self::Item: '__parametrized_lt
+                        {
+                            #(if input.is_recursive()) {
+                                let #loop_fn: &mut dyn ::core::ops::FnMut(&'__parametrized_lt mut #replacing_ty) =
+                                    &mut #loop_fn;
+                            }
+                            #{f(out_for_each_mut.as_slice())}
+                        }
                     }
                 })
             }
             Self::IntoIter => {
-                let out_into_iter_ty = tys_exprs
-                    .iter()
-                    .map(|item| {
-                        Ok(generator::EmitContext {
-                            kind: generator::EmitIntoIterTy(replacing_ty.clone()),
-                            krate: krate.clone(),
-                            replacing_ty: replacing_ty.clone(),
-                        }
-                        .emit_for_tys_exprs(
-                            item.iter().map(|(a, _)| (a.clone(), replacing_ty.clone())),
-                        )?
-                        .unwrap_or(parse_quote!(::core::iter::Empty<#replacing_ty>)))
+                let recursive = input.is_recursive();
+                // `IntoIter` has no lifetime to bound a trait object with, so the
+                // items are collected eagerly where the iterator type is erased.
+                let boxed = boxed && !recursive;
+                let vec_into_iter_ty: Type =
+                    parse_quote!(#krate::_imp::vec::IntoIter<#replacing_ty>);
+                let collect = |expr: &dyn ToTokens| -> Expr {
+                    parse_quote! {
+                        <#krate::_imp::Vec<#replacing_ty> as ::core::iter::FromIterator<#replacing_ty>>
+                            ::from_iter(#expr).into_iter()
+                    }
+                };
+                let out_into_iter_ty = input
+                    .emit_items(
+                        &input.context(generator::EmitIntoIterTy(replacing_ty.clone())),
+                        input.param_tys(),
+                        |_, _| vec_into_iter_ty.clone(),
+                        |_, ty| cfg_iter_ty(krate, &ty),
+                    )?
+                    .into_iter()
+                    .map(|ty| ty.unwrap_or(parse_quote!(::core::iter::Empty<#replacing_ty>)))
+                    .map(|ty| -> Type {
+                        if recursive {
+                            parse_quote!(#krate::_imp::Box<#ty>)
+                        } else {
+                            ty
+                        }
                     })
-                    .collect::<Result<Vec<_>>>()?;
-                let out_into_iter = tys_exprs
-                    .iter()
-                    .map(|item| {
-                        Ok(generator::EmitContext {
-                            kind: generator::EmitIntoIter,
-                            krate: krate.clone(),
-                            replacing_ty: replacing_ty.clone(),
-                        }
-                        .emit_for_tys_exprs(item.iter().map(|(a, b)| (a.clone(), b.clone())))?
-                        .unwrap_or(parse_quote!(::core::iter::empty())))
+                    .collect::<Vec<_>>();
+                let out_into_iter = input
+                    .emit_items(
+                        &input.context(generator::EmitIntoIter),
+                        tys_exprs.iter().cloned(),
+                        |path, expr| collect(&quote!(#path::into_iter(#expr))),
+                        |cfg, expr| {
+                            cfg_iter(krate, cfg, expr, boxed.then(|| quote!(#replacing_ty)))
+                        },
+                    )?
+                    .into_iter()
+                    .map(|expr| expr.unwrap_or(parse_quote!(::core::iter::empty())))
+                    .map(|expr| -> Expr {
+                        if boxed {
+                            collect(&expr)
+                        } else if recursive {
+                            parse_quote!(#krate::_imp::Box::new(#expr))
+                        } else {
+                            expr
+                        }
                     })
-                    .collect::<Result<Vec<_>>>()?
+                    .collect::<Vec<_>>();
+                let chained = input.is_chained(boxed);
+                let (out_into_iter, out_into_iter_ty) = if chained {
+                    input.chain_variants(out_into_iter, out_into_iter_ty)
+                } else {
+                    (out_into_iter, out_into_iter_ty)
+                };
+                let erased = boxed || chained;
+                // The iterator of recursive types holds itself through a `Box`,
+                // which needs a type to name it, hidden unless asked for.
+                let named = match input.enum_iter_name(erased, "IntoIter") {
+                    None if recursive => Some(input.iter_name("__Parametrized", "IntoIter")),
+                    named => named,
+                };
+                let out_into_iter = out_into_iter
                     .into_iter()
-                    .zip(&out_into_iter_ty)
-                    .map(|(expr, ty)| {
-                        if tys_exprs.len() > 1 {
-                            quote!(sumtype!(#expr, #ty))
+                    .enumerate()
+                    .map(|(index, expr)| {
+                        if let Some(name) = &named {
+                            input.wrap_named_iter(name, erased, index, &quote!(#expr))
                         } else {
                             quote!(#expr)
                         }
                     })
                     .collect::<Vec<_>>();
+                let loop_fn: Ident = parse_quote!(__parametrized_loop_fn);
+                let out_fold = input.emit_loop(&loop_fn, generator::LoopKind::Fold)?;
+                let named = named.map(|name| {
+                    input.emit_named_iter(
+                        &name,
+                        erased,
+                        None,
+                        if boxed {
+                            core::slice::from_ref(&vec_into_iter_ty)
+                        } else {
+                            &out_into_iter_ty
+                        },
+                        replacing_ty,
+                        "ParametrizedIntoIter::param_into_iter()",
+                    )
+                });
                 Ok(quote! {
-                    #(if tys_exprs.len() > 1) {
-                        #[#krate::_imp::sumtype(#krate::_imp::traits::Iterator)]
+                    #(if let Some((def, _)) = &named) {
+                        #(if !input.named_iter) { #[doc(hidden)] }
+                        #def
                     }
                     impl #impl_generics #krate::ParametrizedIntoIter<#param_index> for #ident #ty_generics #where_clause {
-                        #(if tys_exprs.len() > 1) {
-                            type IntoIter = sumtype![];
+                        #(if let Some((_, ty)) = &named) {
+                            type IntoIter = #ty;
+                        } #(else if boxed) {
+                            type IntoIter = #krate::_imp::vec::IntoIter<#replacing_ty>;
                         } #(else) {
                             type IntoIter = #(#out_into_iter_ty)*;
                         }
@@ -352,25 +1344,553 @@ impl TraitTarget {
                         {
                             #{f(out_into_iter.as_slice())}
                         }
+                        #[allow(unused_variables, unused_mut)]
+                        fn param_fold<__PARAMETRIZED_ACC>(
+                            #self_val,
+                            __parametrized_acc: __PARAMETRIZED_ACC,
+                            mut #loop_fn: impl FnMut(__PARAMETRIZED_ACC, Self::Item) -> __PARAMETRIZED_ACC,
+                        ) -> __PARAMETRIZED_ACC {
+                            #(if input.is_recursive()) {
+                                let #loop_fn: &mut dyn ::core::ops::FnMut(__PARAMETRIZED_ACC, #replacing_ty)
+                                    -> __PARAMETRIZED_ACC = &mut #loop_fn;
+                            }
+                            #{f(out_fold.as_slice())}
+                        }
                     }
                 })
             }
-            Self::Map => {
+            Self::Unzip => {
+                let left_param: Ident = parse_quote!(__PARAMETRIZED_LEFT_PARAM);
+                let right_param: Ident = parse_quote!(__PARAMETRIZED_RIGHT_PARAM);
+                let ctx = input.context(generator::EmitMap(
+                    parse_quote!(__parametrized_map_fn),
+                    left_param.clone(),
+                    generator::MapKind::Unzip(left_param.clone(), right_param.clone()),
+                ));
+                // Both halves get what is not unzipped, the left one by cloning.
+                let keep = |expr: &Expr| -> Expr {
+                    parse_quote!((::core::clone::Clone::clone(&#expr), #expr))
+                };
+                let out_unzip = tys_exprs
+                    .iter()
+                    .zip(&input.attrs.modes)
+                    .map(|(item, modes)| {
+                        item.iter()
+                            .zip(modes)
+                            .map(|((a, b), mode)| match mode {
+                                FieldMode::Traverse => Ok(ctx
+                                    .emit(a, &(b.clone(), a.clone()))?
+                                    .map(|a| a.0)
+                                    .unwrap_or_else(|| keep(b))),
+                                FieldMode::Skip | FieldMode::Opaque
+                                    if contains_type(a, replacing_ty) =>
+                                {
+                                    Err(Error::new(
+                                        a.span(),
+                                        "A skipped or opaque field mentioning the parameter \
+                                         cannot be unzipped, use `with` instead",
+                                    ))
+                                }
+                                FieldMode::Skip | FieldMode::Opaque => Ok(keep(b)),
+                                FieldMode::With(path) => Ok(parse_quote!(#path::unzip(#b))),
+                            })
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let self_ty: Type = parse_quote!(#ident #ty_generics);
+                let pair: Type = parse_quote!((#left_param, #right_param));
+                let is_param = |p: &TypeParam| {
+                    let ident = &p.ident;
+                    &parse_quote!(#ident) as &Type == replacing_ty
+                };
+                let other_params = generics.params.iter().filter(|p| match p {
+                    GenericParam::Type(p) => !is_param(p),
+                    _ => true,
+                });
+                // The item is `Self` with the parameter replaced by the pair, so
+                // are the predicates on it.
+                let mut where_clause = input.clone_where_clause(generics.where_clause.clone());
+                syn::visit_mut::VisitMut::visit_where_clause_mut(
+                    &mut TypeReplacer(replacing_ty.clone(), pair.clone()),
+                    &mut where_clause,
+                );
+                if let Some(p) = generics.type_params().find(|p| is_param(p)) {
+                    if !p.bounds.is_empty() {
+                        let bounds = &p.bounds;
+                        where_clause.predicates.push(parse_quote!(#pair: #bounds));
+                    }
+                }
+                let unzipped = replace_type(self_ty.clone(), replacing_ty.clone(), pair);
+                let left = replace_type(
+                    self_ty.clone(),
+                    replacing_ty.clone(),
+                    parse_quote!(#left_param),
+                );
+                let right = replace_type(self_ty, replacing_ty.clone(), parse_quote!(#right_param));
+                Ok(quote! {
+                    impl <
+                        #(for p in other_params){ #p, }
+                        #{input.renamed_param(&left_param)},
+                        #{input.renamed_param(&right_param)}
+                    > #krate::ParametrizedUnzip<#param_index, #left_param, #right_param>
+                    for #unzipped #where_clause
+                    {
+                        type Left = #left;
+                        type Right = #right;
+                        fn param_unzip(#self_val) -> (Self::Left, Self::Right) {
+                            #{emit_unzip_f(out_unzip.as_slice())}
+                        }
+                    }
+                })
+            }
+            Self::Retain => {
+                input.check_removable()?;
+                let retain_fn: Ident = parse_quote!(__parametrized_retain_fn);
+                let out_retain = input
+                    .emit_items(
+                        &input.context(generator::EmitRetain(retain_fn.clone())),
+                        input.borrowed_tys_exprs(quote!(&mut)),
+                        |path, expr| {
+                            if input.is_recursive() {
+                                parse_quote!(#path::retain(#expr, &mut *#retain_fn))
+                            } else {
+                                parse_quote!(#path::retain(#expr, &mut #retain_fn))
+                            }
+                        },
+                        |cfg, expr| cfg_select(cfg, expr, quote!(())),
+                    )?
+                    .into_iter()
+                    .map(|expr| {
+                        let expr = expr.unwrap_or(parse_quote!(()));
+                        quote!(#expr;)
+                    })
+                    .collect::<Vec<_>>();
+                Ok(quote! {
+                    impl #impl_generics #krate::ParametrizedRetain<#param_index> for #ident #ty_generics #where_clause {
+                        fn param_retain(
+                            &mut #self_val,
+                            mut #retain_fn: impl FnMut(&Self::Item) -> bool,
+                        ) {
+                            #(if input.is_recursive()) {
+                                let #retain_fn: &mut dyn ::core::ops::FnMut(&#replacing_ty) -> bool =
+                                    &mut #retain_fn;
+                            }
+                            #{f(out_retain.as_slice())}
+                        }
+                    }
+                })
+            }
+            Self::Update => {
+                let update_fn: Ident = parse_quote!(__parametrized_update_fn);
+                let out_update = input
+                    .emit_items(
+                        &input.context(generator::EmitUpdate(update_fn.clone())),
+                        input.borrowed_tys_exprs(quote!(&mut)),
+                        |path, expr| {
+                            if input.is_recursive() {
+                                parse_quote!(#path::update(#expr, &mut *#update_fn))
+                            } else {
+                                parse_quote!(#path::update(#expr, &mut #update_fn))
+                            }
+                        },
+                        |cfg, expr| cfg_select(cfg, expr, quote!(())),
+                    )?
+                    .into_iter()
+                    .map(|expr| {
+                        let expr = expr.unwrap_or(parse_quote!(()));
+                        quote!(#expr;)
+                    })
+                    .collect::<Vec<_>>();
+                // Values held in place are swapped out with their defaults.
+                let mut where_clause = where_clause.cloned().unwrap_or(WhereClause {
+                    where_token: Default::default(),
+                    predicates: Default::default(),
+                });
+                where_clause
+                    .predicates
+                    .push(parse_quote!(#replacing_ty: ::core::default::Default));
+                Ok(quote! {
+                    impl #impl_generics #krate::ParametrizedUpdate<#param_index> for #ident #ty_generics #where_clause {
+                        fn param_update(
+                            &mut #self_val,
+                            mut #update_fn: impl FnMut(Self::Item) -> Self::Item,
+                        ) {
+                            #(if input.is_recursive()) {
+                                let #update_fn: &mut dyn ::core::ops::FnMut(#replacing_ty) -> #replacing_ty =
+                                    &mut #update_fn;
+                            }
+                            #{f(out_update.as_slice())}
+                        }
+                    }
+                })
+            }
+            Self::Path => {
+                let path: Ident = parse_quote!(__parametrized_path);
+                let out = quote!(__parametrized_out);
+                let out_paths = input
+                    .emit_fields(
+                        &input.context(generator::EmitPaths),
+                        input.borrowed_tys_exprs(quote!(&)),
+                        |with, expr| {
+                            parse_quote!(::core::iter::IntoIterator::into_iter(#with::paths(#expr)))
+                        },
+                    )?
+                    .into_iter()
+                    .zip(&input.segments)
+                    .zip(&input.attrs.cfgs)
+                    .map(|((elems, segments), cfgs)| {
+                        quote! {
+                            let #out = ::core::iter::empty::<#krate::ParamPath>();
+                            #(for ((elem, segments), cfg) in elems.iter().zip(segments).zip(cfgs)) {
+                                #(if let Some(elem) = elem) {
+                                    #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                                    let #out = ::core::iter::Iterator::chain(
+                                        #out,
+                                        ::core::iter::Iterator::map(#elem, |__parametrized_elem| {
+                                            __parametrized_elem.prefixed(&[#segments])
+                                        }),
+                                    );
+                                }
+                            }
+                            #krate::_imp::Box::new(#out)
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                let out_get = input
+                    .emit_fields(
+                        &input.context(generator::EmitGetPath(path.clone())),
+                        input.borrowed_tys_exprs(quote!(&)),
+                        |with, expr| parse_quote!(#with::get_path_prefix(#expr, #path)),
+                    )?
+                    .into_iter()
+                    .zip(&input.segments)
+                    .zip(&input.attrs.cfgs)
+                    .map(|((elems, segments), cfgs)| {
+                        quote! {
+                            #(for ((elem, segments), cfg) in elems.iter().zip(segments).zip(cfgs)) {
+                                #(if let Some(elem) = elem) {
+                                    #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                                    if let ::core::option::Option::Some(#path) =
+                                        #path.strip_prefix(&[#segments])
+                                    {
+                                        return #elem;
+                                    }
+                                }
+                            }
+                            ::core::option::Option::None
+                        }
+                    })
+                    .collect::<Vec<_>>();
+                Ok(quote! {
+                    impl #impl_generics #krate::ParametrizedPath<#param_index> for #ident #ty_generics #where_clause {
+                        type PathIter<'__parametrized_a> = #krate::_imp::Box<
+                            dyn ::core::iter::Iterator<Item = #krate::ParamPath> + '__parametrized_a,
+                        >
+                        where
+                            Self: '__parametrized_a;
+
+                        fn param_path_iter(&#self_val) -> Self::PathIter<'_> {
+                            #{f(out_paths.as_slice())}
+                        }
+                        #[allow(unused_variables)]
+                        fn param_get_path_prefix<'__parametrized_a, '__parametrized_p>(
+                            &'__parametrized_a #self_val,
+                            #path: &'__parametrized_p [#krate::PathSegment],
+                        ) -> ::core::option::Option<(
+                            &'__parametrized_a #replacing_ty,
+                            &'__parametrized_p [#krate::PathSegment],
+                        )> {
+                            #{f(out_get.as_slice())}
+                        }
+                    }
+                })
+            }
+            Self::Diff => {
+                let keep: Ident = parse_quote!(__parametrized_keep);
+                let out_pair = input.collect_prefixed(input.emit_fields(
+                    &input.context(generator::EmitPair(keep.clone())),
+                    input.borrowed_tys_exprs(quote!(&)).map(|item| {
+                        item.into_iter()
+                            .map(|(ty, expr)| {
+                                let other = zip_other_expr(&expr);
+                                (ty, parse_quote!((#expr, #other)))
+                            })
+                            .collect()
+                    }),
+                    |with, expr| {
+                        parse_quote!(#krate::_imp::pair_kept(#with::pair #expr, &mut *#keep))
+                    },
+                )?);
+                Ok(quote! {
+                    impl #impl_generics #krate::ParametrizedDiff<#param_index> for #ident #ty_generics #where_clause {
+                        fn param_pair_by<'__parametrized_a>(
+                            &'__parametrized_a #self_val,
+                            #{zip_other_expr(&parse_quote!(#self_val))}: &'__parametrized_a Self,
+                            #keep: &mut dyn ::core::ops::FnMut(&#replacing_ty, &#replacing_ty) -> bool,
+                        ) -> #krate::_imp::Vec<#krate::ParamDiff<&'__parametrized_a #replacing_ty>> {
+                            #{emit_pair_f(out_pair.as_slice())}
+                        }
+                    }
+                })
+            }
+            Self::Union(other_index) | Self::UnionMut(other_index) => {
+                let mutable = matches!(self, Self::UnionMut(_));
+                let other_ty = get_replacing_ty(generics, *other_index);
+                let other_index = trait_index(generics, *other_index, input.legacy_index);
+                if &other_ty == replacing_ty {
+                    return Err(Error::new(
+                        Span::call_site(),
+                        "`union` requires two distinct parameters",
+                    ));
+                }
+                let iter_ty_lt: Lifetime = parse_quote!('__parametrized_lt);
+                let reference = if mutable {
+                    quote!(&#iter_ty_lt mut)
+                } else {
+                    quote!(&#iter_ty_lt)
+                };
+                let boxed_iter_ty: Type = parse_quote! {
+                    #krate::_imp::Box<
+                        dyn ::core::iter::Iterator<
+                            Item = #krate::Either<#reference #replacing_ty, #reference #other_ty>
+                        > + #iter_ty_lt
+                    >
+                };
+                let out_iter = input
+                    .emit_items(
+                        &input.context(generator::EmitUnion(mutable, other_ty.clone())),
+                        input.borrowed_tys_exprs(if mutable { quote!(&mut) } else { quote!(&) }),
+                        |path, expr| {
+                            if mutable {
+                                parse_quote!(#path::iter_mut_either(#expr))
+                            } else {
+                                parse_quote!(#path::iter_either(#expr))
+                            }
+                        },
+                        |cfg, expr| {
+                            cfg_select(
+                                cfg,
+                                quote!(#krate::_imp::Box::new(#expr) as #boxed_iter_ty),
+                                quote!(#krate::_imp::Box::new(::core::iter::empty()) as #boxed_iter_ty),
+                            )
+                        },
+                    )?
+                    .into_iter()
+                    .map(|expr| {
+                        let expr = expr.unwrap_or(parse_quote!(::core::iter::empty()));
+                        quote!(#krate::_imp::Box::new(#expr) as #boxed_iter_ty)
+                    })
+                    .collect::<Vec<_>>();
+                Ok(quote! {
+                    #(if mutable) {
+                        impl #impl_generics #krate::ParametrizedUnionMut<#param_index, #other_index>
+                        for #ident #ty_generics #where_clause {
+                            type UnionIterMut<#iter_ty_lt> = #boxed_iter_ty
+                            where
+                                (Self, #replacing_ty, #other_ty): #iter_ty_lt;
+                            fn param_iter_mut_either<#iter_ty_lt>(&#iter_ty_lt mut #self_val) -> Self::UnionIterMut<#iter_ty_lt>
+                            where
+                                #replacing_ty: #iter_ty_lt,
+                                #other_ty: #iter_ty_lt,
+                            {
+                                #{f(out_iter.as_slice())}
+                            }
+                        }
+                    } #(else) {
+                        impl #impl_generics #krate::ParametrizedUnion<#param_index, #other_index>
+                        for #ident #ty_generics #where_clause {
+                            type UnionIter<#iter_ty_lt> = #boxed_iter_ty
+                            where
+                                (Self, #replacing_ty, #other_ty): #iter_ty_lt;
+                            fn param_iter_either<#iter_ty_lt>(&#iter_ty_lt #self_val) -> Self::UnionIter<#iter_ty_lt>
+                            where
+                                #replacing_ty: #iter_ty_lt,
+                                #other_ty: #iter_ty_lt,
+                            {
+                                #{f(out_iter.as_slice())}
+                            }
+                        }
+                    }
+                })
+            }
+            Self::BiMap(other_index) => {
+                let other_ty = get_replacing_ty(generics, *other_index);
+                let other_index = trait_index(generics, *other_index, input.legacy_index);
+                if &other_ty == replacing_ty {
+                    return Err(Error::new(
+                        Span::call_site(),
+                        "`bimap` requires two distinct parameters",
+                    ));
+                }
+                let (f0, f1): (Ident, Ident) = (
+                    parse_quote!(__parametrized_map_fn0),
+                    parse_quote!(__parametrized_map_fn1),
+                );
+                let (k0, k1): (Ident, Ident) = (
+                    parse_quote!(__PARAMETRIZED_MAP_PARAM0),
+                    parse_quote!(__PARAMETRIZED_MAP_PARAM1),
+                );
+                let ctx = input.context(generator::EmitBiMap {
+                    f0: f0.clone(),
+                    k0: k0.clone(),
+                    ty1: other_ty.clone(),
+                    f1: f1.clone(),
+                    k1: k1.clone(),
+                });
+                let mentions_param =
+                    |ty: &Type| contains_type(ty, replacing_ty) || contains_type(ty, &other_ty);
+                let out_map = tys_exprs
+                    .iter()
+                    .zip(&input.attrs.modes)
+                    .map(|(item, modes)| {
+                        item.iter()
+                            .zip(modes)
+                            .map(|((a, b), mode)| match mode {
+                                FieldMode::Traverse => Ok(ctx
+                                    .emit(a, &(b.clone(), a.clone()))?
+                                    .map(|a| a.0)
+                                    .unwrap_or_else(|| b.clone())),
+                                FieldMode::Skip | FieldMode::Opaque if mentions_param(a) => {
+                                    Err(Error::new(
+                                        a.span(),
+                                        "A skipped or opaque field mentioning the parameter \
+                                         cannot be mapped, use `with` instead",
+                                    ))
+                                }
+                                FieldMode::Skip | FieldMode::Opaque => Ok(b.clone()),
+                                FieldMode::With(path) => Ok(if input.is_recursive() {
+                                    parse_quote!(#path::bimap(#b, &mut *#f0, &mut *#f1))
+                                } else {
+                                    parse_quote!(#path::bimap(#b, &mut #f0, &mut #f1))
+                                }),
+                            })
+                            .collect::<Result<Vec<_>>>()
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let mapped = replace_type(
+                    replace_type(
+                        parse_quote!(#ident #ty_generics),
+                        replacing_ty.clone(),
+                        parse_quote!(#k0),
+                    ),
+                    other_ty.clone(),
+                    parse_quote!(#k1),
+                );
+                Ok(quote! {
+                    impl <
+                        #(for p in &generics.params){ #p, }
+                        #{input.renamed_param(&k0)},
+                        #{input.renamed_param_of(&other_ty, &k1)}
+                    > #krate::ParametrizedBiMap<#param_index, #other_index, #k0, #k1>
+                    for #ident #ty_generics #where_clause {
+                        type Mapped = #mapped;
+                        fn param_bimap(
+                            #self_val,
+                            mut #f0: impl FnMut(#replacing_ty) -> #k0,
+                            mut #f1: impl FnMut(#other_ty) -> #k1,
+                        ) -> Self::Mapped {
+                            #(if input.is_recursive()) {
+                                let #f0: &mut dyn ::core::ops::FnMut(#replacing_ty) -> #k0 = &mut #f0;
+                                let #f1: &mut dyn ::core::ops::FnMut(#other_ty) -> #k1 = &mut #f1;
+                            }
+                            #{emit_map_f(out_map.as_slice())}
+                        }
+                    }
+                })
+            }
+            Self::Map | Self::TryMap | Self::MapRef | Self::Zip | Self::FilterMap => {
                 let map_fn: Ident = parse_quote!(__parametrized_map_fn);
                 let mapped_param: Ident = parse_quote!(__PARAMETRIZED_MAP_PARAM);
+                // The error type of `try_param_map` and `try_param_zip_with`.
+                let map_err: Ident = parse_quote!(__PARAMETRIZED_MAP_ERR);
+                // The parameter of the other value of `param_zip_with`.
+                let other_param: Ident = parse_quote!(__PARAMETRIZED_ZIP_PARAM);
+                let map_lt: Lifetime = parse_quote!('__parametrized_lt);
+                let kind = match self {
+                    Self::TryMap => generator::MapKind::TryMap(map_err.clone()),
+                    Self::MapRef => generator::MapKind::MapRef,
+                    Self::Zip => generator::MapKind::Zip(other_param.clone(), map_err.clone()),
+                    Self::FilterMap => generator::MapKind::FilterMap,
+                    _ => generator::MapKind::Map,
+                };
+                let by_ref = kind == generator::MapKind::MapRef;
+                // The argument of the emitter for the field at `expr`.
+                let arg = |expr: &Expr| -> Expr {
+                    match &kind {
+                        generator::MapKind::MapRef if input.needs_ref => parse_quote!(&#expr),
+                        generator::MapKind::Zip(..) => {
+                            let other = zip_other_expr(expr);
+                            parse_quote!((#expr, #other))
+                        }
+                        _ => expr.clone(),
+                    }
+                };
+                // `param_map_ref` clones what it does not map, and `param_zip_with`
+                // takes it from `self`.
+                let keep = |expr: &Expr| -> Expr {
+                    if by_ref {
+                        let expr = arg(expr);
+                        parse_quote!(::core::clone::Clone::clone(#expr))
+                    } else {
+                        expr.clone()
+                    }
+                };
+                if kind == generator::MapKind::FilterMap {
+                    input.check_removable()?;
+                }
                 let out_map = tys_exprs
                     .iter()
-                    .map(|item| {
+                    .zip(&input.attrs.modes)
+                    .map(|(item, modes)| {
                         item.iter()
-                            .map(|(a, b)| {
-                                Ok(generator::EmitContext {
-                                    kind: generator::EmitMap(map_fn.clone(), mapped_param.clone()),
-                                    krate: krate.clone(),
-                                    replacing_ty: replacing_ty.clone(),
+                            .zip(modes)
+                            .map(|((a, b), mode)| match mode {
+                                FieldMode::Traverse => Ok(input
+                                    .context(generator::EmitMap(
+                                        map_fn.clone(),
+                                        mapped_param.clone(),
+                                        kind.clone(),
+                                    ))
+                                    .emit(a, &(arg(b), a.clone()))?
+                                    .map(|a| a.0)
+                                    .unwrap_or_else(|| keep(b))),
+                                FieldMode::Skip | FieldMode::Opaque
+                                    if contains_type(a, replacing_ty) =>
+                                {
+                                    Err(Error::new(
+                                        a.span(),
+                                        "A skipped or opaque field mentioning the parameter \
+                                         cannot be mapped, use `with` instead",
+                                    ))
+                                }
+                                FieldMode::Skip | FieldMode::Opaque => Ok(keep(b)),
+                                FieldMode::With(path) => {
+                                    let map_fn: Expr = if input.is_recursive() {
+                                        parse_quote!(&mut *#map_fn)
+                                    } else {
+                                        parse_quote!(&mut #map_fn)
+                                    };
+                                    Ok(match &kind {
+                                        generator::MapKind::Map => {
+                                            parse_quote!(#path::map(#b, #map_fn))
+                                        }
+                                        generator::MapKind::FilterMap => {
+                                            parse_quote!(#path::filter_map(#b, #map_fn))
+                                        }
+                                        generator::MapKind::TryMap(_) => {
+                                            parse_quote!(#path::try_map(#b, #map_fn)?)
+                                        }
+                                        generator::MapKind::MapRef => {
+                                            let b = arg(b);
+                                            parse_quote!(#path::map_ref(#b, #map_fn))
+                                        }
+                                        generator::MapKind::Zip(..) => {
+                                            let other = zip_other_expr(b);
+                                            parse_quote!(#path::try_zip_with(#b, #other, #map_fn)?)
+                                        }
+                                        generator::MapKind::Unzip(..) => unreachable!(),
+                                    })
                                 }
-                                .emit(a, &(b.clone(), a.clone()))?
-                                .map(|a| a.0)
-                                .unwrap_or(b.clone()))
                             })
                             .collect::<Result<Vec<_>>>()
                     })
@@ -380,25 +1900,103 @@ impl TraitTarget {
                     replacing_ty.clone(),
                     parse_quote!(#mapped_param),
                 );
-                let mapped_param_impl_generics = generics
-                    .params
-                    .iter()
-                    .filter_map(|p| {
-                        if let GenericParam::Type(p) = p {
-                            let ident = &p.ident;
-                            if &parse_quote!(#ident) as &Type == replacing_ty {
-                                let mut p = p.clone();
-                                p.ident = mapped_param.clone();
-                                Some(quote!(#p))
-                            } else {
-                                None
+                let mapped_param_impl_generics = input.renamed_param(&mapped_param);
+                if let generator::MapKind::Zip(other_param, map_err) = &kind {
+                    let other = replace_type(
+                        parse_quote!(#ident #ty_generics),
+                        replacing_ty.clone(),
+                        parse_quote!(#other_param),
+                    );
+                    return Ok(quote! {
+                        impl <
+                            #(for p in &generics.params){ #p, }
+                            #{input.renamed_param(other_param)},
+                            #mapped_param_impl_generics
+                        > #krate::ParametrizedZip<#param_index, #other, #mapped_param>
+                        for #ident #ty_generics #where_clause {
+                            fn try_param_zip_with<#map_err: ::core::convert::From<#krate::ShapeMismatch>>(
+                                #self_val,
+                                #{zip_other_expr(&parse_quote!(#self_val))}: #other,
+                                mut #map_fn: impl FnMut(Self::Item, #other_param)
+                                    -> ::core::result::Result<#mapped_param, #map_err>,
+                            ) -> ::core::result::Result<Self::Mapped, #map_err> {
+                                #(if input.is_recursive()) {
+                                    let #map_fn: &mut dyn ::core::ops::FnMut(#replacing_ty, #other_param)
+                                        -> ::core::result::Result<#mapped_param, #map_err> = &mut #map_fn;
+                                }
+                                #{emit_zip_f(out_map.as_slice())}
                             }
-                        } else {
-                            None
                         }
-                    })
-                    .next()
-                    .unwrap_or(quote!(#mapped_param));
+                    });
+                }
+                if let generator::MapKind::TryMap(map_err) = &kind {
+                    return Ok(quote! {
+                        impl <
+                            #(for p in &generics.params){ #p, }
+                            #mapped_param_impl_generics
+                        > #krate::ParametrizedTryMap<#param_index, #mapped_param> for #ident #ty_generics #where_clause {
+                            fn try_param_map<#map_err>(
+                                #self_val,
+                                mut #map_fn: impl FnMut(Self::Item) -> ::core::result::Result<#mapped_param, #map_err>,
+                            ) -> ::core::result::Result<Self::Mapped, #map_err>
+                            where
+                                Self::Item: ::core::marker::Sized
+                            {
+                                #(if input.is_recursive()) {
+                                    let #map_fn: &mut dyn ::core::ops::FnMut(#replacing_ty)
+                                        -> ::core::result::Result<#mapped_param, #map_err> = &mut #map_fn;
+                                }
+                                ::core::result::Result::Ok(#{emit_map_f(out_map.as_slice())})
+                            }
+                        }
+                    });
+                }
+                if kind == generator::MapKind::FilterMap {
+                    return Ok(quote! {
+                        impl <
+                            #(for p in &generics.params){ #p, }
+                            #mapped_param_impl_generics
+                        > #krate::ParametrizedFilterMap<#param_index, #mapped_param> for #ident #ty_generics #where_clause {
+                            fn param_filter_map(
+                                #self_val,
+                                mut #map_fn: impl FnMut(Self::Item) -> ::core::option::Option<#mapped_param>,
+                            ) -> Self::Mapped
+                            where
+                                Self::Item: ::core::marker::Sized
+                            {
+                                #(if input.is_recursive()) {
+                                    let #map_fn: &mut dyn ::core::ops::FnMut(#replacing_ty)
+                                        -> ::core::option::Option<#mapped_param> = &mut #map_fn;
+                                }
+                                #{emit_map_f(out_map.as_slice())}
+                            }
+                        }
+                    });
+                }
+                if by_ref {
+                    let where_clause = input.clone_where_clause(generics.where_clause.clone());
+                    return Ok(quote! {
+                        impl <
+                            #(for p in &generics.params){ #p, }
+                            #mapped_param_impl_generics
+                        > #krate::ParametrizedMapRef<#param_index, #mapped_param> for #ident #ty_generics #where_clause {
+                            type Mapped = #mapped;
+                            fn param_map_ref<#map_lt>(
+                                &#map_lt #self_val,
+                                mut #map_fn: impl FnMut(&#map_lt Self::Item) -> #mapped_param,
+                            ) -> Self::Mapped
+                            where
+                                Self::Item: #map_lt
+                            {
+                                #(if input.is_recursive()) {
+                                    let #map_fn: &mut dyn ::core::ops::FnMut(&#map_lt #replacing_ty)
+                                        -> #mapped_param = &mut #map_fn;
+                                }
+                                #{emit_map_f(out_map.as_slice())}
+                            }
+                        }
+                    });
+                }
                 Ok(quote! {
                     impl <
                         #(for p in &generics.params){ #p, }
@@ -409,6 +2007,12 @@ impl TraitTarget {
                         where
                             Self::Item: ::core::marker::Sized
                         {
+                            #(if input.is_recursive()) {
+                                // Erase the closure type so that recursive calls reuse a
+                                // single instantiation of `param_map`.
+                                let #map_fn: &mut dyn ::core::ops::FnMut(#replacing_ty) -> #mapped_param =
+                                    &mut #map_fn;
+                            }
                             #{emit_map_f(out_map.as_slice())}
                         }
                     }
@@ -426,58 +2030,407 @@ impl Parse for TraitTarget {
             "iter_mut" => Self::IterMut,
             "into_iter" => Self::IntoIter,
             "map" => Self::Map,
-            _ => return Err(input.error("Require one of `iter`, `iter_mut`, `into_iter`, `map`")),
+            "try_map" => Self::TryMap,
+            "map_ref" => Self::MapRef,
+            "zip" => Self::Zip,
+            "unzip" => Self::Unzip,
+            "retain" => Self::Retain,
+            "filter_map" => Self::FilterMap,
+            "update" => Self::Update,
+            "path" => Self::Path,
+            "diff" => Self::Diff,
+            _ => {
+                return Err(input.error(
+                    "Require one of `iter`, `iter_mut`, `into_iter`, `map`, `try_map`, \
+                     `map_ref`, `zip`, `unzip`, `retain`, `filter_map`, `update`, `path`, \
+                     `diff`, `bimap`, `union`, `union_mut`",
+                ))
+            }
         };
         input.parse::<Ident>()?;
         Ok(r)
     }
 }
 
+/// A type parameter selected in the arguments, either by its index or by its name.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum ParamSpec {
+    /// The first type parameter, selected by giving none.
+    First,
+    Index(LitInt),
+    Name(Ident),
+}
+
+impl Parse for ParamSpec {
+    fn parse(input: parse::ParseStream) -> Result<Self> {
+        if input.peek(Ident) {
+            Ok(Self::Name(input.parse()?))
+        } else {
+            Ok(Self::Index(input.parse()?))
+        }
+    }
+}
+
+impl ParamSpec {
+    /// Resolve to the position of the type parameter among the type parameters.
+    /// Numeric indices count type parameters only, or all generic parameters
+    /// with `legacy_index`. Without it, an index which selected another type
+    /// parameter in earlier versions is rejected rather than silently changed.
+    fn resolve(&self, generics: &Generics, legacy_index: bool) -> Result<usize> {
+        match self {
+            Self::First => Ok(0),
+            Self::Index(lit) => {
+                let index: usize = lit.base10_parse()?;
+                let legacy = match generics.params.iter().nth(index) {
+                    Some(GenericParam::Type(TypeParam { ident, .. })) => Some(ident),
+                    _ => None,
+                };
+                let current = generics.type_params().nth(index).map(|p| &p.ident);
+                match (legacy_index, legacy, current) {
+                    (true, Some(legacy), _) | (false, None, Some(legacy)) => Ok(generics
+                        .type_params()
+                        .position(|p| &p.ident == legacy)
+                        .unwrap()),
+                    (false, Some(legacy), current) if current != Some(legacy) => Err(Error::new(
+                        lit.span(),
+                        format!(
+                            "Indices count type parameters only, so `{}` no longer selects \
+                                 `{}`; select it by its name or by its index `{}`, or pass \
+                                 `legacy_index` to count all generic parameters",
+                            index,
+                            legacy,
+                            generics
+                                .type_params()
+                                .position(|p| &p.ident == legacy)
+                                .unwrap()
+                        ),
+                    )),
+                    (false, Some(_), Some(_)) => Ok(index),
+                    _ => Err(Error::new(
+                        lit.span(),
+                        format!("Generic parameter {} is not a type parameter", index),
+                    )),
+                }
+            }
+            Self::Name(name) => generics
+                .type_params()
+                .position(|p| &p.ident == name)
+                .ok_or_else(|| {
+                    Error::new(name.span(), format!("`{}` is not a type parameter", name))
+                }),
+        }
+    }
+}
+
+/// How a field is traversed, given by the `#[param(..)]` helper attribute on
+/// the field or on its variant.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+enum FieldMode {
+    #[default]
+    Traverse,
+    Skip,
+    Opaque,
+    With(Path),
+}
+
+impl Parse for FieldMode {
+    fn parse(input: parse::ParseStream) -> Result<Self> {
+        let ident: Ident = input.parse()?;
+        if &ident == "skip" {
+            Ok(Self::Skip)
+        } else if &ident == "opaque" {
+            Ok(Self::Opaque)
+        } else if &ident == "with" {
+            input.parse::<Token![=]>()?;
+            Ok(Self::With(input.parse()?))
+        } else {
+            Err(Error::new(
+                ident.span(),
+                "Require one of `skip`, `opaque`, `with = <path>`",
+            ))
+        }
+    }
+}
+
+impl FieldMode {
+    /// Removes `#[param(..)]` attributes from `attrs` and returns the mode they give.
+    fn take(attrs: &mut Vec<Attribute>) -> Result<Self> {
+        let mut ret = None;
+        let mut err = Ok(());
+        attrs.retain(|attr| {
+            if !attr.path().is_ident("param") {
+                return true;
+            }
+            match attr.parse_args::<Self>() {
+                Ok(_) if ret.is_some() => {
+                    err = Err(Error::new(attr.span(), "Duplicated `param` attribute"));
+                }
+                Ok(mode) => ret = Some(mode),
+                Err(e) => err = Err(e),
+            }
+            false
+        });
+        err.map(|_| ret.unwrap_or_default())
+    }
+}
+
+/// What the attributes of the annotated item tell about its fields, indexed
+/// like [`ImplInput::tys_exprs`].
+struct FieldAttrs {
+    modes: Vec<Vec<FieldMode>>,
+    /// Predicates of `#[cfg(..)]` on fields.
+    cfgs: Vec<Vec<Option<TokenStream>>>,
+    /// Predicates of `#[cfg(..)]` on enum variants.
+    item_cfgs: Vec<Option<TokenStream>>,
+}
+
+/// Combines the predicates of `#[cfg(..)]` in `attrs`.
+fn cfg_predicate(attrs: &[Attribute]) -> Result<Option<TokenStream>> {
+    let preds = attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("cfg"))
+        .map(|attr| Ok(attr.meta.require_list()?.tokens.clone()))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(match preds.len() {
+        0 => None,
+        1 => preds.into_iter().next(),
+        _ => Some(quote!(all(#(#preds),*))),
+    })
+}
+
+fn field_cfgs(fields: &Fields) -> Result<Vec<Option<TokenStream>>> {
+    fields
+        .iter()
+        .map(|field| cfg_predicate(&field.attrs))
+        .collect()
+}
+
+/// The distinct predicates of `#[cfg(..)]` on unnamed fields, which shift the
+/// indices of the fields after them.
+fn positional_cfgs<T: ImplTarget>(target: &mut T) -> Result<Vec<TokenStream>> {
+    let mut cfgs: Vec<TokenStream> = Vec::new();
+    for fields in target.fields_mut() {
+        if let Fields::Unnamed(fields) = fields {
+            for field in &fields.unnamed {
+                if let Some(cfg) = cfg_predicate(&field.attrs)? {
+                    if !cfgs.iter().any(|c| c.to_string() == cfg.to_string()) {
+                        if cfgs.len() == MAX_POSITIONAL_CFGS {
+                            return Err(Error::new(
+                                cfg.span(),
+                                format!(
+                                    "At most {} distinct `#[cfg(..)]` predicates are allowed on \
+                                     unnamed fields, since the traits are implemented for every \
+                                     combination of them; name the fields or share the predicates",
+                                    MAX_POSITIONAL_CFGS
+                                ),
+                            ));
+                        }
+                        cfgs.push(cfg);
+                    }
+                }
+            }
+        }
+    }
+    Ok(cfgs)
+}
+
+/// The number of distinct predicates [`positional_cfgs()`] accepts, which
+/// bounds the number of impls at `2^MAX_POSITIONAL_CFGS`.
+const MAX_POSITIONAL_CFGS: usize = 4;
+
+/// Removes the unnamed fields under the predicates of `cfgs` which do not hold
+/// by `holds`, and the `#[cfg(..)]` of those kept.
+fn configure_positional<T: ImplTarget>(
+    target: &mut T,
+    cfgs: &[TokenStream],
+    holds: &[bool],
+) -> Result<()> {
+    for fields in target.fields_mut() {
+        if let Fields::Unnamed(fields) = fields {
+            let mut kept = punctuated::Punctuated::new();
+            for mut field in core::mem::take(&mut fields.unnamed) {
+                if let Some(cfg) = cfg_predicate(&field.attrs)? {
+                    let index = cfgs
+                        .iter()
+                        .position(|c| c.to_string() == cfg.to_string())
+                        .unwrap();
+                    if !holds[index] {
+                        continue;
+                    }
+                    field.attrs.retain(|attr| !attr.path().is_ident("cfg"));
+                }
+                kept.push(field);
+            }
+            fields.unnamed = kept;
+        }
+    }
+    Ok(())
+}
+
+/// Puts `#[cfg(..)]` with `cfg` on every item of `items`.
+fn gate_items(cfg: &TokenStream, items: TokenStream) -> Result<TokenStream> {
+    let file: File = parse2(items)?;
+    Ok(quote! {
+        #(for item in &file.items) {
+            #[cfg(#cfg)]
+            #item
+        }
+    })
+}
+
+/// The expression of the field at `expr` in the other value of `param_zip_with`
+/// or `param_pair`, which is `self.f` with `self` renamed, or the renamed binding
+/// of a variant field.
+fn zip_other_expr(expr: &Expr) -> Expr {
+    use syn::ext::IdentExt;
+    match expr {
+        Expr::Field(ExprField { base, member, .. }) => {
+            let base = zip_other_expr(base);
+            parse_quote!(#base.#member)
+        }
+        Expr::Reference(ExprReference { expr, .. }) => {
+            let expr = zip_other_expr(expr);
+            parse_quote!(&#expr)
+        }
+        Expr::Path(ExprPath { path, .. }) if path.get_ident().is_some() => {
+            let ident = path.get_ident().unwrap();
+            if ident == "self" {
+                parse_quote!(__parametrized_other)
+            } else {
+                let ident = Ident::new(
+                    &format!("__parametrized_other_{}", ident.unraw()),
+                    ident.span(),
+                );
+                parse_quote!(#ident)
+            }
+        }
+        _ => unreachable!("fields are accessed by `self.f` or bindings"),
+    }
+}
+
+/// Bindings of the left and the right halves of `len` unzipped fields.
+fn unzip_bindings(len: usize) -> (Vec<Expr>, Vec<Expr>) {
+    let binding = |side: &str, i: usize| -> Expr {
+        let ident = Ident::new(&format!("__parametrized_{}_{}", side, i), Span::call_site());
+        parse_quote!(#ident)
+    };
+    (0..len)
+        .map(|i| (binding("left", i), binding("right", i)))
+        .unzip()
+}
+
+/// An expression evaluating to `then` if `cfg` holds, and to `otherwise` if not.
+fn cfg_select(cfg: &TokenStream, then: impl ToTokens, otherwise: impl ToTokens) -> Expr {
+    parse_quote! {
+        {
+            #[cfg(#cfg)]
+            let __parametrized_cfg = #then;
+            #[cfg(not(#cfg))]
+            let __parametrized_cfg = #otherwise;
+            __parametrized_cfg
+        }
+    }
+}
+
+/// The type of [`cfg_iter()`] wrapping the iterator type `ty`.
+fn cfg_iter_ty(krate: &Path, ty: &Type) -> Type {
+    parse_quote!(#krate::Flatten<::core::option::IntoIter<#ty>, #ty>)
+}
+
+/// An iterator which is `iter` if `cfg` holds, and empty if not, of the same
+/// type either way. Where the type is not named, as where the values are
+/// collected, the empty one yields `item` instead.
+fn cfg_iter(krate: &Path, cfg: &TokenStream, iter: Expr, item: Option<TokenStream>) -> Expr {
+    let iter = cfg_select(
+        cfg,
+        quote!(::core::option::Option::Some(#iter)),
+        match item {
+            Some(item) => quote!(::core::option::Option::None::<::core::iter::Empty<#item>>),
+            None => quote!(::core::option::Option::None),
+        },
+    );
+    parse_quote!(#krate::Flatten::new(::core::option::Option::into_iter(#iter)))
+}
+
 #[derive(Debug, Default)]
 struct Arguments {
-    trait_impls: HashMap<usize, HashSet<TraitTarget>>,
+    trait_impls: HashMap<ParamSpec, HashSet<TraitTarget>>,
+    /// Pairs of parameters given by `bimap`, `union` or `union_mut` (the ident)
+    /// as `<arg> = [<PARAM0>, <PARAM1>]`.
+    pairs: Vec<(Ident, ParamSpec, ParamSpec)>,
     krate: Option<Path>,
+    recursive: Vec<Path>,
+    legacy_index: bool,
+    no_param_consts: bool,
+    boxed: bool,
+    named_iter: bool,
 }
 
 impl Parse for Arguments {
     fn parse(input: parse::ParseStream) -> Result<Self> {
         let mut ret: Self = Default::default();
         while input.peek(Ident) {
-            if let Ok(tr) = input.parse::<TraitTarget>() {
-                // parse number or array
-                let param_indices = if input.parse::<Token![=]>().is_ok() {
-                    if let Ok(expr_arr) = input.parse::<ExprArray>() {
-                        expr_arr
-                            .elems
+            if ["bimap", "union", "union_mut"]
+                .contains(&input.fork().parse::<Ident>()?.to_string().as_str())
+            {
+                let ident: Ident = input.parse()?;
+                input.parse::<Token![=]>()?;
+                let content;
+                bracketed!(content in input);
+                let params = content.parse_terminated(ParamSpec::parse, Token![,])?;
+                if let [p0, p1] = params.into_iter().collect::<Vec<_>>().as_slice() {
+                    ret.pairs.push((ident, p0.clone(), p1.clone()));
+                } else {
+                    return Err(Error::new(
+                        ident.span(),
+                        format!(
+                            "Require two parameters as `{} = [<PARAM0>, <PARAM1>]`",
+                            ident
+                        ),
+                    ));
+                }
+            } else if let Ok(tr) = input.parse::<TraitTarget>() {
+                // parse index, name or array of them
+                let params = if input.parse::<Token![=]>().is_ok() {
+                    if input.peek(token::Bracket) {
+                        let content;
+                        bracketed!(content in input);
+                        content
+                            .parse_terminated(ParamSpec::parse, Token![,])?
                             .into_iter()
-                            .map(|expr| {
-                                if let Expr::Lit(ExprLit {
-                                    lit: Lit::Int(lit), ..
-                                }) = expr
-                                {
-                                    lit.base10_parse::<usize>()
-                                } else {
-                                    Err(Error::new(expr.span(), "Bad integer"))
-                                }
-                            })
-                            .collect::<Result<Vec<_>>>()?
+                            .collect()
                     } else {
-                        vec![input.parse::<LitInt>()?.base10_parse::<usize>()?]
+                        vec![input.parse()?]
                     }
                 } else {
-                    vec![0]
+                    vec![ParamSpec::First]
                 };
-                for param_index in param_indices {
-                    ret.trait_impls
-                        .entry(param_index)
-                        .or_insert(HashSet::new())
-                        .insert(tr.clone());
+                for param in params {
+                    ret.trait_impls.entry(param).or_default().insert(tr.clone());
                 }
             } else {
                 let ident: Ident = input.parse()?;
                 if &ident == "krate" {
                     input.parse::<Token![=]>()?;
                     ret.krate = Some(input.parse()?);
+                } else if &ident == "legacy_index" {
+                    ret.legacy_index = true;
+                } else if &ident == "no_param_consts" {
+                    ret.no_param_consts = true;
+                } else if &ident == "boxed" {
+                    ret.boxed = true;
+                } else if &ident == "named_iter" {
+                    ret.named_iter = true;
+                } else if &ident == "recursive" {
+                    input.parse::<Token![=]>()?;
+                    if input.peek(token::Bracket) {
+                        let content;
+                        bracketed!(content in input);
+                        ret.recursive
+                            .extend(content.parse_terminated(Path::parse_mod_style, Token![,])?);
+                    } else {
+                        ret.recursive.push(Path::parse_mod_style(input)?);
+                    }
                 } else {
                     return Err(Error::new(ident.span(), "Bad option"));
                 }
@@ -495,20 +2448,91 @@ impl Parse for Arguments {
 }
 
 trait ImplTarget {
-    fn emit_impl(&self, krate: &Path, tr: &TraitTarget, param_index: usize) -> Result<TokenStream>;
+    fn ident(&self) -> &Ident;
+    fn generics(&self) -> &Generics;
+    fn vis(&self) -> &Visibility;
+    /// The fields of each item.
+    fn fields_mut(&mut self) -> Vec<&mut Fields>;
+    /// Strips the helper attributes and collects what the attributes tell
+    /// about the fields of each item.
+    fn take_field_attrs(&mut self) -> Result<FieldAttrs>;
+    fn emit_impl(
+        &self,
+        args: &Arguments,
+        krate: &Path,
+        tr: &TraitTarget,
+        param_index: usize,
+        attrs: &FieldAttrs,
+    ) -> Result<TokenStream>;
+}
+
+/// The `PathSegment` naming the `index`-th field.
+/// Whether some values of `param` in `ty` are neither `param` itself inside a
+/// collection or an `Option`, nor inside such values, so that they cannot be
+/// removed from `ty`.
+fn holds_unremovable(ty: &Type, param: &Type) -> bool {
+    match ty {
+        _ if ty == param => true,
+        Type::Tuple(TypeTuple { elems, .. }) => elems.iter().any(|ty| holds_unremovable(ty, param)),
+        Type::Array(TypeArray { elem, .. })
+        | Type::Slice(TypeSlice { elem, .. })
+        | Type::Group(TypeGroup { elem, .. })
+        | Type::Paren(TypeParen { elem, .. })
+        | Type::Reference(TypeReference { elem, .. }) => holds_unremovable(elem, param),
+        Type::Path(TypePath { path, .. }) => match path.segments.last().map(|seg| &seg.arguments) {
+            Some(PathArguments::AngleBracketed(abga)) => abga.args.iter().any(|arg| {
+                matches!(arg, GenericArgument::Type(ty) if ty != param && holds_unremovable(ty, param))
+            }),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// The name of the field which `expr` accesses, as written in the item.
+fn field_name(expr: &Expr) -> String {
+    let name = match expr {
+        Expr::Field(ExprField { member, .. }) => quote!(#member).to_string(),
+        _ => quote!(#expr).to_string(),
+    };
+    let name = name.trim_start_matches("r#");
+    name.strip_prefix("__parametric_type_id_")
+        .unwrap_or(name)
+        .to_string()
+}
+
+fn field_segment(krate: &Path, index: usize, field: &Field) -> TokenStream {
+    match &field.ident {
+        Some(ident) => {
+            let name = ident.to_string();
+            let name = name.trim_start_matches("r#");
+            quote!(#krate::PathSegment::Field(#name))
+        }
+        None => quote!(#krate::PathSegment::TupleField(#index)),
+    }
+}
+
+/// The `PARAM` of the traits implemented for the `position`-th type parameter,
+/// which counts all generic parameters with `legacy_index`.
+fn trait_index(generics: &Generics, position: usize, legacy_index: bool) -> usize {
+    if legacy_index {
+        generics
+            .params
+            .iter()
+            .enumerate()
+            .filter(|(_, p)| matches!(p, GenericParam::Type(_)))
+            .nth(position)
+            .map_or(position, |(index, _)| index)
+    } else {
+        position
+    }
 }
 
 fn get_replacing_ty(generics: &Generics, param_index: usize) -> Type {
     generics
-        .params
-        .get(param_index)
-        .and_then(|g| {
-            if let GenericParam::Type(TypeParam { ident, .. }) = g {
-                Some(parse_quote!(#ident))
-            } else {
-                None
-            }
-        })
+        .type_params()
+        .nth(param_index)
+        .map(|TypeParam { ident, .. }| parse_quote!(#ident))
         .unwrap_or_else(|| {
             abort!(
                 Span::call_site(),
@@ -518,9 +2542,38 @@ fn get_replacing_ty(generics: &Generics, param_index: usize) -> Type {
 }
 
 impl ImplTarget for ItemStruct {
-    fn emit_impl(&self, krate: &Path, tr: &TraitTarget, param_index: usize) -> Result<TokenStream> {
+    fn ident(&self) -> &Ident {
+        &self.ident
+    }
+    fn generics(&self) -> &Generics {
+        &self.generics
+    }
+    fn vis(&self) -> &Visibility {
+        &self.vis
+    }
+    fn fields_mut(&mut self) -> Vec<&mut Fields> {
+        vec![&mut self.fields]
+    }
+    fn take_field_attrs(&mut self) -> Result<FieldAttrs> {
+        Ok(FieldAttrs {
+            modes: vec![self
+                .fields
+                .iter_mut()
+                .map(|field| FieldMode::take(&mut field.attrs))
+                .collect::<Result<_>>()?],
+            cfgs: vec![field_cfgs(&self.fields)?],
+            item_cfgs: vec![None],
+        })
+    }
+    fn emit_impl(
+        &self,
+        args: &Arguments,
+        krate: &Path,
+        tr: &TraitTarget,
+        param_index: usize,
+        attrs: &FieldAttrs,
+    ) -> Result<TokenStream> {
         let self_val = Ident::new("self", Span::call_site());
-        let replacing_ty = get_replacing_ty(&self.generics, param_index);
         let tys_exprs = self
             .fields
             .iter()
@@ -538,39 +2591,125 @@ impl ImplTarget for ItemStruct {
                 }
             })
             .collect::<Vec<_>>();
-        tr.emit(
+        let segments = self
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, field)| field_segment(krate, i, field))
+            .collect();
+        let input = ImplInput::new(
             krate,
             &self.ident,
             &self.generics,
             param_index,
-            &replacing_ty,
-            &self_val,
-            &[tys_exprs],
+            vec![tys_exprs],
+            attrs,
+            true,
+            vec![segments],
+            &self.vis,
+            Vec::new(),
+            args,
+        );
+        let construct = |items: &[Vec<Expr>]| {
+            quote! {
+                #[allow(unused)]
+                #{&self.ident}
+                #(if let Fields::Named(_) = &self.fields) {
+                    {#(for ((inner, field), cfg) in items[0].iter().zip(&self.fields).zip(&attrs.cfgs[0])) {
+                        #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                        #{&field.ident} : #inner,
+                    }}
+                } #(else) {
+                    ( #(for inner in items[0].iter()), { #inner })
+                }
+            }
+        };
+        tr.emit(
+            &input,
             |inner| {
                 quote! { #(#inner)* }
             },
+            construct,
+            |items| quote!(::core::result::Result::Ok(#{construct(items)})),
             |items| {
+                let (lefts, rights) = unzip_bindings(items[0].len());
                 quote! {
-                    #[allow(unused)]
-                    #{&self.ident}
-                    #(if let Fields::Named(_) = &self.fields) {
-                        {#(for (inner, field) in items[0].iter().zip(&self.fields)) {
-                            #{&field.ident} : #inner,
-                        }}
-                    } #(else) {
-                        ( #(for inner in items[0].iter()), { #inner })
+                    #(for (((inner, left), right), cfg) in items[0].iter().zip(&lefts).zip(&rights).zip(&attrs.cfgs[0])) {
+                        #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                        let (#left, #right) = #inner;
                     }
+                    (#{construct(core::slice::from_ref(&lefts))}, #{construct(core::slice::from_ref(&rights))})
                 }
             },
-            true,
+            |inner| {
+                quote! { #(#inner)* }
+            },
         )
     }
 }
 
 impl ImplTarget for ItemEnum {
-    fn emit_impl(&self, krate: &Path, tr: &TraitTarget, param_index: usize) -> Result<TokenStream> {
+    fn ident(&self) -> &Ident {
+        &self.ident
+    }
+    fn generics(&self) -> &Generics {
+        &self.generics
+    }
+    fn vis(&self) -> &Visibility {
+        &self.vis
+    }
+    fn fields_mut(&mut self) -> Vec<&mut Fields> {
+        self.variants
+            .iter_mut()
+            .map(|variant| &mut variant.fields)
+            .collect()
+    }
+    fn take_field_attrs(&mut self) -> Result<FieldAttrs> {
+        let modes = self
+            .variants
+            .iter_mut()
+            .map(|variant| {
+                let variant_mode = FieldMode::take(&mut variant.attrs)?;
+                if let FieldMode::With(path) = &variant_mode {
+                    return Err(Error::new(path.span(), "`with` is only allowed on fields"));
+                }
+                variant
+                    .fields
+                    .iter_mut()
+                    .map(|field| {
+                        let mode = FieldMode::take(&mut field.attrs)?;
+                        if mode == FieldMode::Traverse {
+                            Ok(variant_mode.clone())
+                        } else {
+                            Ok(mode)
+                        }
+                    })
+                    .collect()
+            })
+            .collect::<Result<_>>()?;
+        Ok(FieldAttrs {
+            modes,
+            cfgs: self
+                .variants
+                .iter()
+                .map(|variant| field_cfgs(&variant.fields))
+                .collect::<Result<_>>()?,
+            item_cfgs: self
+                .variants
+                .iter()
+                .map(|variant| cfg_predicate(&variant.attrs))
+                .collect::<Result<_>>()?,
+        })
+    }
+    fn emit_impl(
+        &self,
+        args: &Arguments,
+        krate: &Path,
+        tr: &TraitTarget,
+        param_index: usize,
+        attrs: &FieldAttrs,
+    ) -> Result<TokenStream> {
         let self_val: Ident = Ident::new("self", Span::call_site());
-        let replacing_ty = get_replacing_ty(&self.generics, param_index);
         let variant_idents = self
             .variants
             .iter()
@@ -603,24 +2742,114 @@ impl ImplTarget for ItemEnum {
                     .collect::<Vec<_>>()
             })
             .collect::<Vec<_>>();
-        tr.emit(
+        // Patterns of a variant, binding the fields of the other value when
+        // `other` is set.
+        let pattern = |variant: &Variant,
+                       idents: &[Ident],
+                       cfgs: &[Option<TokenStream>],
+                       other: bool| {
+            let bindings = idents
+                .iter()
+                .map(|ident| {
+                    if other {
+                        zip_other_expr(&parse_quote!(#ident))
+                    } else {
+                        parse_quote!(#ident)
+                    }
+                })
+                .collect::<Vec<Expr>>();
+            quote! {
+                #{&self.ident}::#{&variant.ident}
+                #(if let Fields::Named(_) = &variant.fields) {
+                    { #(for ((ident, binding), cfg) in idents.iter().zip(&bindings).zip(cfgs)), {
+                        #(if let Some(cfg) = cfg) { #[cfg(#cfg)] } #ident #(if other) { : #binding }
+                    } }
+                }
+                #(if let Fields::Unnamed(_) = &variant.fields) {
+                    ( #(#bindings),* )
+                }
+            }
+        };
+        // The constructor of a variant from the expressions of its fields.
+        let construct = |variant: &Variant, inner: &[Expr], cfgs: &[Option<TokenStream>]| {
+            quote! {
+                #{&self.ident}::#{&variant.ident}
+                #(if let Fields::Named(_) = &variant.fields) {
+                    {
+                        #(for ((field, inner), cfg) in variant.fields.iter().zip(inner).zip(cfgs)) {
+                            #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                            #{&field.ident}: #inner,
+                        }
+                    }
+                }
+                #(if let Fields::Unnamed(_) = &variant.fields) {
+                    ( #(#inner),* )
+                }
+            }
+        };
+        let segments = self
+            .variants
+            .iter()
+            .map(|variant| {
+                let name = variant.ident.to_string();
+                let name = name.trim_start_matches("r#");
+                variant
+                    .fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, field)| {
+                        quote! {
+                            #krate::PathSegment::Variant(#name),
+                            #{field_segment(krate, i, field)}
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        let input = ImplInput::new(
             krate,
             &self.ident,
             &self.generics,
             param_index,
-            &replacing_ty,
-            &self_val,
-            variant_items.as_slice(),
+            variant_items,
+            attrs,
+            false,
+            segments,
+            &self.vis,
+            self.variants
+                .iter()
+                .map(|variant| variant.ident.clone())
+                .collect(),
+            args,
+        );
+        let variant_name = |val: &Ident| {
+            quote! {
+                match #val {
+                    #(for (variant, cfg) in self.variants.iter().zip(&attrs.item_cfgs)) {
+                        #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                        #{&self.ident}::#{&variant.ident} { .. } => #{variant.ident.to_string()},
+                    }
+                }
+            }
+        };
+        let lhs: Ident = parse_quote!(__parametrized_lhs);
+        let rhs: Ident = parse_quote!(__parametrized_rhs);
+        tr.emit(
+            &input,
             |inner| {
                 quote! {
                     #[allow(unused)]
                     match #self_val {
-                        #(for ((variant, inner), idents) in self
+                        #(for (((variant, inner), idents), (cfg, cfgs)) in self
                             .variants.iter().zip(inner).zip(&variant_idents)
+                            .zip(attrs.item_cfgs.iter().zip(&attrs.cfgs))
                         ) {
+                            #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
                             #{&self.ident}::#{&variant.ident}
                                 #(if let Fields::Named(_) = &variant.fields) {
-                                    { #(#idents),*  }
+                                    { #(for (ident, cfg) in idents.iter().zip(cfgs)), {
+                                        #(if let Some(cfg) = cfg) { #[cfg(#cfg)] } #ident
+                                    } }
                                 }
                                 #(if let Fields::Unnamed(_) = &variant.fields) {
                                     ( #(#idents),* )
@@ -633,12 +2862,16 @@ impl ImplTarget for ItemEnum {
             |items| {
                 quote! {
                     match #self_val {
-                        #(for ((variant, inner), idents) in self
+                        #(for (((variant, inner), idents), (cfg, cfgs)) in self
                             .variants.iter().zip(items).zip(&variant_idents)
+                            .zip(attrs.item_cfgs.iter().zip(&attrs.cfgs))
                         ) {
+                            #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
                             #{&self.ident}::#{&variant.ident}
                                 #(if let Fields::Named(_) = &variant.fields) {
-                                    { #(#idents),*  }
+                                    { #(for (ident, cfg) in idents.iter().zip(cfgs)), {
+                                        #(if let Some(cfg) = cfg) { #[cfg(#cfg)] } #ident
+                                    } }
                                 }
                                 #(if let Fields::Unnamed(_) = &variant.fields) {
                                     ( #(#idents),* )
@@ -647,7 +2880,8 @@ impl ImplTarget for ItemEnum {
                                 #{&self.ident}::#{&variant.ident}
                                 #(if let Fields::Named(_) = &variant.fields) {
                                     {
-                                        #(for (ident, inner) in idents.iter().zip(inner)) {
+                                        #(for ((ident, inner), cfg) in idents.iter().zip(inner).zip(cfgs)) {
+                                            #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
                                             #ident: #inner,
                                         }
                                     }
@@ -660,29 +2894,213 @@ impl ImplTarget for ItemEnum {
                     }
                 }
             },
-            false,
+            |items| {
+                quote! {
+                    #[allow(unused)]
+                    match (#self_val, #{zip_other_expr(&parse_quote!(#self_val))}) {
+                        #(for (((variant, inner), idents), (cfg, cfgs)) in self
+                            .variants.iter().zip(items).zip(&variant_idents)
+                            .zip(attrs.item_cfgs.iter().zip(&attrs.cfgs))
+                        ) {
+                            #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                            (
+                                #{pattern(variant, idents, cfgs, false)},
+                                #{pattern(variant, idents, cfgs, true)},
+                            ) => ::core::result::Result::Ok(
+                                #{&self.ident}::#{&variant.ident}
+                                #(if let Fields::Named(_) = &variant.fields) {
+                                    {
+                                        #(for ((ident, inner), cfg) in idents.iter().zip(inner).zip(cfgs)) {
+                                            #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                                            #ident: #inner,
+                                        }
+                                    }
+                                }
+                                #(if let Fields::Unnamed(_) = &variant.fields) {
+                                    ( #(#inner),* )
+                                }
+                            ),
+                        }
+                        #[allow(unreachable_patterns)]
+                        (#lhs, #rhs) => ::core::result::Result::Err(::core::convert::From::from(
+                            #krate::ShapeMismatch::Variant {
+                                expected: #{variant_name(&lhs)},
+                                found: #{variant_name(&rhs)},
+                            },
+                        )),
+                    }
+                }
+            },
+            |items| {
+                quote! {
+                    #[allow(unused)]
+                    match #self_val {
+                        #(for (((variant, inner), idents), (cfg, cfgs)) in self
+                            .variants.iter().zip(items).zip(&variant_idents)
+                            .zip(attrs.item_cfgs.iter().zip(&attrs.cfgs))
+                        ) {
+                            #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                            #{pattern(variant, idents, cfgs, false)} => {
+                                #{
+                                    let (lefts, rights) = unzip_bindings(inner.len());
+                                    quote! {
+                                        #(for (((inner, left), right), cfg) in inner.iter().zip(&lefts).zip(&rights).zip(cfgs)) {
+                                            #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                                            let (#left, #right) = #inner;
+                                        }
+                                        (#{construct(variant, &lefts, cfgs)}, #{construct(variant, &rights, cfgs)})
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            |items| {
+                quote! {
+                    #[allow(unused)]
+                    match (#self_val, #{zip_other_expr(&parse_quote!(#self_val))}) {
+                        #(for (((variant, inner), idents), (cfg, cfgs)) in self
+                            .variants.iter().zip(items).zip(&variant_idents)
+                            .zip(attrs.item_cfgs.iter().zip(&attrs.cfgs))
+                        ) {
+                            #(if let Some(cfg) = cfg) { #[cfg(#cfg)] }
+                            (
+                                #{pattern(variant, idents, cfgs, false)},
+                                #{pattern(variant, idents, cfgs, true)},
+                            ) => { #inner }
+                        }
+                        #[allow(unreachable_patterns)]
+                        (#lhs, #rhs) => #krate::_imp::Vec::from([#krate::ParamDiff::Shape {
+                            path: #krate::ParamPath::new(),
+                            mismatch: #krate::ShapeMismatch::Variant {
+                                expected: #{variant_name(&lhs)},
+                                found: #{variant_name(&rhs)},
+                            },
+                        }]),
+                    }
+                }
+            },
         )
     }
 }
 
-fn inner_target<T: ImplTarget + ToTokens>(target: &T, arg: Arguments) -> TokenStream {
-    let krate = arg.krate.unwrap_or(parse_quote!(::parametrized));
+/// Name of the associated const holding the `PARAM` index of `param`, such as
+/// `PARAM_OPERAND` for `Operand` or `PARAM_SRC_REG` for `SrcReg`.
+fn param_const_ident(param: &Ident) -> Ident {
+    let mut name = "PARAM_".to_owned();
+    let mut prev_lower = false;
+    for c in param.to_string().trim_start_matches("r#").chars() {
+        if c.is_uppercase() && prev_lower {
+            name.push('_');
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+        name.extend(c.to_uppercase());
+    }
+    Ident::new(&name, param.span())
+}
+
+fn emit_param_consts<T: ImplTarget>(target: &T, legacy_index: bool) -> TokenStream {
+    let generics = target.generics();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    quote! {
+        #[allow(dead_code)]
+        impl #impl_generics #{target.ident()} #ty_generics #where_clause {
+            #(for (position, TypeParam { ident, .. }) in generics.type_params().enumerate()) {
+                #[doc = #{format!("`PARAM` index of the type parameter `{}`.", ident)}]
+                #{target.vis()} const #{param_const_ident(ident)}: usize =
+                    #{trait_index(generics, position, legacy_index)};
+            }
+        }
+    }
+}
+
+fn inner_target<T: ImplTarget + ToTokens + Clone>(mut target: T, arg: Arguments) -> TokenStream {
+    let krate = arg.krate.clone().unwrap_or(parse_quote!(::parametrized));
+    // The indices of unnamed fields depend on which fields before them are
+    // compiled in, so the impls are emitted for every combination of their
+    // predicates.
+    let cfgs = positional_cfgs(&mut target).unwrap_or_else(|e| abort!(e.span(), e));
+    let configured = (0..1usize << cfgs.len())
+        .map(|mask| {
+            let holds = (0..cfgs.len())
+                .map(|i| mask & (1 << i) != 0)
+                .collect::<Vec<_>>();
+            let mut configured = target.clone();
+            configure_positional(&mut configured, &cfgs, &holds)
+                .unwrap_or_else(|e| abort!(e.span(), e));
+            let attrs = configured
+                .take_field_attrs()
+                .unwrap_or_else(|e| abort!(e.span(), e));
+            let gate = (!cfgs.is_empty()).then(|| {
+                let preds = cfgs.iter().zip(&holds).map(|(cfg, holds)| {
+                    if *holds {
+                        quote!(#cfg)
+                    } else {
+                        quote!(not(#cfg))
+                    }
+                });
+                quote!(all(#(#preds),*))
+            });
+            (configured, attrs, gate)
+        })
+        .collect::<Vec<_>>();
+    target
+        .take_field_attrs()
+        .unwrap_or_else(|e| abort!(e.span(), e));
     let mut out = quote!(#target);
-    for (param_index, impl_traits) in &arg.trait_impls {
-        let impl_traits = TraitTarget::make_enough(impl_traits.clone());
-        for impl_trait in &impl_traits {
-            let ret = target
-                .emit_impl(&krate, impl_trait, *param_index)
-                .unwrap_or_else(|e| {
-                    abort!(
-                        e.span(),
-                        format!(
-                            "Cannot implement {:?} for parameter {}: {}",
-                            &impl_trait, param_index, e
+    if !arg.no_param_consts {
+        out.extend(emit_param_consts(&target, arg.legacy_index));
+    }
+    let mut trait_impls: HashMap<usize, HashSet<TraitTarget>> = HashMap::new();
+    for (param, impl_traits) in &arg.trait_impls {
+        let param_index = param
+            .resolve(target.generics(), arg.legacy_index)
+            .unwrap_or_else(|e| abort!(e.span(), e));
+        trait_impls
+            .entry(param_index)
+            .or_default()
+            .extend(impl_traits.iter().cloned());
+    }
+    for (ident, p0, p1) in &arg.pairs {
+        let [i0, i1] = [p0, p1].map(|param| {
+            param
+                .resolve(target.generics(), arg.legacy_index)
+                .unwrap_or_else(|e| abort!(e.span(), e))
+        });
+        // The trait of the pair, and what it needs for the other parameter.
+        let (tr, other_tr) = match ident.to_string().as_str() {
+            "bimap" => (TraitTarget::BiMap(i1), TraitTarget::Map),
+            "union" => (TraitTarget::Union(i1), TraitTarget::Default),
+            _ => (TraitTarget::UnionMut(i1), TraitTarget::Default),
+        };
+        trait_impls.entry(i0).or_default().insert(tr);
+        trait_impls.entry(i1).or_default().insert(other_tr);
+    }
+    for (configured, attrs, gate) in &configured {
+        let mut impls = TokenStream::new();
+        for (param_index, impl_traits) in &trait_impls {
+            let impl_traits = TraitTarget::make_enough(impl_traits.clone());
+            for impl_trait in &impl_traits {
+                let ret = configured
+                    .emit_impl(&arg, &krate, impl_trait, *param_index, attrs)
+                    .unwrap_or_else(|e| {
+                        abort!(
+                            e.span(),
+                            format!(
+                                "Cannot implement {:?} for parameter {}: {}",
+                                &impl_trait, param_index, e
+                            )
                         )
-                    )
-                });
-            out.extend(ret);
+                    });
+                impls.extend(ret);
+            }
+        }
+        match gate {
+            Some(gate) => {
+                out.extend(gate_items(gate, impls).unwrap_or_else(|e| abort!(e.span(), e)))
+            }
+            None => out.extend(impls),
         }
     }
     out
@@ -690,8 +3108,14 @@ fn inner_target<T: ImplTarget + ToTokens>(target: &T, arg: Arguments) -> TokenSt
 
 fn inner(arg: Arguments, input: Item) -> TokenStream {
     match input {
-        Item::Enum(item_enum) => inner_target(&item_enum, arg),
-        Item::Struct(item_struct) => inner_target(&item_struct, arg),
+        Item::Enum(item_enum) if item_enum.variants.is_empty() => abort!(
+            item_enum.ident.span(),
+            "Enums without variants cannot be parametrized, since they hold no value \
+             of the parameter, which would then be unused (E0392)";
+            help = "`enum Never<T> {}` does not compile either; add a variant holding the parameter, such as `Never(PhantomData<T>)`"
+        ),
+        Item::Enum(item_enum) => inner_target(item_enum, arg),
+        Item::Struct(item_struct) => inner_target(item_struct, arg),
         _ => abort!(input.span(), "Bad item"),
     }
 }
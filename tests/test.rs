@@ -1,3 +1,7 @@
+// `test3` borrows explicitly to call the impls for references.
+#![allow(clippy::needless_borrow)]
+
+use core::ops::ControlFlow;
 use parametrized::*;
 
 #[parametrized(default, iter_mut, map)]
@@ -19,7 +23,7 @@ fn test1() {
     assert_eq!(s.param_into_iter().collect::<Vec<_>>(), vec![10, 10]);
 }
 
-#[parametrized(default = 1)]
+#[parametrized(default = 1, legacy_index)]
 struct Struct2<'a, K> {
     f1: K,
     f2: Option<(&'a K, isize)>,
@@ -33,9 +37,15 @@ fn test2() {
     };
     assert_eq!(s.param_len(), 2);
     assert_eq!(s.param_iter().collect::<Vec<_>>(), vec![&"hello", &"world"]);
+    assert_eq!(legacy_len(&s), 2);
+    assert_eq!(Struct2::<()>::PARAM_K, 1);
+}
+
+fn legacy_len<S: Parametrized<1>>(s: &S) -> usize {
+    s.param_len()
 }
 
-#[parametrized(default = 1, iter_mut = 1)]
+#[parametrized(default = 1, iter_mut = 1, legacy_index)]
 struct Struct3<'a, K>(&'a mut std::collections::BTreeMap<usize, K>);
 
 #[test]
@@ -54,7 +64,7 @@ fn test3() {
     );
 }
 
-#[parametrized(default = 1, iter_mut = 1)]
+#[parametrized(default = 1, iter_mut = 1, legacy_index)]
 struct Struct4<'a, K>(&'a mut Vec<[K; 3]>);
 
 #[test]
@@ -74,3 +84,975 @@ fn test4() {
         vec![&4, &6, &10, &14, &22, &26, &34, &38, &46]
     );
 }
+
+#[parametrized(default = SrcReg, map = [SrcReg, DestReg])]
+struct Struct5<'a, DestReg, SrcReg> {
+    name: &'a str,
+    dest: DestReg,
+    src: [SrcReg; 2],
+}
+
+#[test]
+fn test5() {
+    const DEST: usize = Struct5::<(), ()>::PARAM_DEST_REG;
+    const SRC: usize = Struct5::<(), ()>::PARAM_SRC_REG;
+    assert_eq!((DEST, SRC), (0, 1));
+    let s = Struct5 {
+        name: "mov",
+        dest: 1u8,
+        src: ["r2", "r3"],
+    };
+    assert_eq!(
+        Parametrized::<SRC>::param_iter(&s).collect::<Vec<_>>(),
+        vec![&"r2", &"r3"]
+    );
+    let s = ParametrizedMap::<DEST, _>::param_map(s, |d| d as usize + 10);
+    assert_eq!(s.dest, 11usize);
+    let s = ParametrizedMap::<SRC, _>::param_map(s, |r| r.len());
+    assert_eq!((s.name, s.src), ("mov", [2, 2]));
+}
+
+#[parametrized(default = Reg, no_param_consts)]
+struct Struct5Reg<Reg>(Vec<Reg>);
+
+impl<Reg> Struct5Reg<Reg> {
+    const PARAM_REG: &'static str = "reg";
+}
+
+#[test]
+fn test5_user_consts() {
+    assert_eq!(Struct5Reg::<u8>::PARAM_REG, "reg");
+    assert_eq!(Struct5Reg(vec![1u8, 2]).param_len(), 2);
+}
+
+#[parametrized(default = 0)]
+struct Struct6Inner<'a, K> {
+    f1: K,
+    f2: Option<(&'a K, isize)>,
+}
+
+#[parametrized(default)]
+struct Struct6<'a, K> {
+    inner: Struct6Inner<'a, K>,
+    more: Vec<Struct6Inner<'a, K>>,
+}
+
+#[test]
+fn test6() {
+    let s = Struct6 {
+        inner: Struct6Inner { f1: 1, f2: None },
+        more: vec![Struct6Inner {
+            f1: 2,
+            f2: Some((&3, -1)),
+        }],
+    };
+    assert_eq!(<Struct6<usize>>::MIN_LEN, 1);
+    assert_eq!(s.param_iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+}
+
+#[parametrized(default = 1)]
+struct Struct6Idx<K, const N: usize, V>(K, [V; N]);
+
+#[test]
+fn test6_type_index() {
+    let s = Struct6Idx("k", [1, 2]);
+    assert_eq!(Struct6Idx::<(), 0, ()>::PARAM_V, 1);
+    assert_eq!((s.0, s.param_len()), ("k", 2));
+    assert_eq!(s.param_iter().collect::<Vec<_>>(), vec![&1, &2]);
+}
+
+#[parametrized(default, iter_mut, map)]
+struct Struct7<K> {
+    key: K,
+    callback: Box<dyn Fn(u32) -> u32>,
+    raw: *const u8,
+    hook: fn(&str) -> usize,
+}
+
+#[test]
+fn test7() {
+    let mut s = Struct7 {
+        key: 1usize,
+        callback: Box::new(|x| x + 1),
+        raw: core::ptr::null(),
+        hook: str::len,
+    };
+    *s.param_iter_mut().next().unwrap() += 1;
+    let s = s.param_map(|k| k * 10);
+    assert_eq!(s.param_iter().collect::<Vec<_>>(), vec![&20]);
+    assert_eq!(
+        ((s.callback)(1), s.raw.is_null(), (s.hook)("ab")),
+        (2, true, 2)
+    );
+}
+
+#[parametrized(try_map)]
+#[derive(Debug)]
+enum Struct8<K> {
+    Leaf(K),
+    Node(Vec<Option<K>>, Box<(usize, K)>),
+}
+
+#[test]
+fn test8() {
+    let s = Struct8::Node(vec![Some("1"), None, Some("2")], Box::new((0, "3")));
+    let t = s.try_param_map(|v| v.parse::<u8>()).unwrap();
+    assert_eq!(t.param_into_iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+    let s = Struct8::Node(vec![Some("x"), Some("2")], Box::new((0, "y")));
+    let errs = s
+        .try_param_map_all(|v| v.parse::<u8>().map_err(|_| v))
+        .unwrap_err();
+    assert_eq!(errs, vec![(0, "x"), (2, "y")]);
+    let s = Struct8::Leaf(Some(1));
+    assert!(matches!(s.try_param_map_opt(|v| v), Some(Struct8::Leaf(1))));
+    let s: Struct8<Result<u8, ()>> = Struct8::Leaf(Err(()));
+    assert!(s.param_transpose().is_err());
+}
+
+#[test]
+fn test_split_fill() {
+    let s = Struct8::Node(vec![Some(1), None, Some(2)], Box::new((0, 1)));
+    let (shape, items) = s.param_split();
+    assert_eq!(items, vec![1, 2, 1]);
+    let t = shape.param_fill(items.iter().map(|i| i * 10)).unwrap();
+    assert_eq!(t.param_into_iter().collect::<Vec<_>>(), vec![10, 20, 10]);
+    let s = Struct8::Node(vec![Some("a"), None, Some("b")], Box::new((0, "a")));
+    let (shape, items) = s.param_split_dedup();
+    assert_eq!(items, vec!["a", "b"]);
+    assert_eq!(shape.param_into_iter().collect::<Vec<_>>(), vec![0, 1, 0]);
+    let (shape, _) = Struct8::Leaf(1).param_split();
+    assert_eq!(
+        shape.param_fill([1, 2, 3]).unwrap_err(),
+        FillError::TooMany {
+            expected: 1,
+            found: 3
+        }
+    );
+    let (shape, _) = Struct8::Leaf(1).param_split();
+    assert_eq!(
+        shape.param_fill(None::<u8>).unwrap_err(),
+        FillError::TooFew {
+            expected: 1,
+            found: 0
+        }
+    );
+}
+
+#[test]
+fn test_split_dedup_keyed() {
+    use std::collections::{BTreeMap, BTreeSet};
+    let set: BTreeSet<_> = [1, 2, 3].into_iter().collect();
+    let (shape, items) = set.clone().param_split_dedup();
+    assert_eq!(items, vec![1, 2, 3]);
+    assert_eq!(shape.len(), 3);
+    assert_eq!(shape.param_map(|i| items[i]), set);
+    let map: BTreeMap<_, _> = [(1, 'a'), (2, 'b')].into_iter().collect();
+    let (shape, keys) = ParametrizedIntoIter::<0>::param_split_dedup(map.clone());
+    assert_eq!(keys, vec![1, 2]);
+    assert_eq!(shape.values().collect::<String>(), "ab");
+    assert_eq!(ParametrizedMap::<0, _>::param_map(shape, |i| keys[i]), map);
+    let (shape, values) = ParametrizedIntoIter::<1>::param_split(map.clone());
+    assert_eq!(values, vec!['a', 'b']);
+    assert_eq!(ParametrizedMap::<1, _>::param_fill(shape, values), Ok(map));
+}
+
+#[parametrized(default, into_iter, map)]
+#[derive(Debug, PartialEq)]
+struct Struct8Ord<K: Ord + std::hash::Hash> {
+    first: K,
+    rest: Vec<K>,
+}
+
+#[test]
+fn test_split_fill_bounded() {
+    let s = Struct8Ord {
+        first: 3,
+        rest: vec![1, 2],
+    };
+    let (shape, items) = s.param_split();
+    assert_eq!(items, vec![3, 1, 2]);
+    let t = shape
+        .param_fill(items.iter().map(|i| i.to_string()))
+        .unwrap();
+    assert_eq!(t.first, "3");
+    assert_eq!(t.rest, vec!["1", "2"]);
+    let set: std::collections::BTreeSet<_> = [1, 2].into_iter().collect();
+    let (shape, items) = set.param_split();
+    assert_eq!(items, vec![1, 2]);
+    assert_eq!(
+        shape.param_fill(items).unwrap_err(),
+        FillError::TooMany {
+            expected: 1,
+            found: 2
+        }
+    );
+}
+
+#[parametrized(map_ref = K)]
+#[derive(Debug, PartialEq)]
+struct Struct9<L, K> {
+    name: L,
+    table: std::collections::HashMap<String, K>,
+    pair: (L, [K; 2]),
+    result: Result<K, L>,
+}
+
+#[test]
+fn test9() {
+    let s = Struct9 {
+        name: "s".to_string(),
+        table: [("a".to_string(), 1)].into_iter().collect(),
+        pair: ("p".to_string(), [2, 3]),
+        result: Ok(4),
+    };
+    let t = s.param_map_ref(|v| v * 10);
+    assert_eq!(t.param_len(), 4);
+    assert_eq!(t.table["a"], 10);
+    assert_eq!(t.pair, ("p".to_string(), [20, 30]));
+    assert_eq!(t.result, Ok(40));
+    let r: Struct9<String, &i32> = s.param_as_ref();
+    assert_eq!(r.name, "s");
+    assert_eq!(r.pair.1, [&2, &3]);
+}
+
+#[parametrized(zip)]
+#[derive(Debug, PartialEq)]
+struct Struct10<K> {
+    name: &'static str,
+    slots: Vec<Option<K>>,
+    pair: (usize, K),
+    table: std::collections::BTreeMap<u8, K>,
+}
+
+#[test]
+fn test10() {
+    let a = Struct10 {
+        name: "a",
+        slots: vec![Some(1), None],
+        pair: (0, 2),
+        table: [(0, 3)].into_iter().collect(),
+    };
+    let b = Struct10 {
+        name: "b",
+        slots: vec![Some("x"), None],
+        pair: (1, "y"),
+        table: [(0, "z")].into_iter().collect(),
+    };
+    let z = a.param_zip(b).unwrap();
+    assert_eq!(z.name, "a");
+    assert_eq!(z.pair, (0, (2, "y")));
+    assert_eq!(
+        z.param_into_iter().collect::<Vec<_>>(),
+        vec![(1, "x"), (2, "y"), (3, "z")]
+    );
+    let a = Struct10 {
+        name: "a",
+        slots: vec![Some(1), None],
+        pair: (0, 2),
+        table: Default::default(),
+    };
+    let b = Struct10 {
+        name: "b",
+        slots: vec![None, None],
+        pair: (1, 2),
+        table: Default::default(),
+    };
+    assert_eq!(
+        a.param_zip_with(b, |x, y| x + y),
+        Err(ShapeMismatch::Variant {
+            expected: "Some",
+            found: "None"
+        })
+    );
+}
+
+#[parametrized(unzip = K)]
+struct Struct11<N, K> {
+    name: N,
+    slots: Vec<Option<K>>,
+    pair: (String, K),
+    array: [Box<K>; 2],
+    result: Result<K, String>,
+    table: std::collections::HashMap<u8, K>,
+}
+
+#[test]
+fn test11() {
+    let s = Struct11 {
+        name: "s",
+        slots: vec![Some((1, 'a')), None],
+        pair: ("p".to_string(), (2, 'b')),
+        array: [Box::new((3, 'c')), Box::new((4, 'd'))],
+        result: Ok((5, 'e')),
+        table: [(0, (6, 'f'))].into_iter().collect(),
+    };
+    let (left, right) = s.param_unzip();
+    assert_eq!(left.name, "s");
+    assert_eq!(right.pair.0, "p");
+    assert_eq!(left.slots, vec![Some(1), None]);
+    assert_eq!(right.result, Ok('e'));
+    assert_eq!(
+        left.param_iter().collect::<Vec<_>>(),
+        vec![&1, &2, &3, &4, &5, &6]
+    );
+    assert_eq!(
+        right.param_iter().collect::<Vec<_>>(),
+        vec![&'a', &'b', &'c', &'d', &'e', &'f']
+    );
+}
+
+#[parametrized(retain, filter_map)]
+struct Struct12<T> {
+    name: &'static str,
+    list: Vec<T>,
+    maybe: Option<T>,
+    nested: Vec<Option<T>>,
+    table: std::collections::BTreeMap<u8, T>,
+}
+
+#[test]
+fn test12() {
+    let s = || Struct12 {
+        name: "s",
+        list: vec![1, 2, 3, 4],
+        maybe: Some(5),
+        nested: vec![Some(6), None, Some(7)],
+        table: [(0, 8), (1, 9)].into_iter().collect(),
+    };
+    let mut retained = s();
+    retained.param_retain(|v| v % 2 == 0);
+    assert_eq!(retained.name, "s");
+    assert_eq!(retained.list, vec![2, 4]);
+    assert_eq!(retained.maybe, None);
+    assert_eq!(retained.nested, vec![Some(6), None, None]);
+    assert_eq!(retained.table.into_iter().collect::<Vec<_>>(), vec![(0, 8)]);
+    let mapped = s().param_filter_map(|v| (v % 2 == 1).then(|| v.to_string()));
+    assert_eq!(mapped.list, vec!["1".to_string(), "3".to_string()]);
+    assert_eq!(mapped.maybe, Some("5".to_string()));
+    assert_eq!(mapped.nested, vec![None, None, Some("7".to_string())]);
+    assert_eq!(
+        mapped.table.into_iter().collect::<Vec<_>>(),
+        vec![(1, "9".to_string())]
+    );
+}
+
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct Name(String);
+
+#[parametrized(update)]
+#[derive(Debug, PartialEq)]
+struct Struct13<T: Ord + std::hash::Hash> {
+    single: T,
+    list: Vec<(u8, T)>,
+    set: std::collections::BTreeSet<T>,
+    keys: std::collections::HashMap<T, u8>,
+    nested: Option<Vec<Box<T>>>,
+}
+
+#[test]
+fn test13() {
+    let mut s = Struct13 {
+        single: 0,
+        list: vec![(0, 1), (1, 2)],
+        set: [3, 4, 5].into_iter().collect(),
+        keys: [(6, 0)].into_iter().collect(),
+        nested: Some(vec![Box::new(7)]),
+    };
+    s.param_update(|v| v * 10);
+    assert_eq!(
+        s,
+        Struct13 {
+            single: 0,
+            list: vec![(0, 10), (1, 20)],
+            set: [30, 40, 50].into_iter().collect(),
+            keys: [(60, 0)].into_iter().collect(),
+            nested: Some(vec![Box::new(70)]),
+        }
+    );
+    let mut s = Struct13 {
+        single: Name("a".to_string()),
+        list: vec![],
+        set: [Name("b".to_string()), Name("c".to_string())]
+            .into_iter()
+            .collect(),
+        keys: Default::default(),
+        nested: None,
+    };
+    s.param_update(|Name(n)| Name(n.replace(['b', 'c'], "x")));
+    assert_eq!(s.single, Name("a".to_string()));
+    assert_eq!(
+        s.set.into_iter().collect::<Vec<_>>(),
+        vec![Name("x".to_string())]
+    );
+}
+
+#[test]
+#[should_panic(expected = "odd")]
+fn test13_panic() {
+    let mut s = Struct13 {
+        single: 0,
+        list: vec![(0, 1)],
+        set: Default::default(),
+        keys: Default::default(),
+        nested: Some(vec![Box::new(3)]),
+    };
+    s.param_update(|v| if v % 2 == 1 { panic!("odd") } else { v });
+}
+
+#[test]
+fn test_map_accum() {
+    let s = Struct8::Node(vec![Some("x"), None, Some("y")], Box::new((0, "x")));
+    let (names, s) = s.param_map_accum(Vec::new(), |mut names: Vec<&str>, name| {
+        let index = names.iter().position(|n| *n == name).unwrap_or_else(|| {
+            names.push(name);
+            names.len() - 1
+        });
+        (names, index)
+    });
+    assert_eq!(names, vec!["x", "y"]);
+    assert_eq!(s.param_into_iter().collect::<Vec<_>>(), vec![0, 1, 0]);
+    let t: std::collections::BTreeSet<_> = [3, 1, 2].into_iter().collect();
+    let t = t.param_map_indexed(|i, v| (i, v));
+    assert_eq!(
+        t.into_iter().collect::<Vec<_>>(),
+        vec![(0, 1), (1, 2), (2, 3)]
+    );
+}
+
+#[parametrized(bimap = [A, B])]
+#[derive(Debug, PartialEq)]
+struct Struct14<A, B> {
+    pair: (A, B),
+    table: std::collections::BTreeMap<u8, Vec<(B, A)>>,
+    result: Option<Result<A, B>>,
+}
+
+#[test]
+fn test14() {
+    let s = Struct14 {
+        pair: (1u8, 'a'),
+        table: [(0, vec![('b', 2), ('c', 3)])].into_iter().collect(),
+        result: Some(Err('d')),
+    };
+    let log = std::cell::RefCell::new(Vec::new());
+    let s = s.param_bimap(
+        |a| {
+            log.borrow_mut().push(a.to_string());
+            a * 10
+        },
+        |b| {
+            log.borrow_mut().push(b.to_string());
+            b.to_ascii_uppercase()
+        },
+    );
+    assert_eq!(
+        s,
+        Struct14 {
+            pair: (10, 'A'),
+            table: [(0, vec![('B', 20), ('C', 30)])].into_iter().collect(),
+            result: Some(Err('D')),
+        }
+    );
+    assert_eq!(log.into_inner(), vec!["1", "a", "b", "2", "c", "3", "d"]);
+    let m: std::collections::HashMap<_, _> = [(1u8, 'x')].into_iter().collect();
+    let m = m.param_bimap(|a| a + 1, |b| b as u32);
+    assert_eq!(m, [(2u8, 'x' as u32)].into_iter().collect());
+    assert_eq!(
+        ParametrizedBiMap::<1, 2, _, _>::param_bimap(("s", 1, 'c'), |i| i + 1, |c| c == 'c'),
+        ("s", 2, true)
+    );
+}
+
+#[parametrized(union = [Src, Dst])]
+struct Struct15<Src, Dst> {
+    name: &'static str,
+    moves: Vec<(Dst, Src)>,
+    table: std::collections::BTreeMap<Dst, Src>,
+    result: Result<Dst, Src>,
+}
+
+#[parametrized(union = [Src, Dst])]
+struct Struct15Map<Src, Dst> {
+    m: std::collections::BTreeMap<Src, (Dst, Src)>,
+}
+
+#[parametrized(union_mut = [0, 1])]
+#[derive(Debug, PartialEq)]
+struct Struct15Mut<Src, Dst> {
+    moves: Vec<(Dst, Src)>,
+    result: Result<Dst, Src>,
+    nested: Option<Box<[Src; 1]>>,
+}
+
+#[test]
+fn test15() {
+    let s = Struct15 {
+        name: "s",
+        moves: vec![(0, 1), (2, 3)],
+        table: [(4, 5), (6, 7)].into_iter().collect(),
+        result: Err(8),
+    };
+    assert_eq!(s.name, "s");
+    assert_eq!(
+        s.param_iter_union().copied().collect::<Vec<_>>(),
+        (0..9).collect::<Vec<_>>()
+    );
+    let s = Struct15 {
+        name: "t",
+        moves: vec![("r0", 1)],
+        table: Default::default(),
+        result: Ok("r1"),
+    };
+    assert_eq!(
+        s.param_iter_either().collect::<Vec<_>>(),
+        vec![Either::Right(&"r0"), Either::Left(&1), Either::Right(&"r1")]
+    );
+    let s = Struct15Map {
+        m: [(1, (2, 3)), (4, (5, 6))].into_iter().collect(),
+    };
+    assert_eq!(
+        s.param_iter_union().copied().collect::<Vec<_>>(),
+        vec![1, 2, 3, 4, 5, 6]
+    );
+    let mut s = Struct15Mut {
+        moves: vec![(0, 1)],
+        result: Ok(2),
+        nested: Some(Box::new([3])),
+    };
+    s.param_iter_mut_union().for_each(|i| *i *= 10);
+    assert_eq!(
+        s,
+        Struct15Mut {
+            moves: vec![(0, 10)],
+            result: Ok(20),
+            nested: Some(Box::new([30])),
+        }
+    );
+    let m: std::collections::HashMap<_, _> = [('a', 1)].into_iter().collect();
+    assert_eq!(
+        m.param_iter_either().collect::<Vec<_>>(),
+        vec![Either::Left(&'a'), Either::Right(&1)]
+    );
+}
+
+#[parametrized(path)]
+struct Struct16<'a, T> {
+    dest: T,
+    srcs: Vec<Option<T>>,
+    table: std::collections::BTreeMap<&'static str, (u8, T)>,
+    borrowed: &'a T,
+    name: &'static str,
+}
+
+#[test]
+fn test16() {
+    let four = 4;
+    let s = Struct16 {
+        dest: 0,
+        srcs: vec![Some(1), None, Some(2)],
+        table: [("a", (0, 3))].into_iter().collect(),
+        borrowed: &four,
+        name: "s",
+    };
+    assert_eq!(s.name, "s");
+    let paths = s
+        .param_iter_with_path()
+        .map(|(path, v)| format!("{path} = {v}"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        paths,
+        vec![
+            "dest = 0",
+            "srcs[0] = 1",
+            "srcs[2] = 2",
+            "table[0].1 = 3",
+            "borrowed = 4",
+        ]
+    );
+    let path = ParamPath::from(vec![PathSegment::Field("srcs"), PathSegment::Index(2)]);
+    assert_eq!(s.param_get_path(&path), Some(&2));
+    let path = ParamPath::from(vec![PathSegment::Field("srcs"), PathSegment::Index(1)]);
+    assert_eq!(s.param_get_path(&path), None);
+    let path = ParamPath::from(vec![PathSegment::Field("dest"), PathSegment::Index(0)]);
+    assert_eq!(s.param_get_path(&path), None);
+    assert_eq!(s.param_get_path(&ParamPath::new()), None);
+    let path = [
+        PathSegment::Field("table"),
+        PathSegment::Entry(0),
+        PathSegment::TupleField(1),
+    ];
+    assert_eq!(s.param_get_path(&path.into_iter().collect()), Some(&3));
+    let mut v = vec![1, 2, 3];
+    v.param_iter_mut_with_path()
+        .filter(|(path, _)| path.segments() != [PathSegment::Index(1)])
+        .for_each(|(_, v)| *v *= 10);
+    assert_eq!(v, vec![10, 2, 30]);
+}
+
+#[parametrized(default, iter_mut)]
+#[derive(Debug, PartialEq)]
+struct Struct17<T> {
+    dest: T,
+    srcs: Vec<T>,
+    pair: [T; 2],
+    nested: Vec<Vec<T>>,
+    flags: Vec<(Option<T>, u8)>,
+    last: Option<T>,
+}
+
+#[test]
+fn test17() {
+    let mut s = Struct17 {
+        dest: 0,
+        srcs: vec![1, 2],
+        pair: [3, 4],
+        nested: vec![vec![], vec![5, 6], vec![7]],
+        flags: vec![(None, 0), (Some(8), 1)],
+        last: Some(9),
+    };
+    let len = s.param_len();
+    assert_eq!(len, 10);
+    for n in 0..len + 2 {
+        assert_eq!(s.param_get(n), s.param_iter().nth(n));
+        assert_eq!(s.param_get(n), (n < len).then_some(&n));
+    }
+    let mut iter = s.param_iter();
+    assert_eq!(iter.nth(6), Some(&6));
+    assert_eq!(iter.next(), Some(&7));
+    assert_eq!(iter.nth(1), Some(&9));
+    assert_eq!(iter.next(), None);
+    *s.param_get_mut(6).unwrap() *= 10;
+    *s.param_get_mut(8).unwrap() *= 10;
+    assert_eq!(s.param_get_mut(10), None);
+    assert_eq!(s.nested, vec![vec![], vec![5, 60], vec![7]]);
+    assert_eq!(s.flags, vec![(None, 0), (Some(80), 1)]);
+    assert_eq!(s.param_iter_mut().nth(9), Some(&mut 9));
+}
+
+#[parametrized(diff)]
+#[derive(Debug, PartialEq)]
+struct Struct18<'a, T> {
+    name: &'a str,
+    dest: T,
+    srcs: Vec<Option<T>>,
+    table: std::collections::BTreeMap<&'a str, (u8, T)>,
+    pair: (T, u8),
+}
+
+#[test]
+fn test18() {
+    let a = Struct18 {
+        name: "a",
+        dest: 0,
+        srcs: vec![Some(1), None, Some(2)],
+        table: std::collections::BTreeMap::from([("x", (0, 3))]),
+        pair: (4, 0),
+    };
+    let b = Struct18 {
+        name: "b",
+        dest: 0,
+        srcs: vec![Some(1), Some(5)],
+        table: std::collections::BTreeMap::from([("x", (1, 6))]),
+        pair: (4, 1),
+    };
+    assert_eq!(a.param_pair(&b).len(), 6);
+    assert!(a.param_diff(&a).is_empty());
+    let diffs = a.param_diff(&b);
+    assert_eq!(
+        diffs
+            .iter()
+            .map(|diff| diff.path().to_string())
+            .collect::<Vec<_>>(),
+        vec!["srcs", "srcs[1]", "table[\"x\"].1"]
+    );
+    assert_eq!(
+        diffs[0],
+        ParamDiff::Shape {
+            path: ParamPath::from(vec![PathSegment::Field("srcs")]),
+            mismatch: ShapeMismatch::Length {
+                expected: 3,
+                found: 2
+            },
+        }
+    );
+    assert_eq!(
+        format!("{:?}", diffs),
+        "[srcs: expected length 3, found 2, \
+         srcs[1]: expected variant `None`, found `Some`, \
+         table[\"x\"].1: 3 != 6]"
+    );
+    let set: std::collections::HashSet<_> = (0..17).collect();
+    assert!(set.param_diff(&set.clone()).is_empty());
+    let other = (1..18).collect();
+    assert_eq!(
+        format!("{:?}", set.param_diff(&other)),
+        "[[0]: expected an entry, found none, [17]: expected no entry, found one]"
+    );
+    let m = std::collections::BTreeMap::from([(1, 'a'), (2, 'b')]);
+    let n = std::collections::BTreeMap::from([(2, 'b'), (3, 'c')]);
+    let diffs = ParametrizedDiff::<1>::param_diff(&m, &n);
+    assert_eq!(
+        format!("{:?}", diffs),
+        "[[1]: expected an entry, found none, [3]: expected no entry, found one]"
+    );
+    assert_eq!(
+        ParametrizedPath::<1>::param_get_path(&m, diffs[0].path()),
+        None
+    );
+    let path = ParamPath::from(vec![PathSegment::Entry(0)]);
+    assert_eq!(ParametrizedPath::<1>::param_get_path(&m, &path), Some(&'a'));
+    assert_eq!(ParametrizedDiff::<1>::param_pair(&m, &n).len(), 3);
+    assert!(ParametrizedDiff::<0>::param_diff(&m, &m.clone()).is_empty());
+}
+
+#[parametrized(default)]
+struct Struct19<T> {
+    nested: Vec<Vec<T>>,
+    pairs: Vec<[T; 2]>,
+}
+
+#[test]
+fn test19() {
+    let mut nested = vec![Vec::new(); 1_000_000];
+    nested.push(vec![1, 2]);
+    let s = Struct19 {
+        nested,
+        pairs: vec![[3, 4], [5, 6]],
+    };
+    assert_eq!(s.param_iter().next(), Some(&1));
+    assert_eq!(s.param_iter().count(), 6);
+    assert_eq!(s.param_iter().sum::<i32>(), 21);
+    assert_eq!(s.param_iter().nth(3), Some(&4));
+    let mut iter = s.param_iter();
+    #[cfg(not(feature = "boxed"))]
+    assert_eq!(iter.size_hint(), (4, None));
+    // The values are collected beforehand where boxed, so they are counted.
+    #[cfg(feature = "boxed")]
+    assert_eq!(iter.size_hint(), (6, Some(6)));
+    iter.nth(2);
+    assert_eq!(iter.size_hint(), (3, Some(3)));
+    let pairs = Struct19 {
+        nested: vec![],
+        pairs: vec![[1, 2], [3, 4], [5, 6]],
+    };
+    let mut iter = pairs.param_iter();
+    assert_eq!(iter.size_hint(), (6, Some(6)));
+    assert_eq!(iter.nth(3), Some(&4));
+    assert_eq!(iter.size_hint(), (2, Some(2)));
+    assert_eq!(
+        pairs.param_iter().rev().collect::<Vec<_>>(),
+        vec![&6, &5, &4, &3, &2, &1]
+    );
+    let mut iter = pairs.param_iter();
+    assert_eq!(iter.next_back(), Some(&6));
+    assert_eq!(iter.nth(6), None);
+    let mut iter = pairs.param_iter();
+    assert_eq!(iter.next_back(), Some(&6));
+    assert_eq!(iter.nth(4), Some(&5));
+    assert_eq!(iter.next(), None);
+    let mut iter = pairs.param_iter();
+    assert_eq!(iter.nth_back(1), Some(&5));
+    assert_eq!(iter.nth(3), Some(&4));
+    assert_eq!(iter.next(), None);
+}
+
+#[parametrized(default)]
+struct Struct20<T> {
+    dest: T,
+    srcs: Vec<T>,
+    pair: (T, T),
+    pairs: Vec<(T, T)>,
+}
+
+#[parametrized(default, iter_mut, into_iter)]
+#[derive(Clone)]
+struct Struct21<T> {
+    dest: T,
+    srcs: Vec<T>,
+    arrays: Vec<[T; 2]>,
+    boxes: Vec<Box<T>>,
+}
+
+#[parametrized(default)]
+enum Enum20<T> {
+    BinaryOp { dest: T, srcs: [T; 2] },
+    Call(String, Vec<(T, T)>),
+    Nop,
+}
+
+#[parametrized(default, iter_mut, into_iter)]
+#[derive(Clone)]
+enum Enum21<T> {
+    Load(T, Vec<T>),
+    Store { srcs: Vec<[T; 2]> },
+}
+
+fn last_param<'a, S>(s: &'a S) -> Option<&'a S::Item>
+where
+    S: Parametrized<0, Iter<'a>: DoubleEndedIterator>,
+{
+    s.param_iter().next_back()
+}
+
+#[test]
+fn test20() {
+    let s = Struct20 {
+        dest: 0,
+        srcs: vec![1, 2],
+        pair: (3, 4),
+        pairs: vec![(5, 6), (7, 8)],
+    };
+    assert_eq!(last_param(&s), Some(&8));
+    assert_eq!(
+        s.param_iter().rev().copied().collect::<Vec<_>>(),
+        vec![8, 7, 6, 5, 4, 3, 2, 1, 0]
+    );
+    let mut iter = s.param_iter();
+    assert_eq!(iter.len(), 9);
+    iter.next();
+    iter.next_back();
+    assert_eq!(iter.len(), 7);
+    let mut ahead = iter.clone();
+    assert_eq!(ahead.nth(5), Some(&6));
+    assert_eq!(ahead.len(), 1);
+    assert_eq!(iter.next(), Some(&1));
+    let mut iter = iter.fuse();
+    assert_eq!(iter.by_ref().count(), 6);
+    assert_eq!(iter.next(), None);
+
+    let mut s = Struct21 {
+        dest: 0,
+        srcs: vec![1, 2],
+        arrays: vec![[5, 6], [7, 8]],
+        boxes: vec![Box::new(3), Box::new(4)],
+    };
+    assert_eq!(s.param_iter().len(), 9);
+    s.param_iter_mut().rev().take(2).for_each(|v| *v *= 10);
+    assert_eq!(s.param_iter_mut().len(), 9);
+    let mut into_iter = s.clone().param_into_iter();
+    assert_eq!(into_iter.len(), 9);
+    assert_eq!(into_iter.next_back(), Some(40));
+    assert_eq!(into_iter.next_back(), Some(30));
+    assert_eq!(into_iter.next(), Some(0));
+    assert_eq!(into_iter.len(), 6);
+    assert_eq!(into_iter.nth(3), Some(6));
+    assert_eq!(into_iter.len(), 2);
+
+    let e = Enum20::BinaryOp {
+        dest: 0,
+        srcs: [1, 2],
+    };
+    assert_eq!(last_param(&e), Some(&2));
+    let e = Enum20::Call("f".to_string(), vec![(1, 2), (3, 4)]);
+    assert_eq!(
+        e.param_iter().rev().copied().collect::<Vec<_>>(),
+        vec![4, 3, 2, 1]
+    );
+    let mut iter = e.param_iter();
+    assert_eq!(iter.len(), 4);
+    iter.next_back();
+    let mut ahead = iter.clone();
+    assert_eq!(ahead.nth(1), Some(&2));
+    assert_eq!(ahead.len(), 1);
+    assert_eq!(iter.len(), 3);
+    let mut iter = iter.fuse();
+    assert_eq!(iter.by_ref().count(), 3);
+    assert_eq!(iter.next(), None);
+    assert_eq!(Enum20::<u8>::Nop.param_iter().len(), 0);
+
+    let mut e = Enum21::Store {
+        srcs: vec![[1, 2], [3, 4]],
+    };
+    e.param_iter_mut().rev().take(1).for_each(|v| *v *= 10);
+    assert_eq!(e.param_iter_mut().len(), 4);
+    let mut into_iter = e.clone().param_into_iter();
+    assert_eq!(into_iter.len(), 4);
+    assert_eq!(into_iter.next_back(), Some(40));
+    assert_eq!(into_iter.clone().collect::<Vec<_>>(), vec![1, 2, 3]);
+    let e = Enum21::Load(0, vec![1, 2]);
+    assert_eq!(e.param_into_iter().rev().collect::<Vec<_>>(), vec![2, 1, 0]);
+}
+
+#[parametrized(default, iter_mut, into_iter, boxed)]
+#[derive(Debug, PartialEq)]
+enum Enum22<T> {
+    BinaryOp { dest: T, srcs: [T; 2] },
+    Call(String, Vec<Vec<T>>),
+    Nop,
+}
+
+#[test]
+fn test22() {
+    let mut e = Enum22::Call("f".to_string(), vec![vec![1], vec![], vec![2, 3]]);
+    let iter: std::vec::IntoIter<&u8> = e.param_iter();
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.clone().rev().collect::<Vec<_>>(), vec![&3, &2, &1]);
+    assert_eq!(iter.collect::<Vec<_>>(), vec![&1, &2, &3]);
+    assert_eq!(e.param_get(1), Some(&2));
+    e.param_iter_mut().rev().take(1).for_each(|v| *v += 1);
+    assert_eq!(e.param_iter_mut().len(), 3);
+    e.param_iter_mut().for_each(|v| *v += 1);
+    assert_eq!(
+        e,
+        Enum22::Call("f".to_string(), vec![vec![2], vec![], vec![3, 5]])
+    );
+    let e = Enum22::BinaryOp {
+        dest: 0u8,
+        srcs: [1, 2],
+    };
+    assert_eq!(e.param_into_iter().collect::<Vec<_>>(), vec![0, 1, 2]);
+    assert_eq!(Enum22::<u8>::Nop.param_iter().count(), 0);
+}
+
+#[parametrized(default, iter_mut, into_iter)]
+#[derive(Clone, Debug, PartialEq)]
+struct Struct23<T> {
+    dest: T,
+    blocks: Vec<Vec<T>>,
+    spill: Option<Box<T>>,
+    phis: std::collections::BTreeMap<u8, [T; 2]>,
+}
+
+#[test]
+fn test23() {
+    let mut s = Struct23 {
+        dest: 0,
+        blocks: vec![vec![1, 2], vec![], vec![3]],
+        spill: Some(Box::new(4)),
+        phis: [(1, [5, 6]), (0, [7, 8])].into_iter().collect(),
+    };
+    let mut visited = Vec::new();
+    s.param_for_each(|v| visited.push(v));
+    assert_eq!(visited, s.param_iter().collect::<Vec<_>>());
+    assert_eq!(visited, vec![&0, &1, &2, &3, &4, &7, &8, &5, &6]);
+    let mut count = 0;
+    let found = s.param_try_for_each(|v| {
+        count += 1;
+        if *v > 2 {
+            ControlFlow::Break(*v)
+        } else {
+            ControlFlow::Continue(())
+        }
+    });
+    assert_eq!(found, ControlFlow::Break(3));
+    assert_eq!(count, 4);
+    assert_eq!(
+        s.param_try_for_each(|_| ControlFlow::<()>::Continue(())),
+        ControlFlow::Continue(())
+    );
+    s.param_for_each_mut(|v| *v *= 10);
+    assert_eq!(s.blocks, vec![vec![10, 20], vec![], vec![30]]);
+    assert_eq!(s.spill, Some(Box::new(40)));
+    let order = s.clone().param_fold(Vec::new(), |mut acc, v| {
+        acc.push(v);
+        acc
+    });
+    assert_eq!(order, s.param_into_iter().collect::<Vec<_>>());
+
+    let mut e = Enum22::Call("f".to_string(), vec![vec![1], vec![], vec![2, 3]]);
+    let mut uses = Vec::new();
+    e.param_for_each(|v| uses.push(*v));
+    assert_eq!(uses, vec![1, 2, 3]);
+    e.param_for_each_mut(|v| *v += 1);
+    assert_eq!(e.param_fold(0, |acc, v| acc * 10 + v), 234);
+}
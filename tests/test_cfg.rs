@@ -0,0 +1,124 @@
+use parametrized::*;
+
+// `cfg(any())` never holds, while `cfg(test)` always does here.
+
+#[parametrized(default, iter_mut, map)]
+struct Operands<T> {
+    first: T,
+    #[cfg(any())]
+    second: T,
+    #[cfg(test)]
+    #[cfg(not(any()))]
+    rest: Vec<T>,
+}
+
+#[test]
+fn test_cfg_fields() {
+    let mut ops = Operands {
+        first: 1,
+        rest: vec![2, 3],
+    };
+    assert_eq!(<Operands<i32>>::MIN_LEN, 1);
+    assert_eq!(<Operands<i32>>::MAX_LEN, None);
+    assert_eq!(ops.param_len(), 3);
+    ops.param_iter_mut().for_each(|v| *v += 1);
+    let ops = ops.param_map(|v| v * 2);
+    assert_eq!(ops.param_into_iter().collect::<Vec<_>>(), vec![4, 6, 8]);
+}
+
+#[parametrized(default, path)]
+struct PathOperands<T> {
+    first: T,
+    #[cfg(any())]
+    second: T,
+    #[cfg(test)]
+    rest: Vec<T>,
+}
+
+#[test]
+fn test_cfg_paths() {
+    let ops = PathOperands {
+        first: 1,
+        rest: vec![2, 3],
+    };
+    assert_eq!(
+        ops.param_paths()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec!["first", "rest[0]", "rest[1]"]
+    );
+    let path = ParamPath::from(vec![PathSegment::Field("rest"), PathSegment::Index(1)]);
+    assert_eq!(ops.param_get_path(&path), Some(&3));
+}
+
+#[parametrized(default, iter_mut, zip, unzip)]
+enum Instruction<T> {
+    Nop,
+    Mov(T, T),
+    #[cfg(any())]
+    Ext(T, T, T, T),
+    #[cfg(test)]
+    Jump {
+        target: T,
+        #[cfg(any())]
+        hint: T,
+    },
+}
+
+#[test]
+fn test_cfg_variants() {
+    assert_eq!(<Instruction<u8>>::MIN_LEN, 0);
+    assert_eq!(<Instruction<u8>>::MAX_LEN, Some(2));
+    assert_eq!(Instruction::<u8>::Nop.param_len(), 0);
+    let mut i = Instruction::Mov(1, 2);
+    i.param_iter_mut().for_each(|v| *v *= 3);
+    assert_eq!(i.param_iter().collect::<Vec<_>>(), vec![&3, &6]);
+    let i = Instruction::Jump { target: 7 }.param_map(|v| v + 1);
+    assert_eq!(i.param_into_iter().collect::<Vec<_>>(), vec![8]);
+    let i = Instruction::Jump { target: 1 }.param_zip(Instruction::Jump { target: "a" });
+    assert!(matches!(i, Ok(Instruction::Jump { target: (1, "a") })));
+    assert!(Instruction::Mov(1, 2)
+        .param_zip(Instruction::<u8>::Nop)
+        .is_err());
+    let (i, j) = Instruction::Jump { target: (1, 'a') }.param_unzip();
+    assert!(matches!(
+        (i, j),
+        (
+            Instruction::Jump { target: 1 },
+            Instruction::Jump { target: 'a' }
+        )
+    ));
+}
+
+#[parametrized(default, iter_mut, into_iter, map)]
+struct Triple<T>(T, #[cfg(any())] T, #[cfg(test)] Vec<T>, T);
+
+#[parametrized(default, iter_mut, into_iter, map)]
+enum Operand<T> {
+    Reg(T, #[cfg(any())] T),
+    #[cfg(any())]
+    Mem(T),
+    Pair(#[cfg(test)] T, T),
+}
+
+fn is_send<I: Send>(_: &I) {}
+
+#[test]
+fn test_cfg_unnamed_fields() {
+    let mut t = Triple(1, vec![2, 3], 4);
+    t.param_iter_mut().for_each(|v| *v *= 10);
+    assert_eq!(t.param_iter().collect::<Vec<_>>(), vec![&10, &20, &30, &40]);
+    let t = t.param_map(|v| v + 1);
+    assert_eq!((t.0, t.2), (11, 41));
+    assert_eq!(
+        t.param_into_iter().collect::<Vec<_>>(),
+        vec![11, 21, 31, 41]
+    );
+    let o = Operand::Pair(1, 2);
+    assert_eq!(o.param_len(), 2);
+    assert_eq!(o.param_iter().rev().collect::<Vec<_>>(), vec![&2, &1]);
+    is_send(&o.param_iter());
+    let o = Operand::Reg(3).param_map(|v| v * 2);
+    assert_eq!(o.param_into_iter().collect::<Vec<_>>(), vec![6]);
+}
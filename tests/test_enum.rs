@@ -25,3 +25,89 @@ fn test1() {
     };
     assert_eq!(e3.param_len(), 1);
 }
+
+#[parametrized(default = [T, U], iter_mut, map = [T, U])]
+struct Marker<T, U> {
+    value: T,
+    _marker: core::marker::PhantomData<U>,
+}
+
+#[test]
+fn test_phantom() {
+    assert_eq!(<Marker<u8, u8> as Parametrized<1>>::MAX_LEN, Some(0));
+    let m = Marker::<u8, &str> {
+        value: 1,
+        _marker: core::marker::PhantomData,
+    };
+    assert_eq!(Parametrized::<1>::param_len(&m), 0);
+    let m: Marker<u8, usize> = ParametrizedMap::<1, usize>::param_map(m, |s| s.len());
+    assert_eq!(
+        Parametrized::<0>::param_iter(&m).collect::<Vec<_>>(),
+        vec![&1]
+    );
+}
+
+#[parametrized(default, iter_mut, into_iter, named_iter)]
+#[derive(Clone, Debug, PartialEq)]
+pub enum Instruction<Operand> {
+    BinaryOp { dest: Operand, srcs: [Operand; 2] },
+    Call(String, Vec<Operand>),
+    Nop,
+}
+
+/// Holds the iterator by its name.
+struct Uses<'a> {
+    iter: InstructionParamIter<'a, u32>,
+}
+
+#[parametrized(default = [Reg, Imm], iter_mut = Reg, named_iter)]
+struct Operands<'a, Reg, Imm> {
+    name: &'a str,
+    regs: Vec<Reg>,
+    imm: Option<Imm>,
+}
+
+#[test]
+fn test_named_iter() {
+    let mut inst = Instruction::BinaryOp {
+        dest: 0u32,
+        srcs: [1, 2],
+    };
+    {
+        let uses = Uses {
+            iter: inst.param_iter(),
+        };
+        assert_eq!(uses.iter.collect::<Vec<_>>(), vec![&0, &1, &2]);
+    }
+    let iter: InstructionParamIterMut<'_, u32> = inst.param_iter_mut();
+    iter.for_each(|r| *r += 10);
+    let iter = inst.param_iter();
+    // The named types wrap the collected values in structs where boxed.
+    #[cfg(not(feature = "boxed"))]
+    assert!(matches!(iter, InstructionParamIter::BinaryOp(_)));
+    assert_eq!(iter.len(), 3);
+    assert_eq!(iter.rev().collect::<Vec<_>>(), vec![&12, &11, &10]);
+    let call = Instruction::Call("f".to_string(), vec![3u32, 4]);
+    let iter = call.param_iter();
+    assert_eq!(iter.clone().nth(1), Some(&4));
+    assert_eq!(iter.len(), 2);
+    let iter: InstructionParamIntoIter<u32> = inst.param_into_iter();
+    assert_eq!(iter.collect::<Vec<_>>(), vec![10, 11, 12]);
+    assert_eq!(Instruction::<u32>::Nop.param_iter().next(), None);
+
+    let mut ops = Operands {
+        name: "add",
+        regs: vec![1u8, 2],
+        imm: Some(-1i64),
+    };
+    assert_eq!(ops.name, "add");
+    {
+        let regs: OperandsRegIter<'_, '_, u8, i64> = Parametrized::<0>::param_iter(&ops);
+        assert_eq!(regs.collect::<Vec<_>>(), vec![&1, &2]);
+        let imms: OperandsImmIter<'_, '_, u8, i64> = Parametrized::<1>::param_iter(&ops);
+        assert_eq!(imms.collect::<Vec<_>>(), vec![&-1]);
+    }
+    let regs: OperandsRegIterMut<'_, '_, u8, i64> = ops.param_iter_mut();
+    regs.for_each(|r| *r *= 2);
+    assert_eq!(ops.regs, vec![2, 4]);
+}
@@ -0,0 +1,179 @@
+use parametrized::*;
+use std::collections::HashMap;
+
+/// A container without `Parametrized` impls.
+pub struct Pair<T> {
+    first: T,
+    second: T,
+}
+
+mod pair {
+    use super::Pair;
+    use parametrized::{ParamPath, PathSegment};
+
+    pub fn len<T>(_: &Pair<T>) -> usize {
+        2
+    }
+    pub fn iter<T>(p: &Pair<T>) -> impl Iterator<Item = &T> {
+        [&p.first, &p.second].into_iter()
+    }
+    pub fn iter_mut<T>(p: &mut Pair<T>) -> impl Iterator<Item = &mut T> {
+        [&mut p.first, &mut p.second].into_iter()
+    }
+    pub fn into_iter<T>(p: Pair<T>) -> impl Iterator<Item = T> {
+        [p.first, p.second].into_iter()
+    }
+    pub fn map<T, M>(p: Pair<T>, mut f: impl FnMut(T) -> M) -> Pair<M> {
+        Pair {
+            first: f(p.first),
+            second: f(p.second),
+        }
+    }
+    pub fn try_zip_with<T, U, M, E>(
+        p: Pair<T>,
+        q: Pair<U>,
+        mut f: impl FnMut(T, U) -> Result<M, E>,
+    ) -> Result<Pair<M>, E> {
+        Ok(Pair {
+            first: f(p.first, q.first)?,
+            second: f(p.second, q.second)?,
+        })
+    }
+    pub fn unzip<A, B>(p: Pair<(A, B)>) -> (Pair<A>, Pair<B>) {
+        let ((a0, b0), (a1, b1)) = (p.first, p.second);
+        (
+            Pair {
+                first: a0,
+                second: a1,
+            },
+            Pair {
+                first: b0,
+                second: b1,
+            },
+        )
+    }
+    pub fn map_ref<'a, T, M>(p: &'a Pair<T>, mut f: impl FnMut(&'a T) -> M) -> Pair<M> {
+        Pair {
+            first: f(&p.first),
+            second: f(&p.second),
+        }
+    }
+    pub fn paths<T>(_: &Pair<T>) -> Vec<ParamPath> {
+        ["first", "second"]
+            .into_iter()
+            .map(|name| ParamPath::from(vec![PathSegment::Field(name)]))
+            .collect()
+    }
+    pub fn get_path_prefix<'a, 'p, T>(
+        p: &'a Pair<T>,
+        path: &'p [PathSegment],
+    ) -> Option<(&'a T, &'p [PathSegment])> {
+        match path {
+            [PathSegment::Field("first"), rest @ ..] => Some((&p.first, rest)),
+            [PathSegment::Field("second"), rest @ ..] => Some((&p.second, rest)),
+            _ => None,
+        }
+    }
+}
+
+#[parametrized(default, iter_mut, map, map_ref, zip, unzip)]
+struct Node<T> {
+    value: T,
+    #[param(with = pair)]
+    children: Pair<T>,
+    #[param(skip)]
+    memo: HashMap<&'static str, usize>,
+    #[param(skip)]
+    name: &'static str,
+}
+
+#[test]
+fn test_skip_with() {
+    let mut node = Node {
+        value: 1,
+        children: Pair {
+            first: 2,
+            second: 3,
+        },
+        memo: [("one", 1)].into_iter().collect(),
+        name: "node",
+    };
+    assert_eq!(<Node<i32>>::MIN_LEN, 1);
+    assert_eq!(<Node<i32>>::MAX_LEN, None);
+    assert_eq!(node.param_len(), 3);
+    assert_eq!(node.param_iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    node.param_iter_mut().for_each(|v| *v *= 10);
+    let view = node.param_map_ref(|v| v + 1);
+    assert_eq!(view.param_iter().collect::<Vec<_>>(), vec![&11, &21, &31]);
+    assert_eq!(view.memo["one"], 1);
+    assert_eq!(view.name, "node");
+    let sum = view.param_zip_with(node.param_map_ref(|v| v * 2), |a, b| a + b);
+    assert_eq!(
+        sum.unwrap().param_into_iter().collect::<Vec<_>>(),
+        vec![31, 61, 91]
+    );
+    let (left, right) = node.param_map_ref(|v| (*v, v + 1)).param_unzip();
+    assert_eq!(left.param_iter().collect::<Vec<_>>(), vec![&10, &20, &30]);
+    assert_eq!(right.param_iter().collect::<Vec<_>>(), vec![&11, &21, &31]);
+    assert_eq!(right.name, "node");
+    let node = node.param_map(|v| v.to_string());
+    assert_eq!(node.children.second, "30");
+    assert_eq!(node.memo["one"], 1);
+    assert_eq!(node.name, "node");
+    assert_eq!(
+        node.param_into_iter().collect::<Vec<_>>(),
+        vec!["10", "20", "30"]
+    );
+}
+
+#[parametrized(default, path)]
+struct PathNode<T> {
+    value: T,
+    #[param(with = pair)]
+    children: Pair<T>,
+    #[param(skip)]
+    name: &'static str,
+}
+
+#[test]
+fn test_with_paths() {
+    let node = PathNode {
+        value: 1,
+        children: Pair {
+            first: 2,
+            second: 3,
+        },
+        name: "node",
+    };
+    assert_eq!(
+        node.param_iter_with_path()
+            .map(|(path, v)| format!("{path} = {v}"))
+            .collect::<Vec<_>>(),
+        vec!["value = 1", "children.first = 2", "children.second = 3"]
+    );
+    let path = ParamPath::from(vec![
+        PathSegment::Field("children"),
+        PathSegment::Field("second"),
+    ]);
+    assert_eq!(node.param_get_path(&path), Some(&3));
+    assert_eq!(node.name, "node");
+}
+
+#[parametrized(default, into_iter)]
+enum Token<T> {
+    Value(T),
+    #[param(skip)]
+    Cached(T, T),
+    Tagged(T, #[param(opaque)] Vec<T>),
+}
+
+#[test]
+fn test_opaque_variant() {
+    assert_eq!(<Token<u8>>::MIN_LEN, 0);
+    assert_eq!(<Token<u8>>::MAX_LEN, Some(1));
+    assert_eq!(Token::Value(1).param_len(), 1);
+    assert_eq!(Token::Cached(1, 2).param_len(), 0);
+    let t = Token::Tagged(1, vec![2, 3]);
+    assert_eq!(t.param_iter().collect::<Vec<_>>(), vec![&1]);
+    assert_eq!(t.param_into_iter().collect::<Vec<_>>(), vec![1]);
+}
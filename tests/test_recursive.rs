@@ -0,0 +1,307 @@
+use parametrized::*;
+
+#[parametrized(default, iter_mut, into_iter, try_map, map_ref, zip, unzip)]
+#[derive(Debug, PartialEq)]
+enum Expr<T> {
+    Leaf(T),
+    Neg(Box<Self>),
+    Add(Box<Expr<T>>, Box<Expr<T>>),
+    Call(String, Vec<Expr<T>>),
+}
+
+fn sample() -> Expr<usize> {
+    Expr::Add(
+        Box::new(Expr::Leaf(1)),
+        Box::new(Expr::Call(
+            "f".to_string(),
+            vec![Expr::Neg(Box::new(Expr::Leaf(2))), Expr::Leaf(3)],
+        )),
+    )
+}
+
+#[test]
+fn test_expr() {
+    let mut e = sample();
+    assert_eq!(<Expr<usize>>::MIN_LEN, 0);
+    assert_eq!(<Expr<usize>>::MAX_LEN, None);
+    assert_eq!(e.param_len(), 3);
+    assert_eq!(e.param_iter().collect::<Vec<_>>(), vec![&1, &2, &3]);
+    e.param_iter_mut().for_each(|i| *i *= 10);
+    let e = e.param_map(|i| i.to_string());
+    assert_eq!(
+        e.param_into_iter().collect::<Vec<_>>(),
+        vec!["10".to_string(), "20".to_string(), "30".to_string()]
+    );
+    assert_eq!(
+        sample().param_map(|i| i + 1),
+        Expr::Add(
+            Box::new(Expr::Leaf(2)),
+            Box::new(Expr::Call(
+                "f".to_string(),
+                vec![Expr::Neg(Box::new(Expr::Leaf(3))), Expr::Leaf(4)],
+            )),
+        )
+    );
+    assert_eq!(
+        sample().try_param_map(|i| if i < 3 { Ok(i) } else { Err(i) }),
+        Err(3)
+    );
+    let e = sample();
+    assert_eq!(e.param_map_ref(|i| i + 1), sample().param_map(|i| i + 1));
+    assert_eq!(
+        e.param_as_ref().param_into_iter().collect::<Vec<_>>(),
+        vec![&1, &2, &3]
+    );
+    let z = sample().param_zip_with(sample().param_map(|i| i * 10), |a, b| a + b);
+    assert_eq!(z, Ok(sample().param_map(|i| i * 11)));
+    assert_eq!(
+        sample().param_zip(Expr::Leaf(1)),
+        Err(ShapeMismatch::Variant {
+            expected: "Add",
+            found: "Leaf"
+        })
+    );
+    let (l, r) = sample().param_map(|i| (i, i * 2)).param_unzip();
+    assert_eq!(l, sample());
+    assert_eq!(r, sample().param_map(|i| i * 2));
+}
+
+#[parametrized(default, into_iter, map)]
+struct List<T> {
+    head: T,
+    tail: Option<Box<List<T>>>,
+}
+
+#[parametrized(default, into_iter, map, recursive = Stmt)]
+enum Block<T> {
+    Empty,
+    Stmts(Vec<Stmt<T>>),
+}
+
+#[parametrized(default, into_iter, map)]
+enum Stmt<T> {
+    Assign(T, T),
+    Nested(Box<Block<T>>),
+}
+
+#[test]
+fn test_mutual() {
+    let l = List {
+        head: 1,
+        tail: Some(Box::new(List {
+            head: 2,
+            tail: None,
+        })),
+    };
+    assert_eq!(<List<usize>>::MIN_LEN, 1);
+    assert_eq!(
+        l.param_map(|i| i * 2).param_into_iter().collect::<Vec<_>>(),
+        vec![2, 4]
+    );
+
+    let b = Block::Stmts(vec![
+        Stmt::Assign(1, 2),
+        Stmt::Nested(Box::new(Block::Stmts(vec![Stmt::Assign(3, 4)]))),
+        Stmt::Nested(Box::new(Block::Empty)),
+    ]);
+    assert_eq!(<Stmt<usize>>::MIN_LEN, 0);
+    assert_eq!(b.param_len(), 4);
+    assert_eq!(
+        b.param_map(|i| i + 1).param_into_iter().collect::<Vec<_>>(),
+        vec![2, 3, 4, 5]
+    );
+}
+
+#[parametrized(default, into_iter, map)]
+enum Body<T> {
+    Empty,
+    Instrs(Vec<Instr<T>>),
+}
+
+// Refers back to `Body` through a `Box`, so needs no `recursive = ..`.
+#[parametrized(default, into_iter, map)]
+enum Instr<T> {
+    Assign(T, T),
+    Loop(Box<Body<T>>),
+}
+
+#[test]
+fn test_mutual_boxed() {
+    let b = Body::Instrs(vec![
+        Instr::Assign(1, 2),
+        Instr::Loop(Box::new(Body::Instrs(vec![Instr::Assign(3, 4)]))),
+        Instr::Loop(Box::new(Body::Empty)),
+    ]);
+    assert_eq!(<Instr<usize>>::MIN_LEN, 0);
+    assert_eq!(b.param_len(), 4);
+    assert_eq!(
+        b.param_map(|i| i * 2).param_into_iter().collect::<Vec<_>>(),
+        vec![2, 4, 6, 8]
+    );
+}
+
+mod lowered {
+    use parametrized::*;
+
+    #[parametrized(default)]
+    pub struct Expr<T>(pub T, pub T);
+}
+
+mod typed {
+    use parametrized::*;
+
+    // Not recursive, since the field names another `Expr`.
+    #[parametrized(default)]
+    pub struct Expr<T> {
+        pub ty: T,
+        pub value: super::lowered::Expr<T>,
+    }
+}
+
+#[test]
+fn test_same_name() {
+    let e = typed::Expr {
+        ty: 0,
+        value: lowered::Expr(1, 2),
+    };
+    assert_eq!(<typed::Expr<usize>>::MIN_LEN, 3);
+    assert_eq!(e.param_iter().len(), 3);
+    assert_eq!(e.param_iter().rev().collect::<Vec<_>>(), vec![&2, &1, &0]);
+}
+
+#[parametrized(retain, filter_map, diff)]
+#[derive(Debug, PartialEq)]
+enum Tree<T> {
+    Leaf(Option<T>),
+    Node(Vec<Tree<T>>),
+}
+
+#[test]
+fn test_tree() {
+    let mut t = Tree::Node(vec![
+        Tree::Leaf(Some(1)),
+        Tree::Node(vec![Tree::Leaf(Some(2)), Tree::Leaf(Some(3))]),
+    ]);
+    assert_eq!(t.param_get(2), Some(&3));
+    assert_eq!(t.param_get(3), None);
+    let u = Tree::Node(vec![
+        Tree::Leaf(None),
+        Tree::Node(vec![Tree::Leaf(Some(2)), Tree::Leaf(Some(4))]),
+    ]);
+    assert_eq!(
+        format!("{:?}", t.param_diff(&u)),
+        "[Node.0[0].Leaf.0: expected variant `Some`, found `None`, \
+         Node.0[1].Node.0[1].Leaf.0: 3 != 4]"
+    );
+    assert_eq!(
+        Tree::Leaf(Some(1)).param_diff(&Tree::Node(vec![])),
+        vec![ParamDiff::Shape {
+            path: ParamPath::new(),
+            mismatch: ShapeMismatch::Variant {
+                expected: "Leaf",
+                found: "Node"
+            }
+        }]
+    );
+    t.param_retain(|i| *i != 2);
+    assert_eq!(
+        t,
+        Tree::Node(vec![
+            Tree::Leaf(Some(1)),
+            Tree::Node(vec![Tree::Leaf(None), Tree::Leaf(Some(3))]),
+        ])
+    );
+    assert_eq!(
+        t.param_filter_map(|i| (i > 1).then_some(i * 10)),
+        Tree::Node(vec![
+            Tree::Leaf(None),
+            Tree::Node(vec![Tree::Leaf(None), Tree::Leaf(Some(30))]),
+        ])
+    );
+}
+
+#[parametrized(default, path)]
+enum PathTree<T> {
+    Leaf(Option<T>),
+    Node(Vec<PathTree<T>>),
+    Named(std::collections::BTreeMap<&'static str, PathTree<T>>),
+}
+
+#[test]
+fn test_tree_paths() {
+    let t = PathTree::Node(vec![
+        PathTree::Leaf(Some(1)),
+        PathTree::Named(
+            [
+                ("a", PathTree::Leaf(Some(2))),
+                ("b", PathTree::Leaf(Some(3))),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+    ]);
+    let (path, _) = t.param_iter_with_path().find(|(_, i)| **i == 3).unwrap();
+    assert_eq!(path.to_string(), "Node.0[1].Named.0[1].Leaf.0");
+    assert_eq!(t.param_get_path(&path), Some(&3));
+    assert_eq!(
+        t.param_paths()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec![
+            "Node.0[0].Leaf.0",
+            "Node.0[1].Named.0[0].Leaf.0",
+            "Node.0[1].Named.0[1].Leaf.0"
+        ]
+    );
+}
+
+#[parametrized(update)]
+#[derive(Debug, PartialEq)]
+enum Chain<T> {
+    End(T),
+    Link(T, Box<Self>),
+}
+
+#[test]
+fn test_chain() {
+    let mut c = Chain::Link(1, Box::new(Chain::Link(2, Box::new(Chain::End(3)))));
+    c.param_update(|i| i + 1);
+    assert_eq!(
+        c,
+        Chain::Link(2, Box::new(Chain::Link(3, Box::new(Chain::End(4)))))
+    );
+}
+
+#[parametrized(bimap = [0, 1])]
+#[derive(Debug, PartialEq)]
+struct Scope<V, L> {
+    vars: Vec<V>,
+    label: Option<L>,
+    children: Vec<Scope<V, L>>,
+}
+
+#[test]
+fn test_scope() {
+    let s = Scope {
+        vars: vec![1, 2],
+        label: Some('a'),
+        children: vec![Scope {
+            vars: vec![3],
+            label: None,
+            children: vec![],
+        }],
+    };
+    assert_eq!(
+        s.param_bimap(|v| v * 10, |l| l.to_string()),
+        Scope {
+            vars: vec![10, 20],
+            label: Some("a".to_string()),
+            children: vec![Scope {
+                vars: vec![30],
+                label: None,
+                children: vec![],
+            }],
+        }
+    );
+}